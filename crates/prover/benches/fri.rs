@@ -1,11 +1,25 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use num_traits::Zero;
+use stwo_prover::core::backend::cpu::CpuCirclePoly;
+use stwo_prover::core::backend::simd::SimdBackend;
 use stwo_prover::core::backend::CpuBackend;
+use stwo_prover::core::channel::{Blake2sChannel, Channel};
 use stwo_prover::core::fields::m31::BaseField;
 use stwo_prover::core::fields::qm31::SecureField;
 use stwo_prover::core::fields::secure_column::SecureColumn;
-use stwo_prover::core::fri::FriOps;
-use stwo_prover::core::poly::circle::{CanonicCoset, PolyOps};
+use stwo_prover::core::fields::IntoSlice;
+use stwo_prover::core::fri::{
+    fold_circle_into_line_values, fold_line_values, FriConfig, FriOps, FriVerifierTwiddles,
+};
+use stwo_prover::core::low_degree_test::{
+    prove_low_degree, verify_low_degree, verify_low_degree_with_twiddles, LowDegreeProof,
+};
+use stwo_prover::core::poly::circle::{CanonicCoset, PolyOps, SecureEvaluation};
 use stwo_prover::core::poly::line::{LineDomain, LineEvaluation};
+use stwo_prover::core::serialization::Serializable;
+use stwo_prover::core::vcs::blake2_hash::Blake2sHasher;
+use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+use stwo_prover::core::vcs::hasher::Hasher;
 
 fn folding_benchmark(c: &mut Criterion) {
     const LOG_SIZE: u32 = 12;
@@ -29,7 +43,122 @@ fn folding_benchmark(c: &mut Criterion) {
             ));
         })
     });
+
+    let values = evals.values.to_vec();
+    c.bench_function("fold_line_values", |b| {
+        b.iter(|| {
+            black_box(fold_line_values(
+                black_box(&values),
+                domain,
+                black_box(alpha),
+            ))
+        })
+    });
+
+    let circle_domain = CanonicCoset::new(LOG_SIZE + 1).circle_domain();
+    let circle_values = [values.clone(), values].concat();
+    c.bench_function("fold_circle_into_line_values", |b| {
+        b.iter_batched(
+            || vec![SecureField::zero(); 1 << LOG_SIZE],
+            |mut dst| {
+                fold_circle_into_line_values(
+                    &mut dst,
+                    black_box(&circle_values),
+                    circle_domain,
+                    black_box(alpha),
+                );
+                dst
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+/// Benchmarks the verifier side of FRI, which folds all queries of a layer together, either with
+/// the twiddles of the queried subdomains or with precomputed twiddles.
+fn verification_benchmark(c: &mut Criterion) {
+    const LOG_DEGREES: [u32; 3] = [14, 12, 10];
+    const LOG_BLOWUP_FACTOR: u32 = 1;
+    const N_QUERIES: usize = 64;
+    let columns = LOG_DEGREES.map(|log_degree| {
+        let domain = CanonicCoset::new(log_degree + LOG_BLOWUP_FACTOR).circle_domain();
+        let columns = std::array::from_fn(|i| {
+            let coeffs = (0..1 << log_degree)
+                .map(|j| BaseField::from_u32_unchecked((i << 20 | j) as u32))
+                .collect();
+            CpuCirclePoly::new(coeffs).evaluate(domain).values
+        });
+        SecureEvaluation {
+            domain,
+            values: SecureColumn { columns },
+        }
+    });
+    let twiddles = CpuBackend::precompute_twiddles(columns[0].domain.half_coset);
+    let verifier_twiddles = FriVerifierTwiddles::new(columns[0].domain.log_size());
+
+    let mut group = c.benchmark_group("fri_verification");
+    for fold_step in FriConfig::FOLD_STEP_RANGE {
+        let config = FriConfig::new(0, LOG_BLOWUP_FACTOR, N_QUERIES).with_fold_step(fold_step);
+        let proof = prove_low_degree::<CpuBackend, Blake2sMerkleHasher>(
+            &mut test_channel(),
+            config,
+            &columns,
+            &twiddles,
+        );
+        let mut proof_bytes = Vec::new();
+        proof.write_bytes(&mut proof_bytes);
+        let read_proof =
+            || LowDegreeProof::<Blake2sMerkleHasher>::read_bytes(&mut &proof_bytes[..]).unwrap();
+        group.bench_function(BenchmarkId::new("fold_step", fold_step), |b| {
+            b.iter_batched(
+                read_proof,
+                |proof| {
+                    verify_low_degree(&mut test_channel(), config, &LOG_DEGREES, proof).unwrap()
+                },
+                BatchSize::SmallInput,
+            )
+        });
+        group.bench_function(BenchmarkId::new("cpu_twiddles_fold_step", fold_step), |b| {
+            b.iter_batched(
+                read_proof,
+                |proof| {
+                    verify_low_degree_with_twiddles::<CpuBackend, _>(
+                        &mut test_channel(),
+                        config,
+                        &LOG_DEGREES,
+                        proof,
+                        &verifier_twiddles,
+                    )
+                    .unwrap()
+                },
+                BatchSize::SmallInput,
+            )
+        });
+        group.bench_function(
+            BenchmarkId::new("simd_twiddles_fold_step", fold_step),
+            |b| {
+                b.iter_batched(
+                    read_proof,
+                    |proof| {
+                        verify_low_degree_with_twiddles::<SimdBackend, _>(
+                            &mut test_channel(),
+                            config,
+                            &LOG_DEGREES,
+                            proof,
+                            &verifier_twiddles,
+                        )
+                        .unwrap()
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+}
+
+fn test_channel() -> Blake2sChannel {
+    Blake2sChannel::new(Blake2sHasher::hash(BaseField::into_slice(&[])))
 }
 
-criterion_group!(benches, folding_benchmark);
+criterion_group!(benches, folding_benchmark, verification_benchmark);
 criterion_main!(benches);
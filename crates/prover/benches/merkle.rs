@@ -7,13 +7,15 @@ use stwo_prover::core::backend::simd::SimdBackend;
 use stwo_prover::core::backend::{Col, CpuBackend};
 use stwo_prover::core::fields::m31::{BaseField, N_BYTES_FELT};
 use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleHasher;
-use stwo_prover::core::vcs::ops::MerkleOps;
+use stwo_prover::core::vcs::blake3_merkle::Blake3MerkleHasher;
+use stwo_prover::core::vcs::ops::{MerkleHasher, MerkleOps};
+use stwo_prover::core::vcs::poseidon2_m31_merkle::Poseidon2M31MerkleHasher;
 
 const LOG_N_ROWS: u32 = 16;
 
 const LOG_N_COLS: u32 = 8;
 
-fn bench_blake2s_merkle<B: MerkleOps<Blake2sMerkleHasher>>(c: &mut Criterion, id: &str) {
+fn bench_merkle<H: MerkleHasher, B: MerkleOps<H>>(c: &mut Criterion, id: &str) {
     let col: Col<B, BaseField> = (0..1 << LOG_N_ROWS).map(|_| BaseField::zero()).collect();
     let cols = (0..1 << LOG_N_COLS).map(|_| col.clone()).collect_vec();
     let col_refs = cols.iter().collect_vec();
@@ -27,12 +29,22 @@ fn bench_blake2s_merkle<B: MerkleOps<Blake2sMerkleHasher>>(c: &mut Criterion, id
 }
 
 fn blake2s_merkle_benches(c: &mut Criterion) {
-    bench_blake2s_merkle::<SimdBackend>(c, "simd");
-    bench_blake2s_merkle::<CpuBackend>(c, "cpu");
+    bench_merkle::<Blake2sMerkleHasher, SimdBackend>(c, "simd blake2s");
+    bench_merkle::<Blake2sMerkleHasher, CpuBackend>(c, "cpu blake2s");
+}
+
+fn blake3_merkle_benches(c: &mut Criterion) {
+    bench_merkle::<Blake3MerkleHasher, SimdBackend>(c, "simd blake3");
+    bench_merkle::<Blake3MerkleHasher, CpuBackend>(c, "cpu blake3");
+}
+
+fn poseidon2_m31_merkle_benches(c: &mut Criterion) {
+    bench_merkle::<Poseidon2M31MerkleHasher, SimdBackend>(c, "simd poseidon2_m31");
+    bench_merkle::<Poseidon2M31MerkleHasher, CpuBackend>(c, "cpu poseidon2_m31");
 }
 
 criterion_group!(
     name = benches;
     config = Criterion::default().sample_size(10);
-    targets = blake2s_merkle_benches);
+    targets = blake2s_merkle_benches, blake3_merkle_benches, poseidon2_m31_merkle_benches);
 criterion_main!(benches);
@@ -3,7 +3,9 @@ use stwo_prover::core::backend::simd::SimdBackend;
 use stwo_prover::core::channel::{Blake2sChannel, Channel};
 use stwo_prover::core::fields::m31::BaseField;
 use stwo_prover::core::fields::IntoSlice;
+use stwo_prover::core::pcs::PcsConfig;
 use stwo_prover::core::vcs::blake2_hash::Blake2sHasher;
+use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleChannel;
 use stwo_prover::core::vcs::hasher::Hasher;
 use stwo_prover::examples::poseidon::{gen_trace, PoseidonAir, PoseidonComponent};
 use stwo_prover::trace_generation::commit_and_prove;
@@ -20,9 +22,31 @@ pub fn simd_poseidon(c: &mut Criterion) {
             let trace = gen_trace(component.log_column_size());
             let channel = &mut Blake2sChannel::new(Blake2sHasher::hash(BaseField::into_slice(&[])));
             let air = PoseidonAir { component };
-            commit_and_prove::<SimdBackend>(&air, channel, trace).unwrap()
+            commit_and_prove::<SimdBackend, Blake2sMerkleChannel>(
+                &air,
+                channel,
+                trace,
+                PcsConfig::default(),
+            )
+            .unwrap()
         });
     });
+
+    // Report the proof size alongside the timings, for regression tracking.
+    let component = PoseidonComponent {
+        log_n_rows: LOG_N_ROWS,
+    };
+    let trace = gen_trace(component.log_column_size());
+    let channel = &mut Blake2sChannel::new(Blake2sHasher::hash(BaseField::into_slice(&[])));
+    let air = PoseidonAir { component };
+    let proof = commit_and_prove::<SimdBackend, Blake2sMerkleChannel>(
+        &air,
+        channel,
+        trace,
+        PcsConfig::default(),
+    )
+    .unwrap();
+    println!("{}", proof.size_breakdown());
 }
 
 criterion_group!(
@@ -0,0 +1,26 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use stwo_prover::core::backend::simd::SimdBackend;
+use stwo_prover::core::backend::CpuBackend;
+use stwo_prover::core::channel::{Blake2sChannel, Channel};
+use stwo_prover::core::proof_of_work::GrindOps;
+use stwo_prover::core::vcs::blake2_hash::Blake2sHash;
+
+const POW_BITS: u32 = 16;
+
+fn bench_grind<B: GrindOps<Blake2sChannel>>(c: &mut Criterion, id: &str) {
+    let channel = Blake2sChannel::new(Blake2sHash::default());
+    c.bench_function(&format!("{id} grind {POW_BITS} bits"), |b| {
+        b.iter(|| B::grind(&channel, POW_BITS))
+    });
+}
+
+fn proof_of_work_benches(c: &mut Criterion) {
+    bench_grind::<SimdBackend>(c, "simd");
+    bench_grind::<CpuBackend>(c, "cpu");
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = proof_of_work_benches);
+criterion_main!(benches);
@@ -17,6 +17,7 @@ use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
 use crate::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
 use crate::core::fields::FieldExpOps;
+use crate::trace_generation::BASE_TRACE;
 
 /// A trait for evaluating expressions at some point or row.
 pub trait EvalAtRow {
@@ -50,13 +51,15 @@ pub trait EvalAtRow {
         + Sub<Self::EF, Output = Self::EF>
         + Mul<Self::EF, Output = Self::EF>;
 
-    /// Returns the next mask value for the first interaction at offset 0.
+    /// Returns the next mask value for the base trace at offset 0.
     fn next_trace_mask(&mut self) -> Self::F {
-        let [mask_item] = self.next_interaction_mask(0, [0]);
+        let [mask_item] = self.next_interaction_mask(BASE_TRACE, [0]);
         mask_item
     }
 
     /// Returns the mask values of the given offsets for the next column in the interaction.
+    /// Preprocessed columns are read from interaction
+    /// [PREPROCESSED_TRACE](crate::trace_generation::PREPROCESSED_TRACE).
     fn next_interaction_mask<const N: usize>(
         &mut self,
         interaction: usize,
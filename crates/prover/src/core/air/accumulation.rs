@@ -4,6 +4,7 @@
 //!   f(p) = sum_i alpha^{N-1-i} u_i(P).
 
 use itertools::Itertools;
+pub use stwo_verifier::air::accumulation::PointEvaluationAccumulator;
 use tracing::{span, Level};
 
 use crate::core::backend::{Backend, Col, Column, CpuBackend};
@@ -15,34 +16,6 @@ use crate::core::poly::circle::{CanonicCoset, CircleEvaluation, CirclePoly, Secu
 use crate::core::poly::BitReversedOrder;
 use crate::core::utils::generate_secure_powers;
 
-/// Accumulates N evaluations of u_i(P0) at a single point.
-/// Computes f(P0), the combined polynomial at that point.
-/// For n accumulated evaluations, the i'th evaluation is multiplied by alpha^(N-1-i).
-pub struct PointEvaluationAccumulator {
-    random_coeff: SecureField,
-    accumulation: SecureField,
-}
-
-impl PointEvaluationAccumulator {
-    /// Creates a new accumulator.
-    /// `random_coeff` should be a secure random field element, drawn from the channel.
-    pub fn new(random_coeff: SecureField) -> Self {
-        Self {
-            random_coeff,
-            accumulation: SecureField::default(),
-        }
-    }
-
-    /// Accumulates u_i(P0), a polynomial evaluation at a P0 in reverse order.
-    pub fn accumulate(&mut self, evaluation: SecureField) {
-        self.accumulation = self.accumulation * self.random_coeff + evaluation;
-    }
-
-    pub fn finalize(self) -> SecureField {
-        self.accumulation
-    }
-}
-
 // TODO(ShaharS), rename terminology to constraints instead of columns.
 /// Accumulates evaluations of u_i(P), each at an evaluation domain of the size of that polynomial.
 /// Computes the coefficients of f(P).
@@ -1,66 +1,21 @@
-use self::accumulation::{DomainEvaluationAccumulator, PointEvaluationAccumulator};
+use self::accumulation::DomainEvaluationAccumulator;
 use super::backend::Backend;
-use super::circle::CirclePoint;
 use super::fields::m31::BaseField;
-use super::fields::qm31::SecureField;
 use super::pcs::TreeVec;
 use super::poly::circle::{CircleEvaluation, CirclePoly};
 use super::poly::BitReversedOrder;
-use super::prover::VerificationError;
 use super::{ColumnVec, InteractionElements, LookupValues};
 
 pub mod accumulation;
 mod air_ext;
-pub mod mask;
 
-pub use air_ext::{AirExt, AirProverExt};
-
-/// Arithmetic Intermediate Representation (AIR).
-/// An Air instance is assumed to already contain all the information needed to
-/// evaluate the constraints.
-/// For instance, all interaction elements are assumed to be present in it.
-/// Therefore, an AIR is generated only after the initial trace commitment phase.
-// TODO(spapini): consider renaming this struct.
-pub trait Air {
-    fn components(&self) -> Vec<&dyn Component>;
-
-    /// Verifies the lookups done in the Air.
-    fn verify_lookups(&self, lookup_values: &LookupValues) -> Result<(), VerificationError>;
-}
+pub use air_ext::AirProverExt;
+pub use stwo_verifier::air::{mask, Air, AirExt, Component, PublicInput};
 
 pub trait AirProver<B: Backend>: Air {
     fn prover_components(&self) -> Vec<&dyn ComponentProver<B>>;
 }
 
-/// A component is a set of trace columns of various sizes along with a set of
-/// constraints on them.
-pub trait Component {
-    fn n_constraints(&self) -> usize;
-
-    fn max_constraint_log_degree_bound(&self) -> u32;
-
-    /// Returns the degree bounds of each trace column. The returned TreeVec should be of size
-    /// `n_interaction_phases`.
-    fn trace_log_degree_bounds(&self) -> TreeVec<ColumnVec<u32>>;
-
-    /// Returns the mask points for each trace column. The returned TreeVec should be of size
-    /// `n_interaction_phases`.
-    fn mask_points(
-        &self,
-        point: CirclePoint<SecureField>,
-    ) -> TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>>;
-
-    /// Evaluates the constraint quotients combination of the component at a point.
-    fn evaluate_constraint_quotients_at_point(
-        &self,
-        point: CirclePoint<SecureField>,
-        mask: &TreeVec<ColumnVec<Vec<SecureField>>>,
-        evaluation_accumulator: &mut PointEvaluationAccumulator,
-        interaction_elements: &InteractionElements,
-        lookup_values: &LookupValues,
-    );
-}
-
 pub trait ComponentProver<B: Backend>: Component {
     /// Evaluates the constraint quotients of the component on the evaluation domain.
     /// Accumulates quotients in `evaluation_accumulator`.
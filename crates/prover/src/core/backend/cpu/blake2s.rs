@@ -15,7 +15,7 @@ impl MerkleOps<Blake2sMerkleHasher> for CpuBackend {
         (0..(1 << log_size))
             .map(|i| {
                 Blake2sMerkleHasher::hash_node(
-                    prev_layer.map(|prev_layer| (prev_layer[2 * i], prev_layer[2 * i + 1])),
+                    prev_layer.map_or(&[][..], |prev_layer| &prev_layer[2 * i..2 * i + 2]),
                     &columns.iter().map(|column| column[i]).collect_vec(),
                 )
             })
@@ -2,7 +2,9 @@ use super::CpuBackend;
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
 use crate::core::fields::secure_column::SecureColumn;
-use crate::core::fri::{fold_circle_into_line, fold_line, FriOps};
+use crate::core::fri::{
+    decomposition_values, fold_circle_into_line, fold_line, FriOps, FriVerifierOps,
+};
 use crate::core::poly::circle::SecureEvaluation;
 use crate::core::poly::line::LineEvaluation;
 use crate::core::poly::twiddles::TwiddleTree;
@@ -25,22 +27,21 @@ impl FriOps for CpuBackend {
         fold_circle_into_line(dst, src, alpha)
     }
 
-    fn decompose(eval: &SecureEvaluation<Self>) -> (SecureEvaluation<Self>, SecureField) {
-        let lambda = Self::decomposition_coefficient(eval);
+    fn decompose(
+        eval: &SecureEvaluation<Self>,
+        log_blowup_factor: u32,
+    ) -> (SecureEvaluation<Self>, SecureField) {
+        assert!(eval.domain.is_canonic(), "not canonic");
+        let decomposition_values = decomposition_values(eval.domain.log_size(), log_blowup_factor);
+        let lambda = Self::decomposition_coefficient(eval, &decomposition_values);
         let mut g_values = SecureColumn::<Self>::zeros(eval.len());
 
-        let domain_size = eval.len();
-        let half_domain_size = domain_size / 2;
-
-        for i in 0..half_domain_size {
-            let x = eval.values.at(i);
-            let val = x - lambda;
-            g_values.set(i, val);
-        }
-        for i in half_domain_size..domain_size {
-            let x = eval.values.at(i);
-            let val = x + lambda;
-            g_values.set(i, val);
+        let block_size = eval.len() / decomposition_values.len();
+        for (i, &v) in decomposition_values.iter().enumerate() {
+            let lambda_v = lambda * v;
+            for j in i * block_size..(i + 1) * block_size {
+                g_values.set(j, eval.values.at(j) - lambda_v);
+            }
         }
 
         let g = SecureEvaluation {
@@ -51,37 +52,42 @@ impl FriOps for CpuBackend {
     }
 }
 
+impl FriVerifierOps for CpuBackend {}
+
 impl CpuBackend {
     /// Used to decompose a general polynomial to a polynomial inside the fft-space, and
     /// the remainder terms.
     /// A coset-diff on a [`CirclePoly`] that is in the FFT space will return zero.
     ///
-    /// Let N be the domain size, Let h be a coset size N/2. Using lemma #7 from the CircleStark
-    /// paper, <f,V_h> = lambda<V_h,V_h> = lambda\*N => lambda = f(0)\*V_h(0) + f(1)*V_h(1) + .. +
-    /// f(N-1)\*V_h(N-1). The Vanishing polynomial of a cannonic coset sized half the circle
-    /// domain,evaluated on the circle domain, is [(1, -1, -1, 1)] repeating. This becomes
-    /// alternating [+-1] in our NaturalOrder, and [(+, +, +, ... , -, -)] in bit reverse.
-    /// Explicitly, lambda\*N = sum(+f(0..N/2)) + sum(-f(N/2..)).
-    ///
-    /// # Warning
-    /// This function assumes the blowupfactor is 2
+    /// Let N be the domain size, let h be the canonic coset whose size is the degree bound and let
+    /// V_h be its vanishing polynomial. Using lemma #7 from the CircleStark paper,
+    /// <f,V_h> = lambda<V_h,V_h> = lambda\*N/2 => lambda = 2/N \* (f(0)\*V_h(0) + f(1)\*V_h(1)
+    /// + .. + f(N-1)\*V_h(N-1)). In bit reverse order, V_h is constant on consecutive blocks, with
+    /// the values given by `decomposition_values`. For a blowup factor of 2 these are
+    /// [(+c, -c)], i.e. lambda\*N/2 = c\*(sum(f(0..N/2)) - sum(f(N/2..))).
     ///
     /// [`CirclePoly`]: crate::core::poly::circle::CirclePoly
-    fn decomposition_coefficient(eval: &SecureEvaluation<Self>) -> SecureField {
-        let domain_size = 1 << eval.domain.log_size();
-        let half_domain_size = domain_size / 2;
-
-        // eval is in bit-reverse, hence all the positive factors are in the first half, opposite to
-        // the latter.
-        let a_sum = (0..half_domain_size)
-            .map(|i| eval.values.at(i))
-            .sum::<SecureField>();
-        let b_sum = (half_domain_size..domain_size)
-            .map(|i| eval.values.at(i))
+    fn decomposition_coefficient(
+        eval: &SecureEvaluation<Self>,
+        decomposition_values: &[BaseField],
+    ) -> SecureField {
+        let domain_size = eval.len();
+        let block_size = domain_size / decomposition_values.len();
+
+        // eval is in bit-reverse, hence each value of V_h multiplies a contiguous block.
+        let sum = decomposition_values
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| {
+                (i * block_size..(i + 1) * block_size)
+                    .map(|j| eval.values.at(j))
+                    .sum::<SecureField>()
+                    * v
+            })
             .sum::<SecureField>();
 
-        // lambda = sum(+-f(p)) / 2N.
-        (a_sum - b_sum) / BaseField::from_u32_unchecked(domain_size as u32)
+        // lambda = sum(f(p) * V_h(p)) / (N/2).
+        sum / BaseField::from_u32_unchecked((domain_size / 2) as u32)
     }
 }
 
@@ -100,43 +106,46 @@ mod tests {
 
     #[test]
     fn decompose_coeff_out_fft_space_test() {
-        for domain_log_size in 5..12 {
-            let domain_log_half_size = domain_log_size - 1;
-            let s = CanonicCoset::new(domain_log_size);
-            let domain = s.circle_domain();
-
-            let mut coeffs = vec![BaseField::zero(); 1 << domain_log_size];
-
-            // Polynomial is out of FFT space.
-            coeffs[1 << domain_log_half_size] = m31!(1);
-            assert!(!CpuCirclePoly::new(coeffs.clone()).is_in_fft_space(domain_log_half_size));
-
-            let poly = CpuCirclePoly::new(coeffs);
-            let values = poly.evaluate(domain);
-            let secure_column = SecureColumn {
-                columns: [
-                    values.values.clone(),
-                    values.values.clone(),
-                    values.values.clone(),
-                    values.values.clone(),
-                ],
-            };
-            let secure_eval = SecureEvaluation::<CpuBackend> {
-                domain,
-                values: secure_column.clone(),
-            };
-
-            let (g, lambda) = CpuBackend::decompose(&secure_eval);
-
-            // Sanity check.
-            assert_ne!(lambda, SecureField::zero());
-
-            // Assert the new polynomial is in the FFT space.
-            for i in 0..4 {
-                let basefield_column = g.columns[i].clone();
-                let eval = CpuCircleEvaluation::new(domain, basefield_column);
-                let coeffs = eval.interpolate().coeffs;
-                assert!(CpuCirclePoly::new(coeffs).is_in_fft_space(domain_log_half_size));
+        for log_blowup_factor in 1..=4 {
+            for log_degree_bound in 4..10 {
+                let domain_log_size = log_degree_bound + log_blowup_factor;
+                let s = CanonicCoset::new(domain_log_size);
+                let domain = s.circle_domain();
+
+                let mut coeffs = vec![BaseField::zero(); 1 << domain_log_size];
+
+                // Polynomial is out of FFT space.
+                coeffs[1 << log_degree_bound] = m31!(1);
+                coeffs[(1 << log_degree_bound) - 1] = m31!(2);
+                assert!(!CpuCirclePoly::new(coeffs.clone()).is_in_fft_space(log_degree_bound));
+
+                let poly = CpuCirclePoly::new(coeffs);
+                let values = poly.evaluate(domain);
+                let secure_column = SecureColumn {
+                    columns: [
+                        values.values.clone(),
+                        values.values.clone(),
+                        values.values.clone(),
+                        values.values.clone(),
+                    ],
+                };
+                let secure_eval = SecureEvaluation::<CpuBackend> {
+                    domain,
+                    values: secure_column.clone(),
+                };
+
+                let (g, lambda) = CpuBackend::decompose(&secure_eval, log_blowup_factor);
+
+                // Sanity check.
+                assert_ne!(lambda, SecureField::zero());
+
+                // Assert the new polynomial is in the FFT space.
+                for i in 0..4 {
+                    let basefield_column = g.columns[i].clone();
+                    let eval = CpuCircleEvaluation::new(domain, basefield_column);
+                    let coeffs = eval.interpolate().coeffs;
+                    assert!(CpuCirclePoly::new(coeffs).is_in_fft_space(log_degree_bound));
+                }
             }
         }
     }
@@ -0,0 +1,11 @@
+use super::CpuBackend;
+use crate::core::channel::Channel;
+use crate::core::proof_of_work::{grind_tasks, task_nonces, GrindOps};
+
+impl<C: Channel + Sync> GrindOps<C> for CpuBackend {
+    fn grind(channel: &C, pow_bits: u32) -> u64 {
+        grind_tasks(|task| {
+            task_nonces(task).find(|&nonce| channel.verify_pow_nonce(pow_bits, nonce))
+        })
+    }
+}
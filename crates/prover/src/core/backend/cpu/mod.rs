@@ -1,11 +1,15 @@
 mod accumulation;
 mod blake2s;
+mod blake3;
 mod circle;
 mod fri;
+mod grind;
 mod lookups;
+mod poseidon2_m31;
 pub mod quotients;
 
 use std::fmt::Debug;
+use std::ops::Range;
 
 use super::{Backend, Column, ColumnOps, FieldOps};
 use crate::core::fields::Field;
@@ -47,6 +51,9 @@ impl<T: Debug + Clone + Default> Column<T> for Vec<T> {
     fn at(&self, index: usize) -> T {
         self[index].clone()
     }
+    fn copy_range(&self, range: Range<usize>) -> Self {
+        self[range].to_vec()
+    }
 }
 
 pub type CpuCirclePoly = CirclePoly<CpuBackend>;
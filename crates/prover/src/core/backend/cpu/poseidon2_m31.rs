@@ -0,0 +1,24 @@
+use itertools::Itertools;
+
+use crate::core::backend::CpuBackend;
+use crate::core::fields::m31::BaseField;
+use crate::core::vcs::ops::{MerkleHasher, MerkleOps};
+use crate::core::vcs::poseidon2_m31::Poseidon2M31Hash;
+use crate::core::vcs::poseidon2_m31_merkle::Poseidon2M31MerkleHasher;
+
+impl MerkleOps<Poseidon2M31MerkleHasher> for CpuBackend {
+    fn commit_on_layer(
+        log_size: u32,
+        prev_layer: Option<&Vec<Poseidon2M31Hash>>,
+        columns: &[&Vec<BaseField>],
+    ) -> Vec<Poseidon2M31Hash> {
+        (0..(1 << log_size))
+            .map(|i| {
+                Poseidon2M31MerkleHasher::hash_node(
+                    prev_layer.map_or(&[][..], |prev_layer| &prev_layer[2 * i..2 * i + 2]),
+                    &columns.iter().map(|column| column[i]).collect_vec(),
+                )
+            })
+            .collect()
+    }
+}
@@ -1,14 +1,18 @@
 use std::fmt::Debug;
+use std::ops::Range;
 
 pub use cpu::CpuBackend;
 
 use super::air::accumulation::AccumulationOps;
+use super::channel::MerkleChannel;
 use super::fields::m31::BaseField;
 use super::fields::qm31::SecureField;
 use super::fields::FieldOps;
 use super::fri::FriOps;
 use super::pcs::quotients::QuotientOps;
 use super::poly::circle::PolyOps;
+use super::proof_of_work::GrindOps;
+use super::vcs::ops::MerkleOps;
 
 pub mod cpu;
 pub mod simd;
@@ -26,6 +30,17 @@ pub trait Backend:
 {
 }
 
+/// A [Backend] that can commit to and grind proofs of work for a [MerkleChannel].
+pub trait BackendForChannel<MC: MerkleChannel>:
+    Backend + MerkleOps<MC::H> + GrindOps<MC::C>
+{
+}
+
+impl<B, MC: MerkleChannel> BackendForChannel<MC> for B where
+    B: Backend + MerkleOps<MC::H> + GrindOps<MC::C>
+{
+}
+
 pub trait ColumnOps<T> {
     type Column: Column<T>;
     fn bit_reverse_column(column: &mut Self::Column);
@@ -47,4 +62,8 @@ pub trait Column<T>: Clone + Debug + FromIterator<T> {
     }
     /// Retrieves the element at the given index.
     fn at(&self, index: usize) -> T;
+    /// Copies the elements in the given range into a new column.
+    fn copy_range(&self, range: Range<usize>) -> Self {
+        range.map(|index| self.at(index)).collect()
+    }
 }
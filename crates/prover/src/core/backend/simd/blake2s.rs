@@ -60,7 +60,7 @@ impl MerkleOps<Blake2sMerkleHasher> for SimdBackend {
             return iter
                 .map(|i| {
                     Blake2sMerkleHasher::hash_node(
-                        prev_layer.map(|prev_layer| (prev_layer[2 * i], prev_layer[2 * i + 1])),
+                        prev_layer.map_or(&[][..], |prev_layer| &prev_layer[2 * i..2 * i + 2]),
                         &columns.iter().map(|column| column.at(i)).collect_vec(),
                     )
                 })
@@ -130,11 +130,11 @@ fn rotate<const N: u32>(x: u32x16) -> u32x16 {
 
 // `inline(always)` can cause code parsing errors for wasm: "locals exceed maximum".
 #[cfg_attr(not(target_arch = "wasm32"), inline(always))]
-fn round(v: &mut [u32x16; 16], m: [u32x16; 16], r: usize) {
-    v[0] += m[SIGMA[r][0] as usize];
-    v[1] += m[SIGMA[r][2] as usize];
-    v[2] += m[SIGMA[r][4] as usize];
-    v[3] += m[SIGMA[r][6] as usize];
+pub(super) fn round(v: &mut [u32x16; 16], m: [u32x16; 16], sigma: &[u8; 16]) {
+    v[0] += m[sigma[0] as usize];
+    v[1] += m[sigma[2] as usize];
+    v[2] += m[sigma[4] as usize];
+    v[3] += m[sigma[6] as usize];
     v[0] += v[4];
     v[1] += v[5];
     v[2] += v[6];
@@ -159,10 +159,10 @@ fn round(v: &mut [u32x16; 16], m: [u32x16; 16], r: usize) {
     v[5] = rotate::<12>(v[5]);
     v[6] = rotate::<12>(v[6]);
     v[7] = rotate::<12>(v[7]);
-    v[0] += m[SIGMA[r][1] as usize];
-    v[1] += m[SIGMA[r][3] as usize];
-    v[2] += m[SIGMA[r][5] as usize];
-    v[3] += m[SIGMA[r][7] as usize];
+    v[0] += m[sigma[1] as usize];
+    v[1] += m[sigma[3] as usize];
+    v[2] += m[sigma[5] as usize];
+    v[3] += m[sigma[7] as usize];
     v[0] += v[4];
     v[1] += v[5];
     v[2] += v[6];
@@ -188,10 +188,10 @@ fn round(v: &mut [u32x16; 16], m: [u32x16; 16], r: usize) {
     v[6] = rotate::<7>(v[6]);
     v[7] = rotate::<7>(v[7]);
 
-    v[0] += m[SIGMA[r][8] as usize];
-    v[1] += m[SIGMA[r][10] as usize];
-    v[2] += m[SIGMA[r][12] as usize];
-    v[3] += m[SIGMA[r][14] as usize];
+    v[0] += m[sigma[8] as usize];
+    v[1] += m[sigma[10] as usize];
+    v[2] += m[sigma[12] as usize];
+    v[3] += m[sigma[14] as usize];
     v[0] += v[5];
     v[1] += v[6];
     v[2] += v[7];
@@ -216,10 +216,10 @@ fn round(v: &mut [u32x16; 16], m: [u32x16; 16], r: usize) {
     v[6] = rotate::<12>(v[6]);
     v[7] = rotate::<12>(v[7]);
     v[4] = rotate::<12>(v[4]);
-    v[0] += m[SIGMA[r][9] as usize];
-    v[1] += m[SIGMA[r][11] as usize];
-    v[2] += m[SIGMA[r][13] as usize];
-    v[3] += m[SIGMA[r][15] as usize];
+    v[0] += m[sigma[9] as usize];
+    v[1] += m[sigma[11] as usize];
+    v[2] += m[sigma[13] as usize];
+    v[3] += m[sigma[15] as usize];
     v[0] += v[5];
     v[1] += v[6];
     v[2] += v[7];
@@ -248,7 +248,7 @@ fn round(v: &mut [u32x16; 16], m: [u32x16; 16], r: usize) {
 
 /// Transposes input chunks (16 chunks of 16 `u32`s each), to get 16 `u32x16`, each
 /// representing 16 packed instances of a message word.
-fn transpose_msgs(mut data: [u32x16; 16]) -> [u32x16; 16] {
+pub(super) fn transpose_msgs(mut data: [u32x16; 16]) -> [u32x16; 16] {
     // Index abcd:xyzw, refers to a specific word in data as follows:
     //   abcd - chunk index (in base 2)
     //   xyzw - word offset (in base 2)
@@ -272,7 +272,7 @@ fn transpose_msgs(mut data: [u32x16; 16]) -> [u32x16; 16] {
     data
 }
 
-fn untranspose_states(mut states: [u32x16; 8]) -> [u32x16; 8] {
+pub(super) fn untranspose_states(mut states: [u32x16; 8]) -> [u32x16; 8] {
     // Index abc:xyzw, refers to a specific word in data as follows:
     //   abc - chunk index (in base 2)
     //   xyzw - word offset (in base 2)
@@ -290,7 +290,7 @@ fn untranspose_states(mut states: [u32x16; 8]) -> [u32x16; 8] {
 }
 
 /// Compresses 16 blake2s instances.
-fn compress16(
+pub fn compress16(
     h_vecs: [u32x16; 8],
     msg_vecs: [u32x16; 16],
     count_low: u32x16,
@@ -317,16 +317,16 @@ fn compress16(
         u32x16::splat(IV[7]) ^ lastnode,
     ];
 
-    round(&mut v, msg_vecs, 0);
-    round(&mut v, msg_vecs, 1);
-    round(&mut v, msg_vecs, 2);
-    round(&mut v, msg_vecs, 3);
-    round(&mut v, msg_vecs, 4);
-    round(&mut v, msg_vecs, 5);
-    round(&mut v, msg_vecs, 6);
-    round(&mut v, msg_vecs, 7);
-    round(&mut v, msg_vecs, 8);
-    round(&mut v, msg_vecs, 9);
+    round(&mut v, msg_vecs, &SIGMA[0]);
+    round(&mut v, msg_vecs, &SIGMA[1]);
+    round(&mut v, msg_vecs, &SIGMA[2]);
+    round(&mut v, msg_vecs, &SIGMA[3]);
+    round(&mut v, msg_vecs, &SIGMA[4]);
+    round(&mut v, msg_vecs, &SIGMA[5]);
+    round(&mut v, msg_vecs, &SIGMA[6]);
+    round(&mut v, msg_vecs, &SIGMA[7]);
+    round(&mut v, msg_vecs, &SIGMA[8]);
+    round(&mut v, msg_vecs, &SIGMA[9]);
 
     [
         h_vecs[0] ^ v[0] ^ v[8],
@@ -0,0 +1,246 @@
+//! A SIMD implementation of the BLAKE3 compression function, and of Merkle hashing with it.
+//! BLAKE3 uses the BLAKE2s round function, with 7 rounds and a fixed message permutation.
+
+use std::array;
+use std::iter::repeat;
+use std::mem::transmute;
+use std::simd::u32x16;
+
+use bytemuck::cast_slice;
+use itertools::Itertools;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::blake2s::{round, transpose_msgs, untranspose_states};
+use super::m31::{LOG_N_LANES, N_LANES};
+use super::SimdBackend;
+use crate::core::backend::{Col, Column, ColumnOps};
+use crate::core::fields::m31::{BaseField, N_BYTES_FELT};
+use crate::core::utils::bit_reverse;
+use crate::core::vcs::blake2s_ref::IV;
+use crate::core::vcs::blake3_hash::Blake3Hash;
+use crate::core::vcs::blake3_merkle::Blake3MerkleHasher;
+use crate::core::vcs::ops::{MerkleHasher, MerkleOps};
+
+/// The message word order of each round, i.e. the BLAKE3 message permutation applied `r` times.
+const MSG_SCHEDULE: [[u8; 16]; 7] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8],
+    [3, 4, 10, 12, 13, 2, 7, 14, 6, 5, 9, 0, 11, 15, 8, 1],
+    [10, 7, 12, 9, 14, 3, 13, 15, 4, 0, 11, 2, 5, 8, 1, 6],
+    [12, 13, 9, 11, 15, 10, 14, 8, 7, 2, 5, 3, 0, 1, 6, 4],
+    [9, 14, 11, 5, 8, 12, 15, 1, 13, 3, 0, 10, 2, 6, 4, 7],
+    [11, 15, 5, 0, 1, 9, 8, 6, 14, 10, 2, 12, 3, 4, 7, 13],
+];
+
+/// Domain separation flags of the compression function.
+pub const CHUNK_START: u32 = 1 << 0;
+pub const CHUNK_END: u32 = 1 << 1;
+pub const ROOT: u32 = 1 << 3;
+
+const BLOCK_LEN: usize = 64;
+
+/// Inputs longer than a chunk are hashed as a tree of chunks, which isn't vectorized.
+const CHUNK_LEN: usize = 1024;
+
+impl ColumnOps<Blake3Hash> for SimdBackend {
+    type Column = Vec<Blake3Hash>;
+
+    fn bit_reverse_column(column: &mut Self::Column) {
+        bit_reverse(column)
+    }
+}
+
+impl MerkleOps<Blake3MerkleHasher> for SimdBackend {
+    fn commit_on_layer(
+        log_size: u32,
+        prev_layer: Option<&Vec<Blake3Hash>>,
+        columns: &[&Col<Self, BaseField>],
+    ) -> Vec<Blake3Hash> {
+        let node_len = prev_layer.map_or(0, |_| BLOCK_LEN) + columns.len() * N_BYTES_FELT;
+        if log_size < LOG_N_LANES || node_len == 0 || node_len > CHUNK_LEN {
+            #[cfg(not(feature = "parallel"))]
+            let iter = 0..1 << log_size;
+
+            #[cfg(feature = "parallel")]
+            let iter = (0..1 << log_size).into_par_iter();
+
+            return iter
+                .map(|i| {
+                    Blake3MerkleHasher::hash_node(
+                        prev_layer.map_or(&[][..], |prev_layer| &prev_layer[2 * i..2 * i + 2]),
+                        &columns.iter().map(|column| column.at(i)).collect_vec(),
+                    )
+                })
+                .collect();
+        }
+
+        if let Some(prev_layer) = prev_layer {
+            assert_eq!(prev_layer.len(), 1 << (log_size + 1));
+        }
+
+        let zeros = u32x16::splat(0);
+        let n_blocks = node_len.div_ceil(BLOCK_LEN);
+
+        // Commit to columns.
+        let mut res = vec![Blake3Hash::default(); 1 << log_size];
+        #[cfg(not(feature = "parallel"))]
+        let iter = res.chunks_mut(1 << LOG_N_LANES);
+
+        #[cfg(feature = "parallel")]
+        let iter = res.par_chunks_mut(1 << LOG_N_LANES);
+
+        iter.enumerate().for_each(|(i, chunk)| {
+            let mut state = IV.map(u32x16::splat);
+            let mut block_index = 0;
+            let mut compress_block = |state, msgs, block_len: usize| {
+                let mut flags = 0;
+                if block_index == 0 {
+                    flags |= CHUNK_START;
+                }
+                if block_index == n_blocks - 1 {
+                    flags |= CHUNK_END | ROOT;
+                }
+                block_index += 1;
+                compress16(
+                    state,
+                    msgs,
+                    zeros,
+                    zeros,
+                    u32x16::splat(block_len as u32),
+                    u32x16::splat(flags),
+                )
+            };
+
+            // Hash prev_layer, if exists.
+            if let Some(prev_layer) = prev_layer {
+                let prev_chunk_u32s = cast_slice::<_, u32>(&prev_layer[(i << 5)..((i + 1) << 5)]);
+                let msgs: [u32x16; 16] = array::from_fn(|j| {
+                    u32x16::from_array(array::from_fn(|k| prev_chunk_u32s[16 * j + k]))
+                });
+                state = compress_block(state, transpose_msgs(msgs), BLOCK_LEN);
+            }
+
+            // Hash columns in chunks of 16.
+            let mut col_chunk_iter = columns.array_chunks();
+            for col_chunk in &mut col_chunk_iter {
+                let msgs = col_chunk.map(|column| column.data[i].into_simd());
+                state = compress_block(state, msgs, BLOCK_LEN);
+            }
+
+            // Hash remaining columns. Unlike in BLAKE2s, the last block has its actual length.
+            let remainder = col_chunk_iter.remainder();
+            if !remainder.is_empty() {
+                let msgs = remainder
+                    .iter()
+                    .map(|column| column.data[i].into_simd())
+                    .chain(repeat(zeros))
+                    .take(N_LANES)
+                    .collect_vec()
+                    .try_into()
+                    .unwrap();
+                state = compress_block(state, msgs, remainder.len() * N_BYTES_FELT);
+            }
+            let state: [Blake3Hash; 16] = unsafe { transmute(untranspose_states(state)) };
+            chunk.copy_from_slice(&state);
+        });
+        res
+    }
+}
+
+/// Compresses 16 blake3 instances, returning the first 8 words of each output.
+pub fn compress16(
+    cv_vecs: [u32x16; 8],
+    msg_vecs: [u32x16; 16],
+    counter_low: u32x16,
+    counter_high: u32x16,
+    block_len: u32x16,
+    flags: u32x16,
+) -> [u32x16; 8] {
+    let mut v = [
+        cv_vecs[0],
+        cv_vecs[1],
+        cv_vecs[2],
+        cv_vecs[3],
+        cv_vecs[4],
+        cv_vecs[5],
+        cv_vecs[6],
+        cv_vecs[7],
+        u32x16::splat(IV[0]),
+        u32x16::splat(IV[1]),
+        u32x16::splat(IV[2]),
+        u32x16::splat(IV[3]),
+        counter_low,
+        counter_high,
+        block_len,
+        flags,
+    ];
+
+    for sigma in &MSG_SCHEDULE {
+        round(&mut v, msg_vecs, sigma);
+    }
+
+    array::from_fn(|i| v[i] ^ v[i + 8])
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::backend::{Col, CpuBackend};
+    use crate::core::fields::m31::BaseField;
+    use crate::core::vcs::blake3_hash::Blake3Hash;
+    use crate::core::vcs::blake3_merkle::Blake3MerkleHasher;
+    use crate::core::vcs::ops::MerkleOps;
+
+    #[test]
+    fn commit_on_layer_matches_cpu() {
+        const LOG_SIZE: u32 = 6;
+        let mut rng = SmallRng::seed_from_u64(0);
+        // Covers a partial last block, a full last block and nodes spanning several chunks.
+        for n_columns in [0, 1, 15, 16, 17, 240, 256, 300] {
+            let columns = (0..n_columns)
+                .map(|_| (0..1 << LOG_SIZE).map(|_| rng.gen()).collect_vec())
+                .collect_vec();
+            let prev_layer = (0..2 << LOG_SIZE)
+                .map(|_| Blake3Hash::from(rng.gen::<[u8; 32]>().to_vec()))
+                .collect_vec();
+            let simd_columns = columns
+                .iter()
+                .map(|column| {
+                    column
+                        .iter()
+                        .copied()
+                        .collect::<Col<SimdBackend, BaseField>>()
+                })
+                .collect_vec();
+
+            let cpu_leaves = <CpuBackend as MerkleOps<Blake3MerkleHasher>>::commit_on_layer(
+                LOG_SIZE,
+                None,
+                &columns.iter().collect_vec(),
+            );
+            let simd_leaves = <SimdBackend as MerkleOps<Blake3MerkleHasher>>::commit_on_layer(
+                LOG_SIZE,
+                None,
+                &simd_columns.iter().collect_vec(),
+            );
+            let cpu_nodes = <CpuBackend as MerkleOps<Blake3MerkleHasher>>::commit_on_layer(
+                LOG_SIZE,
+                Some(&prev_layer),
+                &columns.iter().collect_vec(),
+            );
+            let simd_nodes = <SimdBackend as MerkleOps<Blake3MerkleHasher>>::commit_on_layer(
+                LOG_SIZE,
+                Some(&prev_layer),
+                &simd_columns.iter().collect_vec(),
+            );
+
+            assert_eq!(simd_leaves, cpu_leaves, "n_columns={n_columns}");
+            assert_eq!(simd_nodes, cpu_nodes, "n_columns={n_columns}");
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use std::mem;
+use std::ops::Range;
 
 use bytemuck::{cast_slice, cast_slice_mut, Zeroable};
 use itertools::{izip, Itertools};
@@ -72,6 +73,17 @@ impl Column<BaseField> for BaseFieldVec {
     fn at(&self, index: usize) -> BaseField {
         self.data[index / N_LANES].to_array()[index % N_LANES]
     }
+
+    fn copy_range(&self, range: Range<usize>) -> Self {
+        if range.start % N_LANES != 0 || range.end % N_LANES != 0 {
+            return self.as_slice()[range].iter().copied().collect();
+        }
+        // Copy whole packed words.
+        Self {
+            data: self.data[range.start / N_LANES..range.end / N_LANES].to_vec(),
+            length: range.len(),
+        }
+    }
 }
 
 impl FromIterator<BaseField> for BaseFieldVec {
@@ -230,6 +242,16 @@ mod tests {
         assert_eq!(res.to_cpu(), values);
     }
 
+    #[test]
+    fn base_field_vec_copy_range_works() {
+        let values: [BaseField; 70] = array::from_fn(BaseField::from);
+        let column = values.into_iter().collect::<BaseFieldVec>();
+
+        for range in [0..70, 16..48, 3..40, 32..35, 64..70] {
+            assert_eq!(column.copy_range(range.clone()).to_cpu(), values[range]);
+        }
+    }
+
     #[test]
     fn secure_field_vec_from_iter_works() {
         let mut rng = SmallRng::seed_from_u64(0);
@@ -8,11 +8,11 @@ use super::SimdBackend;
 use crate::core::backend::simd::fft::compute_first_twiddles;
 use crate::core::backend::simd::fft::ifft::simd_ibutterfly;
 use crate::core::backend::simd::qm31::PackedSecureField;
-use crate::core::backend::Column;
+use crate::core::backend::{Column, CpuBackend};
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
 use crate::core::fields::secure_column::SecureColumn;
-use crate::core::fri::{self, FriOps};
+use crate::core::fri::{self, FriOps, FriVerifierOps};
 use crate::core::poly::circle::SecureEvaluation;
 use crate::core::poly::line::LineEvaluation;
 use crate::core::poly::twiddles::TwiddleTree;
@@ -96,20 +96,37 @@ impl FriOps for SimdBackend {
         }
     }
 
-    fn decompose(eval: &SecureEvaluation<Self>) -> (SecureEvaluation<Self>, SecureField) {
-        let lambda = decomposition_coefficient(eval);
-        let broadcasted_lambda = PackedSecureField::broadcast(lambda);
+    fn decompose(
+        eval: &SecureEvaluation<Self>,
+        log_blowup_factor: u32,
+    ) -> (SecureEvaluation<Self>, SecureField) {
+        assert!(eval.domain.is_canonic(), "not canonic");
+        let log_block_size = eval.domain.log_size() - log_blowup_factor;
+        if log_block_size < LOG_N_LANES {
+            let cpu_eval = SecureEvaluation {
+                domain: eval.domain,
+                values: eval.to_cpu(),
+            };
+            let (g, lambda) = CpuBackend::decompose(&cpu_eval, log_blowup_factor);
+            let g = SecureEvaluation {
+                domain: g.domain,
+                values: g.values.into_iter().collect(),
+            };
+            return (g, lambda);
+        }
+
+        let decomposition_values =
+            fri::decomposition_values(eval.domain.log_size(), log_blowup_factor);
+        let lambda = decomposition_coefficient(eval, &decomposition_values);
         let mut g_values = SecureColumn::<Self>::zeros(eval.len());
 
-        let range = eval.len().div_ceil(N_LANES);
-        let half_range = range / 2;
-        for i in 0..half_range {
-            let val = unsafe { eval.packed_at(i) } - broadcasted_lambda;
-            unsafe { g_values.set_packed(i, val) }
-        }
-        for i in half_range..range {
-            let val = unsafe { eval.packed_at(i) } + broadcasted_lambda;
-            unsafe { g_values.set_packed(i, val) }
+        let packed_block_size = 1 << (log_block_size - LOG_N_LANES);
+        for (i, &v) in decomposition_values.iter().enumerate() {
+            let broadcasted_lambda_v = PackedSecureField::broadcast(lambda * v);
+            for j in i * packed_block_size..(i + 1) * packed_block_size {
+                let val = unsafe { eval.packed_at(j) } - broadcasted_lambda_v;
+                unsafe { g_values.set_packed(j, val) }
+            }
         }
 
         let g = SecureEvaluation {
@@ -120,52 +137,83 @@ impl FriOps for SimdBackend {
     }
 }
 
+impl FriVerifierOps for SimdBackend {
+    fn fold_values(
+        values: &[SecureField],
+        itwiddles: &[BaseField],
+        alpha: SecureField,
+    ) -> Vec<SecureField> {
+        assert_eq!(values.len(), itwiddles.len() * 2);
+        let packed_alpha = PackedSecureField::broadcast(alpha);
+        let value_chunks = values.chunks_exact(2 * N_LANES);
+        let itwiddle_chunks = itwiddles.chunks_exact(N_LANES);
+        let (values_rem, itwiddles_rem) = (value_chunks.remainder(), itwiddle_chunks.remainder());
+
+        let mut folded_values = Vec::with_capacity(itwiddles.len());
+        for (values, itwiddles) in value_chunks.zip(itwiddle_chunks) {
+            let (lhs, rhs) = values.split_at(N_LANES);
+            let (f0, f1) = PackedSecureField::from_array(lhs.try_into().unwrap())
+                .deinterleave(PackedSecureField::from_array(rhs.try_into().unwrap()));
+            let itwiddles = PackedBaseField::from_array(itwiddles.try_into().unwrap());
+            let folded = (f0 + f1) + packed_alpha * ((f0 - f1) * itwiddles);
+            folded_values.extend(folded.to_array());
+        }
+        folded_values.extend(CpuBackend::fold_values(values_rem, itwiddles_rem, alpha));
+        folded_values
+    }
+}
+
 /// See [`decomposition_coefficient`].
 ///
+/// Assumes each block of `decomposition_values` spans at least [`N_LANES`] values.
+///
 /// [`decomposition_coefficient`]: crate::core::backend::cpu::CpuBackend::decomposition_coefficient
-fn decomposition_coefficient(eval: &SecureEvaluation<SimdBackend>) -> SecureField {
+fn decomposition_coefficient(
+    eval: &SecureEvaluation<SimdBackend>,
+    decomposition_values: &[BaseField],
+) -> SecureField {
     let cols = &eval.values.columns;
-    let [mut x_sum, mut y_sum, mut z_sum, mut w_sum] = [PackedBaseField::zero(); 4];
+    let packed_block_size = cols[0].len() / N_LANES / decomposition_values.len();
 
-    let range = cols[0].len() / N_LANES;
-    let (half_a, half_b) = (range / 2, range);
+    let sum = decomposition_values
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| {
+            let [mut x_sum, mut y_sum, mut z_sum, mut w_sum] = [PackedBaseField::zero(); 4];
+            for j in i * packed_block_size..(i + 1) * packed_block_size {
+                x_sum += cols[0].data[j];
+                y_sum += cols[1].data[j];
+                z_sum += cols[2].data[j];
+                w_sum += cols[3].data[j];
+            }
 
-    for i in 0..half_a {
-        x_sum += cols[0].data[i];
-        y_sum += cols[1].data[i];
-        z_sum += cols[2].data[i];
-        w_sum += cols[3].data[i];
-    }
-    for i in half_a..half_b {
-        x_sum -= cols[0].data[i];
-        y_sum -= cols[1].data[i];
-        z_sum -= cols[2].data[i];
-        w_sum -= cols[3].data[i];
-    }
+            let x = x_sum.pointwise_sum();
+            let y = y_sum.pointwise_sum();
+            let z = z_sum.pointwise_sum();
+            let w = w_sum.pointwise_sum();
+            SecureField::from_m31(x, y, z, w) * v
+        })
+        .sum::<SecureField>();
 
-    let x = x_sum.pointwise_sum();
-    let y = y_sum.pointwise_sum();
-    let z = z_sum.pointwise_sum();
-    let w = w_sum.pointwise_sum();
-
-    SecureField::from_m31(x, y, z, w) / BaseField::from_u32_unchecked(1 << eval.domain.log_size())
+    sum / BaseField::from_u32_unchecked(1 << (eval.domain.log_size() - 1))
 }
 
 #[cfg(test)]
 mod tests {
     use itertools::Itertools;
-    use num_traits::One;
+    use num_traits::{One, Zero};
     use rand::rngs::SmallRng;
     use rand::{Rng, SeedableRng};
 
+    use crate::core::backend::cpu::CpuCirclePoly;
     use crate::core::backend::simd::column::BaseFieldVec;
     use crate::core::backend::simd::SimdBackend;
-    use crate::core::backend::{Column, CpuBackend};
+    use crate::core::backend::CpuBackend;
     use crate::core::fields::m31::BaseField;
     use crate::core::fields::qm31::SecureField;
     use crate::core::fields::secure_column::SecureColumn;
     use crate::core::fri::FriOps;
-    use crate::core::poly::circle::{CanonicCoset, CirclePoly, PolyOps, SecureEvaluation};
+    use crate::core::poly::circle::{CanonicCoset, PolyOps, SecureEvaluation};
     use crate::core::poly::line::{LineDomain, LineEvaluation};
     use crate::qm31;
 
@@ -229,38 +277,43 @@ mod tests {
 
     #[test]
     fn decomposition_test() {
-        const DOMAIN_LOG_SIZE: u32 = 5;
-        const DOMAIN_LOG_HALF_SIZE: u32 = DOMAIN_LOG_SIZE - 1;
-        let s = CanonicCoset::new(DOMAIN_LOG_SIZE);
-        let domain = s.circle_domain();
-        let mut coeffs = BaseFieldVec::zeros(1 << DOMAIN_LOG_SIZE);
-        // Polynomial is out of FFT space.
-        coeffs.as_mut_slice()[1 << DOMAIN_LOG_HALF_SIZE] = BaseField::one();
-        let poly = CirclePoly::<SimdBackend>::new(coeffs);
-        let values = poly.evaluate(domain);
-        let avx_column = SecureColumn::<SimdBackend> {
-            columns: [
-                values.values.clone(),
-                values.values.clone(),
-                values.values.clone(),
-                values.values.clone(),
-            ],
-        };
-        let avx_eval = SecureEvaluation {
-            domain,
-            values: avx_column.clone(),
-        };
-        let cpu_eval = SecureEvaluation::<CpuBackend> {
-            domain,
-            values: avx_eval.to_cpu(),
-        };
-        let (cpu_g, cpu_lambda) = CpuBackend::decompose(&cpu_eval);
+        for log_blowup_factor in 1..=4 {
+            // Degree bounds below and above the SIMD lane count.
+            for log_degree_bound in 3..=5 {
+                let domain_log_size = log_degree_bound + log_blowup_factor;
+                let s = CanonicCoset::new(domain_log_size);
+                let domain = s.circle_domain();
+                let mut coeffs = vec![BaseField::zero(); 1 << domain_log_size];
+                // Polynomial is out of FFT space.
+                coeffs[1 << log_degree_bound] = BaseField::one();
+                // Small domains are not supported by the SIMD FFT.
+                let poly = CpuCirclePoly::new(coeffs);
+                let values: BaseFieldVec = poly.evaluate(domain).values.into_iter().collect();
+                let avx_column = SecureColumn::<SimdBackend> {
+                    columns: [
+                        values.clone(),
+                        values.clone(),
+                        values.clone(),
+                        values.clone(),
+                    ],
+                };
+                let avx_eval = SecureEvaluation {
+                    domain,
+                    values: avx_column.clone(),
+                };
+                let cpu_eval = SecureEvaluation::<CpuBackend> {
+                    domain,
+                    values: avx_eval.to_cpu(),
+                };
+                let (cpu_g, cpu_lambda) = CpuBackend::decompose(&cpu_eval, log_blowup_factor);
 
-        let (avx_g, avx_lambda) = SimdBackend::decompose(&avx_eval);
+                let (avx_g, avx_lambda) = SimdBackend::decompose(&avx_eval, log_blowup_factor);
 
-        assert_eq!(avx_lambda, cpu_lambda);
-        for i in 0..1 << DOMAIN_LOG_SIZE {
-            assert_eq!(avx_g.values.at(i), cpu_g.values.at(i));
+                assert_eq!(avx_lambda, cpu_lambda);
+                for i in 0..1 << domain_log_size {
+                    assert_eq!(avx_g.values.at(i), cpu_g.values.at(i));
+                }
+            }
         }
     }
 }
@@ -0,0 +1,204 @@
+use std::array;
+use std::iter::zip;
+use std::simd::u32x16;
+
+use super::blake2s::compress16;
+use super::blake3::{self, CHUNK_END, CHUNK_START, ROOT};
+use super::m31::{PackedBaseField, N_LANES};
+use super::SimdBackend;
+use crate::core::backend::CpuBackend;
+use crate::core::channel::poseidon2_m31::{nonce_limbs, pow_trailing_zeros};
+use crate::core::channel::{
+    Blake2sChannel, Blake3Channel, Channel, Poseidon252Channel, Poseidon2M31Channel,
+};
+use crate::core::proof_of_work::{grind_tasks, GrindOps, LOG_NONCES_PER_TASK};
+use crate::core::vcs::blake2s_ref::IV;
+use crate::core::vcs::poseidon2_m31::{Poseidon2M31Hash, Poseidon2M31Sponge, N_HASH_FELTS};
+
+/// Length in bytes of the hashed proof of work input: the channel digest followed by the nonce.
+const POW_INPUT_LEN: u32 = 40;
+
+/// Blake2s parameter block word for an unkeyed hash with a 32 byte output.
+const PARAMS: u32 = 0x01010020;
+
+impl GrindOps<Blake2sChannel> for SimdBackend {
+    fn grind(channel: &Blake2sChannel, pow_bits: u32) -> u64 {
+        let zeros = u32x16::splat(0);
+        let mut state = IV.map(u32x16::splat);
+        state[0] ^= u32x16::splat(PARAMS);
+        let hash = |msgs| {
+            compress16(
+                state,
+                msgs,
+                u32x16::splat(POW_INPUT_LEN),
+                zeros,
+                u32x16::splat(u32::MAX),
+                zeros,
+            )
+        };
+        let digest_words = digest_words(channel.get_digest().as_ref());
+        grind_tasks(|task| find_in_task(hash, digest_words, task, pow_bits))
+    }
+}
+
+impl GrindOps<Blake3Channel> for SimdBackend {
+    fn grind(channel: &Blake3Channel, pow_bits: u32) -> u64 {
+        let zeros = u32x16::splat(0);
+        let hash = |msgs| {
+            blake3::compress16(
+                IV.map(u32x16::splat),
+                msgs,
+                zeros,
+                zeros,
+                u32x16::splat(POW_INPUT_LEN),
+                u32x16::splat(CHUNK_START | CHUNK_END | ROOT),
+            )
+        };
+        let digest_words = digest_words(channel.get_digest().as_ref());
+        grind_tasks(|task| find_in_task(hash, digest_words, task, pow_bits))
+    }
+}
+
+impl GrindOps<Poseidon2M31Channel> for SimdBackend {
+    fn grind(channel: &Poseidon2M31Channel, pow_bits: u32) -> u64 {
+        let digest = channel.get_digest();
+        grind_tasks(|task| find_in_task_poseidon2_m31(digest, task, pow_bits))
+    }
+}
+
+impl GrindOps<Poseidon252Channel> for SimdBackend {
+    fn grind(channel: &Poseidon252Channel, pow_bits: u32) -> u64 {
+        CpuBackend::grind(channel, pow_bits)
+    }
+}
+
+fn digest_words(digest: &[u8]) -> [u32; 8] {
+    array::from_fn(|i| u32::from_le_bytes(digest[i * 4..(i + 1) * 4].try_into().unwrap()))
+}
+
+/// Returns the smallest nonce of the grinding task whose proof of work hash has at least
+/// `pow_bits` leading zeros. `hash` hashes the single input block of [N_LANES] nonces at once.
+fn find_in_task(
+    hash: impl Fn([u32x16; 16]) -> [u32x16; 8],
+    digest_words: [u32; 8],
+    task: u64,
+    pow_bits: u32,
+) -> Option<u64> {
+    let zeros = u32x16::splat(0);
+
+    // The input fits in a single block: the digest, the nonce and zero padding.
+    let mut msgs = [zeros; 16];
+    for (msg, word) in zip(&mut msgs, digest_words) {
+        *msg = u32x16::splat(word);
+    }
+    // All nonces of a task share their high word.
+    let start = task << LOG_NONCES_PER_TASK;
+    msgs[9] = u32x16::splat((start >> 32) as u32);
+    let lane_offsets = u32x16::from_array(array::from_fn(|i| i as u32));
+
+    for offset in (0..1 << LOG_NONCES_PER_TASK).step_by(N_LANES) {
+        let nonce = start + offset;
+        msgs[8] = u32x16::splat(nonce as u32) + lane_offsets;
+        let hashes = hash(msgs);
+        if let Some(lane) = (0..N_LANES).find(|&lane| leading_zeros(&hashes, lane) >= pow_bits) {
+            return Some(nonce + lane as u64);
+        }
+    }
+    None
+}
+
+/// Returns the smallest nonce of the grinding task that is a valid Poseidon2 proof of work.
+/// Hashes [N_LANES] nonces at once.
+fn find_in_task_poseidon2_m31(digest: Poseidon2M31Hash, task: u64, pow_bits: u32) -> Option<u64> {
+    // The proof of work input is the digest followed by the nonce limbs. The digest part of the
+    // sponge is shared by all nonces.
+    let limbs_len = nonce_limbs(0).len();
+    let mut digest_sponge = Poseidon2M31Sponge::<PackedBaseField>::new(N_HASH_FELTS + limbs_len);
+    digest
+        .0
+        .iter()
+        .for_each(|value| digest_sponge.absorb(PackedBaseField::broadcast(*value)));
+
+    let start = task << LOG_NONCES_PER_TASK;
+    for offset in (0..1 << LOG_NONCES_PER_TASK).step_by(N_LANES) {
+        let nonce = start + offset;
+        let lane_limbs: [_; N_LANES] = array::from_fn(|lane| nonce_limbs(nonce + lane as u64));
+        let mut sponge = digest_sponge.clone();
+        for limb in 0..limbs_len {
+            sponge.absorb(PackedBaseField::from_array(array::from_fn(|lane| {
+                lane_limbs[lane][limb]
+            })));
+        }
+        let hashes = sponge.finalize().map(|packed| packed.to_array());
+        if let Some(lane) = (0..N_LANES).find(|&lane| {
+            let hash = Poseidon2M31Hash(array::from_fn(|j| hashes[j][lane]));
+            pow_trailing_zeros(&hash) >= pow_bits
+        }) {
+            return Some(nonce + lane as u64);
+        }
+    }
+    None
+}
+
+/// Returns the number of leading zeros of the hash in `lane`, read as a little endian number.
+fn leading_zeros(hashes: &[u32x16; 8], lane: usize) -> u32 {
+    let mut n_zeros = 0;
+    for word in hashes.iter().rev() {
+        n_zeros += word[lane].leading_zeros();
+        if word[lane] != 0 {
+            break;
+        }
+    }
+    n_zeros
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::backend::CpuBackend;
+    use crate::core::channel::{Blake2sChannel, Blake3Channel, Channel, Poseidon2M31Channel};
+    use crate::core::proof_of_work::GrindOps;
+    use crate::core::vcs::blake2_hash::Blake2sHash;
+    use crate::core::vcs::blake3_hash::Blake3Hash;
+    use crate::core::vcs::poseidon2_m31::Poseidon2M31Hash;
+    use crate::m31;
+
+    #[test]
+    fn grind_blake2s_matches_cpu() {
+        for seed in 0..4 {
+            let channel = Blake2sChannel::new(Blake2sHash::from(vec![seed; 32]));
+            let pow_bits = 10 + seed as u32;
+
+            let nonce = SimdBackend::grind(&channel, pow_bits);
+
+            assert!(channel.verify_pow_nonce(pow_bits, nonce));
+            assert_eq!(nonce, CpuBackend::grind(&channel, pow_bits));
+        }
+    }
+
+    #[test]
+    fn grind_blake3_matches_cpu() {
+        for seed in 0..4 {
+            let channel = Blake3Channel::new(Blake3Hash::from(vec![seed; 32]));
+            let pow_bits = 10 + seed as u32;
+
+            let nonce = SimdBackend::grind(&channel, pow_bits);
+
+            assert!(channel.verify_pow_nonce(pow_bits, nonce));
+            assert_eq!(nonce, CpuBackend::grind(&channel, pow_bits));
+        }
+    }
+
+    #[test]
+    fn grind_poseidon2_m31_matches_cpu() {
+        for seed in 0..4 {
+            let channel = Poseidon2M31Channel::new(Poseidon2M31Hash([m31!(seed); 8]));
+            let pow_bits = 6 + seed;
+
+            let nonce = SimdBackend::grind(&channel, pow_bits);
+
+            assert!(channel.verify_pow_nonce(pow_bits, nonce));
+            assert_eq!(nonce, CpuBackend::grind(&channel, pow_bits));
+        }
+    }
+}
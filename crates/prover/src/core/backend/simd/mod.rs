@@ -3,12 +3,16 @@ use super::Backend;
 pub mod accumulation;
 pub mod bit_reverse;
 pub mod blake2s;
+pub mod blake3;
 pub mod circle;
 pub mod cm31;
 pub mod column;
 pub mod fft;
 pub mod fri;
+pub mod grind;
 pub mod m31;
+pub mod poseidon252;
+pub mod poseidon2_m31;
 pub mod qm31;
 pub mod quotients;
 mod utils;
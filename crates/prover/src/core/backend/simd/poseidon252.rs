@@ -0,0 +1,129 @@
+use itertools::Itertools;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use starknet_ff::FieldElement as FieldElement252;
+
+use super::m31::{LOG_N_LANES, N_LANES};
+use super::SimdBackend;
+use crate::core::backend::{Col, Column, ColumnOps};
+use crate::core::fields::m31::BaseField;
+use crate::core::utils::bit_reverse;
+use crate::core::vcs::ops::{MerkleHasher, MerkleOps};
+use crate::core::vcs::poseidon252_merkle::Poseidon252MerkleHasher;
+
+impl ColumnOps<FieldElement252> for SimdBackend {
+    type Column = Vec<FieldElement252>;
+
+    fn bit_reverse_column(column: &mut Self::Column) {
+        bit_reverse(column)
+    }
+}
+
+impl MerkleOps<Poseidon252MerkleHasher> for SimdBackend {
+    fn commit_on_layer(
+        log_size: u32,
+        prev_layer: Option<&Vec<FieldElement252>>,
+        columns: &[&Col<Self, BaseField>],
+    ) -> Vec<FieldElement252> {
+        if log_size < LOG_N_LANES {
+            #[cfg(not(feature = "parallel"))]
+            let iter = 0..1 << log_size;
+
+            #[cfg(feature = "parallel")]
+            let iter = (0..1 << log_size).into_par_iter();
+
+            return iter
+                .map(|i| {
+                    Poseidon252MerkleHasher::hash_node(
+                        prev_layer.map_or(&[][..], |prev_layer| &prev_layer[2 * i..2 * i + 2]),
+                        &columns.iter().map(|column| column.at(i)).collect_vec(),
+                    )
+                })
+                .collect();
+        }
+
+        if let Some(prev_layer) = prev_layer {
+            assert_eq!(prev_layer.len(), 1 << (log_size + 1));
+        }
+
+        let mut res = vec![FieldElement252::default(); 1 << log_size];
+        #[cfg(not(feature = "parallel"))]
+        let iter = res.chunks_mut(N_LANES);
+
+        #[cfg(feature = "parallel")]
+        let iter = res.par_chunks_mut(N_LANES);
+
+        iter.enumerate().for_each(|(i, chunk)| {
+            // Unpack the rows of the chunk. Poseidon252 has no vectorized implementation, so the
+            // nodes are then hashed one by one.
+            let packed_rows = columns
+                .iter()
+                .map(|column| column.data[i].to_array())
+                .collect_vec();
+            let mut values = vec![BaseField::default(); columns.len()];
+            for (j, hash) in chunk.iter_mut().enumerate() {
+                for (value, packed_row) in values.iter_mut().zip(&packed_rows) {
+                    *value = packed_row[j];
+                }
+                let node = i * N_LANES + j;
+                *hash = Poseidon252MerkleHasher::hash_node(
+                    prev_layer.map_or(&[][..], |prev_layer| &prev_layer[2 * node..2 * node + 2]),
+                    &values,
+                );
+            }
+        });
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+    use starknet_ff::FieldElement as FieldElement252;
+
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::backend::{Col, CpuBackend};
+    use crate::core::fields::m31::BaseField;
+    use crate::core::vcs::ops::MerkleOps;
+    use crate::core::vcs::poseidon252_merkle::Poseidon252MerkleHasher;
+
+    #[test]
+    fn commit_on_layer_matches_cpu() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for log_size in [2, 5] {
+            let columns = (0..11)
+                .map(|_| (0..1 << log_size).map(|_| rng.gen()).collect_vec())
+                .collect_vec();
+            let prev_layer = (0..2 << log_size)
+                .map(|_| FieldElement252::from(rng.gen::<u64>()))
+                .collect_vec();
+            let simd_columns = columns
+                .iter()
+                .map(|column| {
+                    column
+                        .iter()
+                        .copied()
+                        .collect::<Col<SimdBackend, BaseField>>()
+                })
+                .collect_vec();
+
+            for prev_layer in [None, Some(&prev_layer)] {
+                let cpu_nodes = <CpuBackend as MerkleOps<Poseidon252MerkleHasher>>::commit_on_layer(
+                    log_size,
+                    prev_layer,
+                    &columns.iter().collect_vec(),
+                );
+                let simd_nodes =
+                    <SimdBackend as MerkleOps<Poseidon252MerkleHasher>>::commit_on_layer(
+                        log_size,
+                        prev_layer,
+                        &simd_columns.iter().collect_vec(),
+                    );
+
+                assert_eq!(simd_nodes, cpu_nodes);
+            }
+        }
+    }
+}
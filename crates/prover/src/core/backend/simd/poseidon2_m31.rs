@@ -0,0 +1,137 @@
+use std::array;
+
+use itertools::Itertools;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::m31::{PackedBaseField, LOG_N_LANES, N_LANES};
+use super::SimdBackend;
+use crate::core::backend::{Col, Column, ColumnOps};
+use crate::core::fields::m31::BaseField;
+use crate::core::utils::bit_reverse;
+use crate::core::vcs::ops::{MerkleHasher, MerkleOps};
+use crate::core::vcs::poseidon2_m31::{Poseidon2M31Hash, Poseidon2M31Sponge, N_HASH_FELTS};
+use crate::core::vcs::poseidon2_m31_merkle::Poseidon2M31MerkleHasher;
+
+impl ColumnOps<Poseidon2M31Hash> for SimdBackend {
+    type Column = Vec<Poseidon2M31Hash>;
+
+    fn bit_reverse_column(column: &mut Self::Column) {
+        bit_reverse(column)
+    }
+}
+
+impl MerkleOps<Poseidon2M31MerkleHasher> for SimdBackend {
+    fn commit_on_layer(
+        log_size: u32,
+        prev_layer: Option<&Vec<Poseidon2M31Hash>>,
+        columns: &[&Col<Self, BaseField>],
+    ) -> Vec<Poseidon2M31Hash> {
+        if log_size < LOG_N_LANES {
+            #[cfg(not(feature = "parallel"))]
+            let iter = 0..1 << log_size;
+
+            #[cfg(feature = "parallel")]
+            let iter = (0..1 << log_size).into_par_iter();
+
+            return iter
+                .map(|i| {
+                    Poseidon2M31MerkleHasher::hash_node(
+                        prev_layer.map_or(&[][..], |prev_layer| &prev_layer[2 * i..2 * i + 2]),
+                        &columns.iter().map(|column| column.at(i)).collect_vec(),
+                    )
+                })
+                .collect();
+        }
+
+        if let Some(prev_layer) = prev_layer {
+            assert_eq!(prev_layer.len(), 1 << (log_size + 1));
+        }
+
+        let node_len = prev_layer.map_or(0, |_| 2 * N_HASH_FELTS) + columns.len();
+
+        let mut res = vec![Poseidon2M31Hash::default(); 1 << log_size];
+        #[cfg(not(feature = "parallel"))]
+        let iter = res.chunks_mut(N_LANES);
+
+        #[cfg(feature = "parallel")]
+        let iter = res.par_chunks_mut(N_LANES);
+
+        // All the nodes of a layer have the same length, so [N_LANES] nodes are hashed at once.
+        iter.enumerate().for_each(|(i, chunk)| {
+            let mut sponge = Poseidon2M31Sponge::<PackedBaseField>::new(node_len);
+            if let Some(prev_layer) = prev_layer {
+                let children = &prev_layer[2 * i * N_LANES..2 * (i + 1) * N_LANES];
+                for child in 0..2 {
+                    for j in 0..N_HASH_FELTS {
+                        sponge.absorb(PackedBaseField::from_array(array::from_fn(|lane| {
+                            children[2 * lane + child].0[j]
+                        })));
+                    }
+                }
+            }
+            columns
+                .iter()
+                .for_each(|column| sponge.absorb(column.data[i]));
+
+            let hashes = sponge.finalize().map(|packed| packed.to_array());
+            for (lane, hash) in chunk.iter_mut().enumerate() {
+                *hash = Poseidon2M31Hash(array::from_fn(|j| hashes[j][lane]));
+            }
+        });
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::backend::{Col, CpuBackend};
+    use crate::core::fields::m31::BaseField;
+    use crate::core::vcs::ops::MerkleOps;
+    use crate::core::vcs::poseidon2_m31::Poseidon2M31Hash;
+    use crate::core::vcs::poseidon2_m31_merkle::Poseidon2M31MerkleHasher;
+
+    #[test]
+    fn commit_on_layer_matches_cpu() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for (log_size, n_columns) in [(2, 3), (5, 0), (5, 7), (5, 8), (5, 21)] {
+            let columns = (0..n_columns)
+                .map(|_| (0..1 << log_size).map(|_| rng.gen()).collect_vec())
+                .collect_vec();
+            let prev_layer = (0..2 << log_size)
+                .map(|_| Poseidon2M31Hash(rng.gen()))
+                .collect_vec();
+            let simd_columns = columns
+                .iter()
+                .map(|column| {
+                    column
+                        .iter()
+                        .copied()
+                        .collect::<Col<SimdBackend, BaseField>>()
+                })
+                .collect_vec();
+
+            for prev_layer in [None, Some(&prev_layer)] {
+                let cpu_nodes =
+                    <CpuBackend as MerkleOps<Poseidon2M31MerkleHasher>>::commit_on_layer(
+                        log_size,
+                        prev_layer,
+                        &columns.iter().collect_vec(),
+                    );
+                let simd_nodes =
+                    <SimdBackend as MerkleOps<Poseidon2M31MerkleHasher>>::commit_on_layer(
+                        log_size,
+                        prev_layer,
+                        &simd_columns.iter().collect_vec(),
+                    );
+
+                assert_eq!(simd_nodes, cpu_nodes);
+            }
+        }
+    }
+}
@@ -6,19 +6,17 @@ use super::column::SecureFieldVec;
 use super::m31::{PackedBaseField, LOG_N_LANES, N_LANES};
 use super::qm31::PackedSecureField;
 use super::SimdBackend;
-use crate::core::backend::cpu::quotients::{
-    batch_random_coeffs, column_line_coeffs, QuotientConstants,
-};
 use crate::core::backend::{Col, Column};
 use crate::core::circle::CirclePoint;
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
 use crate::core::fields::secure_column::{SecureColumn, SECURE_EXTENSION_DEGREE};
 use crate::core::fields::{ComplexConjugate, FieldOps};
-use crate::core::pcs::quotients::{ColumnSampleBatch, QuotientOps};
+use crate::core::pcs::quotients::{
+    batch_random_coeffs, column_line_coeffs, ColumnSampleBatch, QuotientConstants, QuotientOps,
+};
 use crate::core::poly::circle::{CircleDomain, CircleEvaluation, PolyOps, SecureEvaluation};
 use crate::core::poly::BitReversedOrder;
-use crate::core::prover::LOG_BLOWUP_FACTOR;
 use crate::core::utils::{bit_reverse, bit_reverse_index};
 
 impl QuotientOps for SimdBackend {
@@ -27,10 +25,11 @@ impl QuotientOps for SimdBackend {
         columns: &[&CircleEvaluation<Self, BaseField, BitReversedOrder>],
         random_coeff: SecureField,
         sample_batches: &[ColumnSampleBatch],
+        log_blowup_factor: u32,
     ) -> SecureEvaluation<Self> {
         // Split the domain into a subdomain and a shift coset.
         // TODO(spapini): Move to the caller when Columns support slices.
-        let (subdomain, mut subdomain_shifts) = domain.split(LOG_BLOWUP_FACTOR);
+        let (subdomain, mut subdomain_shifts) = domain.split(log_blowup_factor);
 
         // Bit reverse the shifts.
         // Since we traverse the domain in bit-reversed order, we need bit-reverse the shifts.
@@ -137,7 +136,7 @@ fn accumulate_quotients_on_subdomain(
 pub fn accumulate_row_quotients(
     sample_batches: &[ColumnSampleBatch],
     columns: &[&CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>],
-    quotient_constants: &QuotientConstants<SimdBackend>,
+    quotient_constants: &QuotientConstants<Col<SimdBackend, SecureField>>,
     quad_row: usize,
     spaced_ys: PackedBaseField,
 ) -> [PackedSecureField; 4] {
@@ -246,7 +245,7 @@ fn quotient_constants(
     sample_batches: &[ColumnSampleBatch],
     random_coeff: SecureField,
     domain: CircleDomain,
-) -> QuotientConstants<SimdBackend> {
+) -> QuotientConstants<Col<SimdBackend, SecureField>> {
     let line_coeffs = column_line_coeffs(sample_batches, random_coeff);
     let batch_random_coeffs = batch_random_coeffs(sample_batches, random_coeff);
     let denominator_inverses = denominator_inverses(sample_batches, domain);
@@ -269,12 +268,12 @@ mod tests {
     use crate::core::pcs::quotients::{ColumnSampleBatch, QuotientOps};
     use crate::core::poly::circle::{CanonicCoset, CircleEvaluation};
     use crate::core::poly::BitReversedOrder;
-    use crate::core::prover::LOG_BLOWUP_FACTOR;
     use crate::qm31;
 
     #[test]
     fn test_accumulate_quotients() {
         const LOG_SIZE: u32 = 8;
+        const LOG_BLOWUP_FACTOR: u32 = 1;
         let small_domain = CanonicCoset::new(LOG_SIZE).circle_domain();
         let domain = CanonicCoset::new(LOG_SIZE + LOG_BLOWUP_FACTOR).circle_domain();
         let e0: BaseFieldVec = (0..small_domain.size()).map(BaseField::from).collect();
@@ -309,6 +308,7 @@ mod tests {
             &cpu_columns.iter().collect_vec(),
             random_coeff,
             &samples,
+            LOG_BLOWUP_FACTOR,
         )
         .values
         .to_vec();
@@ -318,6 +318,7 @@ mod tests {
             &columns.iter().collect_vec(),
             random_coeff,
             &samples,
+            LOG_BLOWUP_FACTOR,
         )
         .values
         .to_vec();
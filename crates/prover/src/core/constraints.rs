@@ -1,116 +1,4 @@
-use num_traits::One;
-
-use super::circle::{CirclePoint, Coset};
-use super::fields::m31::BaseField;
-use super::fields::qm31::SecureField;
-use super::fields::ExtensionOf;
-use super::pcs::quotients::PointSample;
-use crate::core::fields::ComplexConjugate;
-
-/// Evaluates a vanishing polynomial of the coset at a point.
-pub fn coset_vanishing<F: ExtensionOf<BaseField>>(coset: Coset, mut p: CirclePoint<F>) -> F {
-    // Doubling a point `log_order - 1` times and taking the x coordinate is
-    // essentially evaluating a polynomial in x of degree `2^(log_order - 1)`. If
-    // the entire `2^log_order` points of the coset are roots (i.e. yield 0), then
-    // this is a vanishing polynomial of these points.
-
-    // Rotating the coset -coset.initial + step / 2 yields a canonic coset:
-    // `step/2 + <step>.`
-    // Doubling this coset log_order - 1 times yields the coset +-G_4.
-    // The polynomial x vanishes on these points.
-    // ```text
-    //   X
-    // .   .
-    //   X
-    // ```
-    p = p - coset.initial.into_ef() + coset.step_size.half().to_point().into_ef();
-    let mut x = p.x;
-
-    // The formula for the x coordinate of the double of a point.
-    for _ in 1..coset.log_size {
-        x = CirclePoint::double_x(x);
-    }
-    x
-}
-
-/// Evaluates the polynomial that is used to exclude the excluded point at point
-/// p. Note that this polynomial has a zero of multiplicity 2 at the excluded
-/// point.
-pub fn point_excluder<F: ExtensionOf<BaseField>>(
-    excluded: CirclePoint<BaseField>,
-    p: CirclePoint<F>,
-) -> F {
-    (p - excluded.into_ef()).x - BaseField::one()
-}
-
-// A vanishing polynomial on 2 circle points.
-pub fn pair_vanishing<F: ExtensionOf<BaseField>>(
-    excluded0: CirclePoint<F>,
-    excluded1: CirclePoint<F>,
-    p: CirclePoint<F>,
-) -> F {
-    // The algorithm check computes the area of the triangle formed by the
-    // 3 points. This is done using the determinant of:
-    // | p.x  p.y  1 |
-    // | e0.x e0.y 1 |
-    // | e1.x e1.y 1 |
-    // This is a polynomial of degree 1 in p.x and p.y, and thus it is a line.
-    // It vanishes at e0 and e1.
-    (excluded0.y - excluded1.y) * p.x
-        + (excluded1.x - excluded0.x) * p.y
-        + (excluded0.x * excluded1.y - excluded0.y * excluded1.x)
-}
-
-/// Evaluates a vanishing polynomial of the vanish_point at a point.
-/// Note that this function has a pole on the antipode of the vanish_point.
-pub fn point_vanishing<F: ExtensionOf<BaseField>, EF: ExtensionOf<F>>(
-    vanish_point: CirclePoint<F>,
-    p: CirclePoint<EF>,
-) -> EF {
-    let h = p - vanish_point.into_ef();
-    h.y / (EF::one() + h.x)
-}
-
-/// Evaluates a point on a line between a point and its complex conjugate.
-/// Relies on the fact that every polynomial F over the base field holds:
-/// F(p*) == F(p)* (* being the complex conjugate).
-pub fn complex_conjugate_line(
-    point: CirclePoint<SecureField>,
-    value: SecureField,
-    p: CirclePoint<BaseField>,
-) -> SecureField {
-    // TODO(AlonH): This assertion will fail at a probability of 1 to 2^62. Use a better solution.
-    assert_ne!(
-        point.y,
-        point.y.complex_conjugate(),
-        "Cannot evaluate a line with a single point ({point:?})."
-    );
-    value
-        + (value.complex_conjugate() - value) * (-point.y + p.y)
-            / (point.complex_conjugate().y - point.y)
-}
-
-/// Evaluates the coefficients of a line between a point and its complex conjugate. Specifically,
-/// `a, b, and c, s.t. a*x + b -c*y = 0` for (x,y) being (sample.y, sample.value) and
-/// (conj(sample.y), conj(sample.value)).
-/// Relies on the fact that every polynomial F over the base
-/// field holds: F(p*) == F(p)* (* being the complex conjugate).
-pub fn complex_conjugate_line_coeffs(
-    sample: &PointSample,
-    alpha: SecureField,
-) -> (SecureField, SecureField, SecureField) {
-    // TODO(AlonH): This assertion will fail at a probability of 1 to 2^62. Use a better solution.
-    assert_ne!(
-        sample.point.y,
-        sample.point.y.complex_conjugate(),
-        "Cannot evaluate a line with a single point ({:?}).",
-        sample.point
-    );
-    let a = sample.value.complex_conjugate() - sample.value;
-    let c = sample.point.complex_conjugate().y - sample.point.y;
-    let b = sample.value * c - a * sample.point.y;
-    (alpha * a, alpha * b, alpha * c)
-}
+pub use stwo_verifier::constraints::*;
 
 #[cfg(test)]
 mod tests {
@@ -1,12 +1,10 @@
 use super::m31::BaseField;
 use super::qm31::SecureField;
-use super::{ExtensionOf, FieldOps};
+pub use super::qm31::SECURE_EXTENSION_DEGREE;
+use super::FieldOps;
 use crate::core::backend::{Col, Column, CpuBackend};
 use crate::core::utils::IteratorMutExt;
 
-pub const SECURE_EXTENSION_DEGREE: usize =
-    <SecureField as ExtensionOf<BaseField>>::EXTENSION_DEGREE;
-
 /// An array of `SECURE_EXTENSION_DEGREE` base field columns, that represents a column of secure
 /// field elements.
 #[derive(Clone, Debug)]
@@ -0,0 +1,206 @@
+use std::array;
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+pub use stwo_verifier::low_degree_test::*;
+use tracing::{span, Level};
+
+use super::channel::Channel;
+use super::fields::qm31::{SecureField, SECURE_EXTENSION_DEGREE};
+use super::fri::{FriConfig, FriOps, FriProver};
+use super::poly::circle::SecureEvaluation;
+use super::poly::twiddles::TwiddleTree;
+use super::vcs::ops::{MerkleHasher, MerkleOps};
+use super::vcs::prover::MerkleProver;
+
+/// Proves that `columns` are evaluations of circle polynomials within their degree bounds.
+///
+/// Each column's degree bound is its domain size divided by the blowup factor of `config`. The
+/// columns are committed to in a single Merkle tree, which is mixed into the channel before FRI.
+/// The returned proof contains the column values at the FRI query positions and can be checked
+/// with [verify_low_degree], given the log degree bounds of the columns in the same order.
+///
+/// Unlike [FriProver], no quotienting takes place: the columns are folded by FRI directly.
+///
+/// # Panics
+///
+/// Panics if:
+/// * `columns` is empty or not sorted in descending order by domain size.
+/// * A column's domain is not a canonic circle domain.
+/// * A column's domain is not larger than the last layer domain.
+/// * A column is not of sufficiently low degree.
+pub fn prove_low_degree<B: FriOps + MerkleOps<H>, H: MerkleHasher>(
+    channel: &mut impl Channel<Digest = H::Hash>,
+    config: FriConfig,
+    columns: &[SecureEvaluation<B>],
+    twiddles: &TwiddleTree<B>,
+) -> LowDegreeProof<H> {
+    let _span = span!(Level::INFO, "Low degree test").entered();
+    // Each column is committed as its coordinate columns.
+    let coordinate_columns = columns
+        .iter()
+        .flat_map(|column| column.columns.iter())
+        .collect_vec();
+    let tree = MerkleProver::<B, H>::commit(coordinate_columns.clone());
+    let commitment = tree.root();
+    channel.mix_digest(commitment);
+
+    let fri_prover = FriProver::<B, H>::commit(channel, config, columns, twiddles);
+    let (fri_proof, query_domain_per_log_size) = fri_prover.decommit(channel);
+
+    let queries_per_log_size: BTreeMap<u32, Vec<usize>> = query_domain_per_log_size
+        .iter()
+        .map(|(&log_size, domain)| (log_size, domain.flatten()))
+        .collect();
+    let (coordinate_values, decommitment) = tree.decommit(queries_per_log_size, coordinate_columns);
+    let queried_values = coordinate_values
+        .chunks(SECURE_EXTENSION_DEGREE)
+        .map(|coordinates| {
+            (0..coordinates[0].len())
+                .map(|i| SecureField::from_m31_array(array::from_fn(|j| coordinates[j][i])))
+                .collect()
+        })
+        .collect();
+
+    LowDegreeProof {
+        commitment,
+        queried_values,
+        decommitment,
+        fri_proof,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::array;
+
+    use itertools::Itertools;
+    use num_traits::One;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::{prove_low_degree, verify_low_degree, LowDegreeProof, LowDegreeTestError};
+    use crate::core::backend::cpu::CpuCirclePoly;
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::backend::CpuBackend;
+    use crate::core::fields::qm31::SecureField;
+    use crate::core::fields::secure_column::SecureColumn;
+    use crate::core::fri::FriConfig;
+    use crate::core::poly::circle::{CanonicCoset, PolyOps, SecureEvaluation};
+    use crate::core::serialization::Serializable;
+    use crate::core::test_utils::test_channel;
+    use crate::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+
+    const LOG_BLOWUP_FACTOR: u32 = 1;
+    const LOG_DEGREES: [u32; 4] = [8, 6, 6, 3];
+
+    #[test]
+    fn low_degree_columns_pass_verification() {
+        let columns =
+            LOG_DEGREES.map(|log_degree| random_evaluation(log_degree, LOG_BLOWUP_FACTOR));
+        let config = FriConfig::new(1, LOG_BLOWUP_FACTOR, 10).with_fold_step(2);
+        let proof = prove(config, &columns);
+
+        // The proof is self-contained, so it can be sent on its own.
+        let mut bytes = Vec::new();
+        proof.write_bytes(&mut bytes);
+        let proof =
+            LowDegreeProof::<Blake2sMerkleHasher>::read_bytes(&mut bytes.as_slice()).unwrap();
+
+        verify_low_degree(&mut test_channel(), config, &LOG_DEGREES, proof).unwrap();
+    }
+
+    #[test]
+    fn simd_low_degree_columns_pass_verification() {
+        // The SIMD backend can't fold small evaluations, so use larger domains.
+        const SIMD_LOG_BLOWUP_FACTOR: u32 = 2;
+        const SIMD_LOG_DEGREES: [u32; 4] = [10, 7, 7, 5];
+        let columns = SIMD_LOG_DEGREES.map(|log_degree| {
+            let column = random_evaluation(log_degree, SIMD_LOG_BLOWUP_FACTOR);
+            SecureEvaluation::<SimdBackend> {
+                domain: column.domain,
+                values: column.values.to_vec().into_iter().collect(),
+            }
+        });
+        let config = FriConfig::new(1, SIMD_LOG_BLOWUP_FACTOR, 10).with_fold_step(3);
+        let twiddles = SimdBackend::precompute_twiddles(columns[0].domain.half_coset);
+
+        let proof = prove_low_degree::<SimdBackend, Blake2sMerkleHasher>(
+            &mut test_channel(),
+            config,
+            &columns,
+            &twiddles,
+        );
+
+        verify_low_degree(&mut test_channel(), config, &SIMD_LOG_DEGREES, proof).unwrap();
+    }
+
+    #[test]
+    fn proof_with_invalid_queried_value_fails_verification() {
+        let columns =
+            LOG_DEGREES.map(|log_degree| random_evaluation(log_degree, LOG_BLOWUP_FACTOR));
+        let config = FriConfig::new(1, LOG_BLOWUP_FACTOR, 10);
+        let mut proof = prove(config, &columns);
+        proof.queried_values[1][0] += SecureField::one();
+
+        let result = verify_low_degree(&mut test_channel(), config, &LOG_DEGREES, proof);
+
+        assert!(matches!(result, Err(LowDegreeTestError::Merkle(_))));
+    }
+
+    #[test]
+    fn proof_with_missing_column_fails_verification() {
+        let columns =
+            LOG_DEGREES.map(|log_degree| random_evaluation(log_degree, LOG_BLOWUP_FACTOR));
+        let config = FriConfig::new(1, LOG_BLOWUP_FACTOR, 10);
+        let mut proof = prove(config, &columns);
+        proof.queried_values.pop();
+
+        let result = verify_low_degree(&mut test_channel(), config, &LOG_DEGREES, proof);
+
+        assert!(matches!(
+            result,
+            Err(LowDegreeTestError::NumQueriedColumns {
+                expected: 4,
+                actual: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn proof_for_higher_degree_bound_fails_verification() {
+        let columns =
+            LOG_DEGREES.map(|log_degree| random_evaluation(log_degree, LOG_BLOWUP_FACTOR));
+        let config = FriConfig::new(1, LOG_BLOWUP_FACTOR, 10);
+        let proof = prove(config, &columns);
+        let mut log_degree_bounds = LOG_DEGREES;
+        log_degree_bounds[0] += 1;
+
+        let result = verify_low_degree(&mut test_channel(), config, &log_degree_bounds, proof);
+
+        assert!(result.is_err());
+    }
+
+    fn prove(
+        config: FriConfig,
+        columns: &[SecureEvaluation<CpuBackend>],
+    ) -> LowDegreeProof<Blake2sMerkleHasher> {
+        let twiddles = CpuBackend::precompute_twiddles(columns[0].domain.half_coset);
+        prove_low_degree(&mut test_channel(), config, columns, &twiddles)
+    }
+
+    /// Returns an evaluation of a random secure polynomial with degree bound `2^log_degree` on a
+    /// canonic domain of size `2^(log_degree + log_blowup_factor)`.
+    fn random_evaluation(log_degree: u32, log_blowup_factor: u32) -> SecureEvaluation<CpuBackend> {
+        let mut rng = SmallRng::seed_from_u64(log_degree.into());
+        let domain = CanonicCoset::new(log_degree + log_blowup_factor).circle_domain();
+        let columns = array::from_fn(|_| {
+            let coeffs = (0..1 << log_degree).map(|_| rng.gen()).collect_vec();
+            CpuCirclePoly::new(coeffs).evaluate(domain).values
+        });
+        SecureEvaluation {
+            domain,
+            values: SecureColumn { columns },
+        }
+    }
+}
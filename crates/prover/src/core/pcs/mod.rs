@@ -8,9 +8,9 @@
 
 mod prover;
 pub mod quotients;
-mod utils;
-mod verifier;
 
-pub use self::prover::{CommitmentSchemeProof, CommitmentSchemeProver, CommitmentTreeProver};
-pub use self::utils::TreeVec;
-pub use self::verifier::CommitmentSchemeVerifier;
+pub use stwo_verifier::pcs::{
+    security, CommitmentSchemeProof, CommitmentSchemeVerifier, PcsConfig, TreeVec,
+};
+
+pub use self::prover::{CommitmentSchemeProver, CommitmentTreeProver, TreeCommitment};
@@ -1,64 +1,106 @@
 use std::collections::BTreeMap;
+use std::ops::Range;
 
+use educe::Educe;
 use itertools::Itertools;
 use tracing::{span, Level};
 
-use super::super::channel::Blake2sChannel;
 use super::super::circle::CirclePoint;
 use super::super::fields::m31::BaseField;
 use super::super::fields::qm31::SecureField;
-use super::super::fri::{FriConfig, FriProof, FriProver};
+use super::super::fields::FieldOps;
+use super::super::fri::FriProver;
 use super::super::poly::circle::CanonicCoset;
 use super::super::poly::BitReversedOrder;
-use super::super::proof_of_work::{ProofOfWork, ProofOfWorkProof};
-use super::super::prover::{
-    LOG_BLOWUP_FACTOR, LOG_LAST_LAYER_DEGREE_BOUND, N_QUERIES, PROOF_OF_WORK_BITS,
-};
+use super::super::proof_of_work::prove_pow;
 use super::super::ColumnVec;
 use super::quotients::{compute_fri_quotients, PointSample};
-use super::utils::TreeVec;
-use crate::core::backend::Backend;
-use crate::core::channel::Channel;
-use crate::core::poly::circle::{CircleEvaluation, CirclePoly};
+use super::{CommitmentSchemeProof, PcsConfig, TreeVec};
+use crate::core::backend::{BackendForChannel, Col, Column};
+use crate::core::channel::{Channel, MerkleChannel};
+use crate::core::poly::circle::{CircleEvaluation, CirclePoly, PolyOps};
 use crate::core::poly::twiddles::TwiddleTree;
-use crate::core::vcs::blake2_hash::Blake2sHash;
-use crate::core::vcs::blake2_merkle::Blake2sMerkleHasher;
-use crate::core::vcs::ops::MerkleOps;
+use crate::core::vcs::ops::{MerkleHasher, MerkleOps};
 use crate::core::vcs::prover::{MerkleDecommitment, MerkleProver};
-
-type MerkleHasher = Blake2sMerkleHasher;
-type ProofChannel = Blake2sChannel;
+use crate::core::vcs::streaming_prover::{ColumnSource, StreamingMerkleProver};
+use crate::core::vcs::verifier::MerkleConfig;
 
 /// The prover side of a FRI polynomial commitment scheme. See [super].
-pub struct CommitmentSchemeProver<B: Backend + MerkleOps<MerkleHasher>> {
-    pub trees: TreeVec<CommitmentTreeProver<B>>,
-    pub log_blowup_factor: u32,
+pub struct CommitmentSchemeProver<B: BackendForChannel<MC>, MC: MerkleChannel> {
+    pub trees: TreeVec<CommitmentTreeProver<B, MC>>,
+    pub config: PcsConfig,
+    /// If set, trees are committed with [CommitmentTreeProver::new_streaming], keeping this many
+    /// top layers of their Merkle trees.
+    pub streaming_top_layers: Option<usize>,
 }
 
-impl<B: Backend + MerkleOps<MerkleHasher>> CommitmentSchemeProver<B> {
-    pub fn new(log_blowup_factor: u32) -> Self {
+impl<B: BackendForChannel<MC>, MC: MerkleChannel> CommitmentSchemeProver<B, MC> {
+    pub fn new(config: PcsConfig) -> Self {
         CommitmentSchemeProver {
             trees: TreeVec::default(),
-            log_blowup_factor,
+            config,
+            streaming_top_layers: None,
+        }
+    }
+
+    /// Commits to the following trees keeping only the top `n_top_layers` layers of their Merkle
+    /// trees in memory. See [CommitmentTreeProver::new_streaming].
+    pub fn with_streaming_merkle(self, n_top_layers: usize) -> Self {
+        Self {
+            streaming_top_layers: Some(n_top_layers),
+            ..self
         }
     }
 
+    /// Commits to `polynomials` in a tree of the shape given for it in the config, see
+    /// [PcsConfig::merkle_config].
     pub fn commit(
         &mut self,
         polynomials: ColumnVec<CirclePoly<B>>,
-        channel: &mut ProofChannel,
+        channel: &mut MC::C,
         twiddles: &TwiddleTree<B>,
     ) {
         let _span = span!(Level::INFO, "Commitment").entered();
-        let tree =
-            CommitmentTreeProver::new(polynomials, self.log_blowup_factor, channel, twiddles);
+        let log_blowup_factor = self.config.fri_config.log_blowup_factor;
+        let merkle_config = self.config.merkle_config(self.trees.len());
+        let tree = match self.streaming_top_layers {
+            None => CommitmentTreeProver::new_with_merkle_config(
+                polynomials,
+                log_blowup_factor,
+                merkle_config,
+                twiddles,
+            ),
+            Some(n_top_layers) => CommitmentTreeProver::new_streaming(
+                polynomials,
+                log_blowup_factor,
+                merkle_config,
+                n_top_layers,
+                twiddles,
+            ),
+        };
+        self.commit_tree(tree, channel);
+    }
+
+    /// Adds an already committed tree to the commitment scheme, e.g. one that was computed once
+    /// and is shared between proofs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shape of the tree is not the one given for it in the config.
+    pub fn commit_tree(&mut self, tree: CommitmentTreeProver<B, MC>, channel: &mut MC::C) {
+        assert_eq!(
+            tree.commitment.config(),
+            self.config.merkle_config(self.trees.len()),
+            "Merkle config mismatch."
+        );
+        MC::mix_cap(channel, &tree.commitment.cap());
         self.trees.push(tree);
     }
 
     pub fn commit_on_evals(
         &mut self,
         evals: ColumnVec<CircleEvaluation<B, BaseField, BitReversedOrder>>,
-        channel: &mut ProofChannel,
+        channel: &mut MC::C,
         twiddles: &TwiddleTree<B>,
     ) {
         let span = span!(Level::INFO, "Interpolation for commitment").entered();
@@ -70,8 +112,9 @@ impl<B: Backend + MerkleOps<MerkleHasher>> CommitmentSchemeProver<B> {
         self.commit(polys, channel, twiddles);
     }
 
-    pub fn roots(&self) -> TreeVec<Blake2sHash> {
-        self.trees.as_ref().map(|tree| tree.commitment.root())
+    /// Returns the caps of the trees, see [PcsConfig::merkle_config].
+    pub fn caps(&self) -> TreeVec<Vec<<MC::H as MerkleHasher>::Hash>> {
+        self.trees.as_ref().map(|tree| tree.commitment.cap())
     }
 
     pub fn polynomials(&self) -> TreeVec<ColumnVec<&CirclePoly<B>>> {
@@ -89,9 +132,9 @@ impl<B: Backend + MerkleOps<MerkleHasher>> CommitmentSchemeProver<B> {
     pub fn prove_values(
         &self,
         sampled_points: TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>>,
-        channel: &mut ProofChannel,
+        channel: &mut MC::C,
         twiddles: &TwiddleTree<B>,
-    ) -> CommitmentSchemeProof {
+    ) -> CommitmentSchemeProof<MC::H> {
         // Evaluate polynomials on open points.
         let span = span!(Level::INFO, "Evaluate columns out of domain").entered();
         let samples = self
@@ -114,15 +157,19 @@ impl<B: Backend + MerkleOps<MerkleHasher>> CommitmentSchemeProver<B> {
 
         // Compute oods quotients for boundary constraints on the sampled points.
         let columns = self.evaluations().flatten();
-        let quotients = compute_fri_quotients(&columns, &samples.flatten(), channel.draw_felt());
+        let quotients = compute_fri_quotients(
+            &columns,
+            &samples.flatten(),
+            channel.draw_felt(),
+            self.config.fri_config.log_blowup_factor,
+        );
 
         // Run FRI commitment phase on the oods quotients.
-        let fri_config = FriConfig::new(LOG_LAST_LAYER_DEGREE_BOUND, LOG_BLOWUP_FACTOR, N_QUERIES);
         let fri_prover =
-            FriProver::<B, MerkleHasher>::commit(channel, fri_config, &quotients, twiddles);
+            FriProver::<B, MC::H>::commit(channel, self.config.fri_config, &quotients, twiddles);
 
         // Proof of work.
-        let proof_of_work = ProofOfWork::new(PROOF_OF_WORK_BITS).prove(channel);
+        let proof_of_work = prove_pow::<B, _>(channel, self.config.pow_bits);
 
         // FRI decommitment phase.
         let (fri_proof, fri_query_domains) = fri_prover.decommit(channel);
@@ -140,6 +187,7 @@ impl<B: Backend + MerkleOps<MerkleHasher>> CommitmentSchemeProver<B> {
         let decommitments = decommitment_results.map(|(_, d)| d);
 
         CommitmentSchemeProof {
+            config: self.config.clone(),
             sampled_values,
             decommitments,
             queried_values,
@@ -149,51 +197,74 @@ impl<B: Backend + MerkleOps<MerkleHasher>> CommitmentSchemeProver<B> {
     }
 }
 
-#[derive(Debug)]
-pub struct CommitmentSchemeProof {
-    pub sampled_values: TreeVec<ColumnVec<Vec<SecureField>>>,
-    pub decommitments: TreeVec<MerkleDecommitment<MerkleHasher>>,
-    pub queried_values: TreeVec<ColumnVec<Vec<BaseField>>>,
-    pub proof_of_work: ProofOfWorkProof,
-    pub fri_proof: FriProof<MerkleHasher>,
-}
-
 /// Prover data for a single commitment tree in a commitment scheme. The commitment scheme allows to
 /// commit on a set of polynomials at a time. This corresponds to such a set.
-pub struct CommitmentTreeProver<B: Backend + MerkleOps<MerkleHasher>> {
+#[derive(Educe)]
+#[educe(Clone)]
+pub struct CommitmentTreeProver<B: BackendForChannel<MC>, MC: MerkleChannel> {
     pub polynomials: ColumnVec<CirclePoly<B>>,
     pub evaluations: ColumnVec<CircleEvaluation<B, BaseField, BitReversedOrder>>,
-    pub commitment: MerkleProver<B, MerkleHasher>,
+    pub commitment: TreeCommitment<B, MC::H>,
 }
 
-impl<B: Backend + MerkleOps<MerkleHasher>> CommitmentTreeProver<B> {
-    fn new(
+impl<B: BackendForChannel<MC>, MC: MerkleChannel> CommitmentTreeProver<B, MC> {
+    pub fn new(
         polynomials: ColumnVec<CirclePoly<B>>,
         log_blowup_factor: u32,
-        channel: &mut ProofChannel,
         twiddles: &TwiddleTree<B>,
     ) -> Self {
-        let span = span!(Level::INFO, "Extension").entered();
-        let evaluations = polynomials
-            .iter()
-            .map(|poly| {
-                poly.evaluate_with_twiddles(
-                    CanonicCoset::new(poly.log_size() + log_blowup_factor).circle_domain(),
-                    twiddles,
-                )
-            })
-            .collect_vec();
+        Self::new_with_merkle_config(
+            polynomials,
+            log_blowup_factor,
+            MerkleConfig::default(),
+            twiddles,
+        )
+    }
 
-        span.exit();
+    /// Commits to `polynomials` in a tree of shape `merkle_config`. See [MerkleConfig].
+    pub fn new_with_merkle_config(
+        polynomials: ColumnVec<CirclePoly<B>>,
+        log_blowup_factor: u32,
+        merkle_config: MerkleConfig,
+        twiddles: &TwiddleTree<B>,
+    ) -> Self {
+        let evaluations = extend(&polynomials, log_blowup_factor, twiddles);
+
+        let _span = span!(Level::INFO, "Merkle").entered();
+        let tree = MerkleProver::commit_with_config(
+            evaluations.iter().map(|eval| &eval.values).collect(),
+            merkle_config,
+        );
+
+        CommitmentTreeProver {
+            polynomials,
+            evaluations,
+            commitment: TreeCommitment::Full(tree),
+        }
+    }
+
+    /// Like [Self::new_with_merkle_config], but only keeps the top `n_top_layers` layers of the
+    /// Merkle tree, or all of them if the tree is smaller, and reads the evaluations through
+    /// [ColumnSource] to recompute the rest when decommitting. See [StreamingMerkleProver].
+    pub fn new_streaming(
+        polynomials: ColumnVec<CirclePoly<B>>,
+        log_blowup_factor: u32,
+        merkle_config: MerkleConfig,
+        n_top_layers: usize,
+        twiddles: &TwiddleTree<B>,
+    ) -> Self {
+        let evaluations = extend(&polynomials, log_blowup_factor, twiddles);
 
         let _span = span!(Level::INFO, "Merkle").entered();
-        let tree = MerkleProver::commit(evaluations.iter().map(|eval| &eval.values).collect());
-        channel.mix_digest(tree.root());
+        let source = EvaluationSource(&evaluations);
+        let n_layers = merkle_config.layers(&source.column_log_sizes()).len();
+        let tree =
+            StreamingMerkleProver::commit(&source, n_top_layers.min(n_layers), merkle_config);
 
         CommitmentTreeProver {
             polynomials,
             evaluations,
-            commitment: tree,
+            commitment: TreeCommitment::Streaming(tree),
         }
     }
 
@@ -204,12 +275,202 @@ impl<B: Backend + MerkleOps<MerkleHasher>> CommitmentTreeProver<B> {
     fn decommit(
         &self,
         queries: BTreeMap<u32, Vec<usize>>,
-    ) -> (ColumnVec<Vec<BaseField>>, MerkleDecommitment<MerkleHasher>) {
-        let eval_vec = self
-            .evaluations
+    ) -> (ColumnVec<Vec<BaseField>>, MerkleDecommitment<MC::H>) {
+        match &self.commitment {
+            TreeCommitment::Full(tree) => {
+                let eval_vec = self
+                    .evaluations
+                    .iter()
+                    .map(|eval| &eval.values)
+                    .collect_vec();
+                tree.decommit(queries, eval_vec)
+            }
+            TreeCommitment::Streaming(tree) => {
+                tree.decommit(queries, &EvaluationSource(&self.evaluations))
+            }
+        }
+    }
+}
+
+/// Evaluates `polynomials` on their domains extended by `2^log_blowup_factor`.
+fn extend<B: PolyOps>(
+    polynomials: &[CirclePoly<B>],
+    log_blowup_factor: u32,
+    twiddles: &TwiddleTree<B>,
+) -> ColumnVec<CircleEvaluation<B, BaseField, BitReversedOrder>> {
+    let _span = span!(Level::INFO, "Extension").entered();
+    polynomials
+        .iter()
+        .map(|poly| {
+            poly.evaluate_with_twiddles(
+                CanonicCoset::new(poly.log_size() + log_blowup_factor).circle_domain(),
+                twiddles,
+            )
+        })
+        .collect_vec()
+}
+
+/// The Merkle commitment of a [CommitmentTreeProver].
+#[derive(Educe)]
+#[educe(Clone)]
+pub enum TreeCommitment<B: MerkleOps<H>, H: MerkleHasher> {
+    /// A tree with all its layers in memory.
+    Full(MerkleProver<B, H>),
+    /// A tree with only its top layers in memory.
+    Streaming(StreamingMerkleProver<B, H>),
+}
+
+impl<B: MerkleOps<H>, H: MerkleHasher> TreeCommitment<B, H> {
+    /// Returns the shape of the tree.
+    pub fn config(&self) -> MerkleConfig {
+        match self {
+            Self::Full(tree) => tree.config,
+            Self::Streaming(tree) => tree.config,
+        }
+    }
+
+    /// Returns the nodes of the top layer of the tree, see [MerkleConfig].
+    pub fn cap(&self) -> Vec<H::Hash> {
+        match self {
+            Self::Full(tree) => tree.cap(),
+            Self::Streaming(tree) => tree.cap(),
+        }
+    }
+}
+
+/// The evaluations of a [CommitmentTreeProver], as read by its [StreamingMerkleProver].
+struct EvaluationSource<'a, B: FieldOps<BaseField>>(
+    &'a [CircleEvaluation<B, BaseField, BitReversedOrder>],
+);
+
+impl<B: FieldOps<BaseField>> ColumnSource<B> for EvaluationSource<'_, B> {
+    fn column_log_sizes(&self) -> Vec<u32> {
+        self.0.iter().map(|eval| eval.domain.log_size()).collect()
+    }
+
+    fn read_rows(&self, column: usize, rows: Range<usize>) -> Col<B, BaseField> {
+        self.0[column].values.copy_range(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter::zip;
+
+    use itertools::Itertools;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::CommitmentSchemeProver;
+    use crate::core::backend::cpu::CpuCircleEvaluation;
+    use crate::core::backend::CpuBackend;
+    use crate::core::circle::CirclePoint;
+    use crate::core::pcs::{CommitmentSchemeVerifier, PcsConfig, TreeVec};
+    use crate::core::poly::circle::{CanonicCoset, PolyOps};
+    use crate::core::test_utils::test_channel;
+    use crate::core::vcs::blake2_merkle::Blake2sMerkleChannel;
+    use crate::core::vcs::verifier::MerkleConfig;
+
+    #[test]
+    fn test_commit_with_merkle_cap() {
+        const LOG_SIZE: u32 = 5;
+        const N_COLUMNS: usize = 3;
+        let mut rng = SmallRng::seed_from_u64(0);
+        // The second tree commits to a cap of 4 nodes.
+        let config = PcsConfig::default()
+            .with_merkle_config(1, MerkleConfig::default().with_log_cap_size(2));
+        let twiddles = CpuBackend::precompute_twiddles(
+            CanonicCoset::new(LOG_SIZE + config.fri_config.log_blowup_factor)
+                .circle_domain()
+                .half_coset,
+        );
+        let domain = CanonicCoset::new(LOG_SIZE).circle_domain();
+        let polys = (0..N_COLUMNS)
+            .map(|_| {
+                let values = (0..1 << LOG_SIZE).map(|_| rng.gen()).collect_vec();
+                CpuCircleEvaluation::new(domain, values).interpolate()
+            })
+            .collect_vec();
+        let log_sizes = [LOG_SIZE; N_COLUMNS];
+
+        // Commit to the same columns, once to a root and once to a cap.
+        let channel = &mut test_channel();
+        let mut commitment_scheme =
+            CommitmentSchemeProver::<CpuBackend, Blake2sMerkleChannel>::new(config.clone());
+        commitment_scheme.commit(polys.clone(), channel, &twiddles);
+        commitment_scheme.commit(polys, channel, &twiddles);
+        let point = CirclePoint::get_random_point(channel);
+        let sampled_points = TreeVec::new(vec![vec![vec![point]; N_COLUMNS]; 2]);
+        let proof = commitment_scheme.prove_values(sampled_points.clone(), channel, &twiddles);
+        let caps = commitment_scheme.caps();
+
+        let channel = &mut test_channel();
+        let mut commitment_scheme = CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
+        commitment_scheme.commit(caps[0].clone(), &log_sizes, channel);
+        commitment_scheme.commit(caps[1].clone(), &log_sizes, channel);
+        assert_eq!(CirclePoint::get_random_point(channel), point);
+
+        assert_eq!(caps[1].len(), 4);
+        assert!(
+            proof.decommitments[1].hash_witness.len() < proof.decommitments[0].hash_witness.len()
+        );
+        commitment_scheme
+            .verify_values(sampled_points, proof, channel)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_streaming_commit_matches_commit() {
+        const N_COLUMNS: usize = 4;
+        let mut rng = SmallRng::seed_from_u64(0);
+        let config = PcsConfig::default()
+            .with_merkle_config(1, MerkleConfig::new(2, 1).with_log_cap_size(1));
+        let log_sizes = (0..N_COLUMNS).map(|_| rng.gen_range(3..7)).collect_vec();
+        let max_log_size = log_sizes.iter().max().unwrap();
+        let twiddles = CpuBackend::precompute_twiddles(
+            CanonicCoset::new(max_log_size + config.fri_config.log_blowup_factor)
+                .circle_domain()
+                .half_coset,
+        );
+        let polys = log_sizes
             .iter()
-            .map(|eval| &eval.values)
+            .map(|&log_size| {
+                let domain = CanonicCoset::new(log_size).circle_domain();
+                let values = (0..1 << log_size).map(|_| rng.gen()).collect_vec();
+                CpuCircleEvaluation::new(domain, values).interpolate()
+            })
             .collect_vec();
-        self.commitment.decommit(queries, eval_vec)
+        let prove =
+            |mut commitment_scheme: CommitmentSchemeProver<CpuBackend, Blake2sMerkleChannel>| {
+                let channel = &mut test_channel();
+                commitment_scheme.commit(polys.clone(), channel, &twiddles);
+                commitment_scheme.commit(polys.clone(), channel, &twiddles);
+                let point = CirclePoint::get_random_point(channel);
+                let sampled_points = TreeVec::new(vec![vec![vec![point]; N_COLUMNS]; 2]);
+                let proof = commitment_scheme.prove_values(sampled_points, channel, &twiddles);
+                (commitment_scheme.caps(), proof)
+            };
+
+        let (caps, proof) = prove(CommitmentSchemeProver::new(config.clone()));
+        for n_top_layers in 1..=4 {
+            let (streaming_caps, streaming_proof) = prove(
+                CommitmentSchemeProver::new(config.clone()).with_streaming_merkle(n_top_layers),
+            );
+
+            assert_eq!(streaming_caps, caps);
+            assert_eq!(streaming_proof.queried_values, proof.queried_values);
+            for (streaming_decommitment, decommitment) in
+                zip(&streaming_proof.decommitments.0, &proof.decommitments.0)
+            {
+                assert_eq!(
+                    streaming_decommitment.hash_witness,
+                    decommitment.hash_witness
+                );
+                assert_eq!(
+                    streaming_decommitment.column_witness,
+                    decommitment.column_witness
+                );
+            }
+        }
     }
 }
@@ -3,7 +3,7 @@ pub mod line;
 // TODO(spapini): Remove pub, when LinePoly moved to the backend as well, and we can move the fold
 // function there.
 pub mod twiddles;
-pub mod utils;
+pub use stwo_verifier::poly::utils;
 
 /// Bit-reversed evaluation ordering.
 #[derive(Copy, Clone, Debug)]
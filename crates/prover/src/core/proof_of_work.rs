@@ -1,134 +1,55 @@
-use thiserror::Error;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+pub use stwo_verifier::proof_of_work::*;
 use tracing::{span, Level};
 
-use super::channel::Blake2sChannel;
-use crate::core::channel::Channel;
-use crate::core::vcs::blake2_hash::{Blake2sHash, Blake2sHasher};
-use crate::core::vcs::hasher::Hasher;
+use super::channel::Channel;
 
-// TODO(ShaharS): generalize to more channels and create a from function in the hash traits.
-pub struct ProofOfWork {
-    // Proof of work difficulty.
-    pub n_bits: u32,
-}
-
-#[derive(Clone, Debug)]
-pub struct ProofOfWorkProof {
-    pub nonce: u64,
-}
-
-impl ProofOfWork {
-    pub fn new(n_bits: u32) -> Self {
-        Self { n_bits }
-    }
-
-    pub fn prove(&self, channel: &mut Blake2sChannel) -> ProofOfWorkProof {
-        let _span = span!(Level::INFO, "Proof of work").entered();
-        let seed = channel.get_digest().as_ref().to_vec();
-        let proof = self.grind(seed);
-        channel.mix_nonce(proof.nonce);
-        proof
-    }
-
-    pub fn verify(
-        &self,
-        channel: &mut Blake2sChannel,
-        proof: &ProofOfWorkProof,
-    ) -> Result<(), ProofOfWorkVerificationError> {
-        let seed = channel.get_digest().as_ref().to_vec();
-        let verified = check_leading_zeros(
-            self.hash_with_nonce(&seed, proof.nonce).as_ref(),
-            self.n_bits,
-        );
-
-        if !verified {
-            return Err(ProofOfWorkVerificationError::ProofOfWorkVerificationFailed);
-        }
-
-        channel.mix_nonce(proof.nonce);
-        Ok(())
-    }
+/// Log of the number of consecutive nonces checked by a single grinding task.
+pub const LOG_NONCES_PER_TASK: u32 = 12;
 
-    fn grind(&self, seed: Vec<u8>) -> ProofOfWorkProof {
-        let mut nonce = 0u64;
-        // TODO(ShaharS): naive implementation, should be replaced with a parallel one.
-        loop {
-            let hash = self.hash_with_nonce(&seed, nonce);
-            if check_leading_zeros(hash.as_ref(), self.n_bits) {
-                return ProofOfWorkProof { nonce };
-            }
-            nonce += 1;
-        }
-    }
-
-    fn hash_with_nonce(&self, seed: &[u8], nonce: u64) -> Blake2sHash {
-        let hash_input = seed
-            .iter()
-            .chain(nonce.to_le_bytes().iter())
-            .cloned()
-            .collect::<Vec<_>>();
-        Blake2sHasher::hash(&hash_input)
-    }
+/// Proof of work grinding for a [Channel].
+///
+/// Backends implement it for the channels whose nonce check they can accelerate, e.g. by testing
+/// multiple nonces at once. All implementations find the same nonce, so proofs don't depend on the
+/// backend or on the `parallel` feature.
+pub trait GrindOps<C: Channel> {
+    /// Returns the smallest nonce for which `channel.verify_pow_nonce(pow_bits, nonce)` holds.
+    fn grind(channel: &C, pow_bits: u32) -> u64;
 }
 
-/// Check that the prefix leading zeros is greater than `bound_bits`.
-fn check_leading_zeros(bytes: &[u8], bound_bits: u32) -> bool {
-    let mut n_bits = 0;
-    // bytes are in little endian order.
-    for byte in bytes.iter().rev() {
-        if *byte == 0 {
-            n_bits += 8;
-        } else {
-            n_bits += byte.leading_zeros();
-            break;
-        }
-    }
-    n_bits >= bound_bits
+/// Grinds a proof of work with the backend `B` and mixes its nonce into the channel. The prover
+/// side of [ProofOfWork::verify].
+pub fn prove_pow<B: GrindOps<C>, C: Channel>(channel: &mut C, pow_bits: u32) -> ProofOfWorkProof {
+    let _span = span!(Level::INFO, "Proof of work").entered();
+    let nonce = B::grind(channel, pow_bits);
+    channel.mix_nonce(nonce);
+    ProofOfWorkProof { nonce }
 }
 
-#[derive(Clone, Copy, Debug, Error)]
-pub enum ProofOfWorkVerificationError {
-    #[error("Proof of work verification failed.")]
-    ProofOfWorkVerificationFailed,
+/// Returns the first nonce found by `find_in_task`, searching tasks in increasing order.
+///
+/// Task `i` covers the nonces `[i * 2^LOG_NONCES_PER_TASK, (i + 1) * 2^LOG_NONCES_PER_TASK)` and
+/// should return the smallest valid nonce among them, if any. With the `parallel` feature, tasks
+/// are split across threads.
+///
+/// # Panics
+///
+/// Panics if there is no valid nonce.
+pub fn grind_tasks(find_in_task: impl Fn(u64) -> Option<u64> + Sync + Send) -> u64 {
+    let n_tasks = 1u64 << (u64::BITS - LOG_NONCES_PER_TASK);
+
+    #[cfg(not(feature = "parallel"))]
+    let nonce = (0..n_tasks).find_map(find_in_task);
+
+    #[cfg(feature = "parallel")]
+    let nonce = (0..n_tasks).into_par_iter().find_map_first(find_in_task);
+
+    nonce.expect("no proof of work nonce found")
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::core::channel::{Blake2sChannel, Channel};
-    use crate::core::proof_of_work::{ProofOfWork, ProofOfWorkProof};
-    use crate::core::vcs::blake2_hash::Blake2sHash;
-
-    #[test]
-    fn test_verify_proof_of_work_success() {
-        let mut channel = Blake2sChannel::new(Blake2sHash::from(vec![0; 32]));
-        let proof_of_work_prover = ProofOfWork { n_bits: 11 };
-        let proof = ProofOfWorkProof { nonce: 133 };
-
-        proof_of_work_prover.verify(&mut channel, &proof).unwrap();
-    }
-
-    #[test]
-    fn test_verify_proof_of_work_fail() {
-        let mut channel = Blake2sChannel::new(Blake2sHash::from(vec![0; 32]));
-        let proof_of_work_prover = ProofOfWork { n_bits: 1 };
-        let invalid_proof = ProofOfWorkProof { nonce: 0 };
-
-        proof_of_work_prover
-            .verify(&mut channel, &invalid_proof)
-            .unwrap_err();
-    }
-
-    #[test]
-    fn test_proof_of_work() {
-        let n_bits = 12;
-        let mut prover_channel = Blake2sChannel::new(Blake2sHash::default());
-        let mut verifier_channel = Blake2sChannel::new(Blake2sHash::default());
-        let prover = ProofOfWork::new(n_bits);
-        let verifier = ProofOfWork::new(n_bits);
-
-        let proof = prover.prove(&mut prover_channel);
-        verifier.verify(&mut verifier_channel, &proof).unwrap();
-
-        assert_eq!(prover_channel.get_digest(), verifier_channel.get_digest());
-    }
+/// Returns the nonces covered by grinding task `task`. See [grind_tasks].
+pub fn task_nonces(task: u64) -> impl Iterator<Item = u64> {
+    let start = task << LOG_NONCES_PER_TASK;
+    start..start + (1 << LOG_NONCES_PER_TASK)
 }
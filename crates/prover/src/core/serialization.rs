@@ -0,0 +1,113 @@
+pub use stwo_verifier::serialization::*;
+
+#[cfg(test)]
+mod tests {
+    use super::{DeserializationError, Serializable, PROOF_FORMAT_VERSION};
+    use crate::core::fields::m31::{BaseField, P};
+    use crate::core::fields::qm31::SecureField;
+    use crate::core::prover::StarkProof;
+    use crate::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+    use crate::examples::fibonacci::Fibonacci;
+    use crate::m31;
+
+    fn fib_proof() -> (Fibonacci, StarkProof<Blake2sMerkleHasher>) {
+        let fib = Fibonacci::new(5, m31!(443693538));
+        let proof = fib.prove().unwrap();
+        (fib, proof)
+    }
+
+    #[test]
+    fn test_proof_round_trip() {
+        let (fib, proof) = fib_proof();
+        let bytes = proof.to_bytes();
+
+        let decoded = StarkProof::<Blake2sMerkleHasher>::from_bytes(&bytes).unwrap();
+
+        assert_eq!(decoded.to_bytes(), bytes);
+        fib.verify(decoded).unwrap();
+    }
+
+    #[test]
+    fn test_size_breakdown_sums_to_proof_size() {
+        let (_, proof) = fib_proof();
+
+        let breakdown = proof.size_breakdown();
+
+        assert_eq!(breakdown.total(), proof.to_bytes().len());
+        assert_eq!(breakdown.sampled_values.len(), proof.commitments.len());
+        assert_eq!(
+            breakdown.fri_inner_layers.len(),
+            proof.commitment_scheme_proof.fri_proof.inner_layers.len()
+        );
+        // OODS and query nonces, and the length of the empty interaction nonces.
+        assert_eq!(breakdown.proof_of_work, 4 + 8 + 8);
+    }
+
+    #[test]
+    fn test_non_canonical_base_field_fails() {
+        let mut bytes = Vec::new();
+        P.write_bytes(&mut bytes);
+
+        let result = BaseField::read_bytes(&mut &bytes[..]);
+
+        assert_eq!(result, Err(DeserializationError::NonCanonicalFieldElement));
+    }
+
+    #[test]
+    fn test_non_canonical_secure_field_fails() {
+        let mut bytes = Vec::new();
+        SecureField::from_u32_unchecked(1, 2, 3, 4).write_bytes(&mut bytes);
+        bytes[12..].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        let result = SecureField::read_bytes(&mut &bytes[..]);
+
+        assert_eq!(result, Err(DeserializationError::NonCanonicalFieldElement));
+    }
+
+    #[test]
+    fn test_trailing_bytes_fails() {
+        let (_, proof) = fib_proof();
+        let mut bytes = proof.to_bytes();
+        bytes.push(0);
+
+        let result = StarkProof::<Blake2sMerkleHasher>::from_bytes(&bytes);
+
+        assert_eq!(result.unwrap_err(), DeserializationError::TrailingBytes(1));
+    }
+
+    #[test]
+    fn test_truncated_proof_fails() {
+        let (_, proof) = fib_proof();
+        let bytes = proof.to_bytes();
+
+        let result = StarkProof::<Blake2sMerkleHasher>::from_bytes(&bytes[..bytes.len() - 1]);
+
+        assert_eq!(result.unwrap_err(), DeserializationError::UnexpectedEnd);
+    }
+
+    #[test]
+    fn test_unsupported_version_fails() {
+        let (_, proof) = fib_proof();
+        let mut bytes = proof.to_bytes();
+        bytes[0] = PROOF_FORMAT_VERSION + 1;
+
+        let result = StarkProof::<Blake2sMerkleHasher>::from_bytes(&bytes);
+
+        assert_eq!(
+            result.unwrap_err(),
+            DeserializationError::UnsupportedVersion(PROOF_FORMAT_VERSION + 1)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let (fib, proof) = fib_proof();
+
+        let json = serde_json::to_string(&proof).unwrap();
+        let decoded: StarkProof<Blake2sMerkleHasher> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(decoded.to_bytes(), proof.to_bytes());
+        fib.verify(decoded).unwrap();
+    }
+}
@@ -1,50 +1,25 @@
-use num_traits::Zero;
-
-use super::blake2_hash::Blake2sHash;
-use super::blake2s_ref::compress;
-use super::ops::MerkleHasher;
-use crate::core::fields::m31::BaseField;
-
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
-pub struct Blake2sMerkleHasher;
-impl MerkleHasher for Blake2sMerkleHasher {
-    type Hash = Blake2sHash;
-
-    fn hash_node(
-        children_hashes: Option<(Self::Hash, Self::Hash)>,
-        column_values: &[BaseField],
-    ) -> Self::Hash {
-        let mut state = [0; 8];
-        if let Some((left, right)) = children_hashes {
-            state = compress(
-                state,
-                unsafe { std::mem::transmute([left, right]) },
-                0,
-                0,
-                0,
-                0,
-            );
-        }
-        let rem = 15 - ((column_values.len() + 15) % 16);
-        let padded_values = column_values
-            .iter()
-            .copied()
-            .chain(std::iter::repeat(BaseField::zero()).take(rem));
-        for chunk in padded_values.array_chunks::<16>() {
-            state = compress(state, unsafe { std::mem::transmute(chunk) }, 0, 0, 0, 0);
-        }
-        state.map(|x| x.to_le_bytes()).flatten().into()
-    }
-}
+pub use stwo_verifier::vcs::blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher};
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
+    use itertools::Itertools;
     use num_traits::Zero;
 
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::backend::{Col, CpuBackend};
     use crate::core::fields::m31::BaseField;
-    use crate::core::vcs::blake2_merkle::{Blake2sHash, Blake2sMerkleHasher};
-    use crate::core::vcs::test_utils::prepare_merkle;
-    use crate::core::vcs::verifier::MerkleVerificationError;
+    use crate::core::serialization::Serializable;
+    use crate::core::vcs::blake2_hash::Blake2sHash;
+    use crate::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+    use crate::core::vcs::prover::MerkleProver;
+    use crate::core::vcs::test_utils::{
+        prepare_merkle, prepare_merkle_opening, prepare_merkle_with_config,
+    };
+    use crate::core::vcs::verifier::{
+        MerkleConfig, MerkleOpening, MerkleVerificationError, MerkleVerifier,
+    };
 
     #[test]
     fn test_merkle_success() {
@@ -118,4 +93,229 @@ mod tests {
             MerkleVerificationError::ColumnValuesTooShort
         );
     }
+
+    #[test]
+    fn test_merkle_empty_tree() {
+        let merkle = MerkleProver::<CpuBackend, Blake2sMerkleHasher>::commit(vec![]);
+        let (values, decommitment) = merkle.decommit(BTreeMap::new(), vec![]);
+        let verifier = MerkleVerifier::<Blake2sMerkleHasher>::new(merkle.root(), vec![]);
+
+        verifier
+            .verify(BTreeMap::new(), values, decommitment)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_merkle_opening_success() {
+        let (rows, opening, cols, verifier) = prepare_merkle_opening::<Blake2sMerkleHasher>();
+        let mut bytes = Vec::new();
+        opening.write_bytes(&mut bytes);
+        let opening = MerkleOpening::read_bytes(&mut bytes.as_slice()).unwrap();
+
+        let values = verifier.verify_opening(&rows, opening).unwrap();
+
+        for ((column_rows, column_values), col) in rows.iter().zip(values).zip(cols) {
+            let expected_values = column_rows.iter().map(|&row| col[row]).collect_vec();
+            assert_eq!(column_values, expected_values);
+        }
+    }
+
+    #[test]
+    fn test_merkle_opening_no_rows() {
+        let (rows, _, cols, verifier) = prepare_merkle_opening::<Blake2sMerkleHasher>();
+        let rows = vec![vec![]; rows.len()];
+        let merkle = MerkleProver::<CpuBackend, Blake2sMerkleHasher>::commit(cols.iter().collect());
+        let opening = merkle.open(&rows, cols.iter().collect());
+
+        let values = verifier.verify_opening(&rows, opening).unwrap();
+
+        assert!(values.iter().all(|column_values| column_values.is_empty()));
+    }
+
+    #[test]
+    fn test_merkle_opening_invalid_value() {
+        let (rows, mut opening, _, verifier) = prepare_merkle_opening::<Blake2sMerkleHasher>();
+        let column = rows
+            .iter()
+            .position(|column_rows| !column_rows.is_empty())
+            .unwrap();
+        opening.queried_values[column][0] += BaseField::from(1);
+
+        assert_eq!(
+            verifier.verify_opening(&rows, opening).unwrap_err(),
+            MerkleVerificationError::RootMismatch
+        );
+    }
+
+    #[test]
+    fn test_merkle_opening_row_out_of_bounds() {
+        let (mut rows, opening, _, verifier) = prepare_merkle_opening::<Blake2sMerkleHasher>();
+        rows[0].push(1 << verifier.column_log_sizes[0]);
+
+        assert_eq!(
+            verifier.verify_opening(&rows, opening).unwrap_err(),
+            MerkleVerificationError::RowOutOfBounds
+        );
+    }
+
+    #[test]
+    fn test_merkle_opening_num_columns_mismatch() {
+        let (mut rows, opening, _, verifier) = prepare_merkle_opening::<Blake2sMerkleHasher>();
+        rows.pop();
+
+        assert_eq!(
+            verifier.verify_opening(&rows, opening).unwrap_err(),
+            MerkleVerificationError::NumColumnsMismatch
+        );
+    }
+
+    #[test]
+    fn test_merkle_opening_column_values_too_long() {
+        let (rows, mut opening, _, verifier) = prepare_merkle_opening::<Blake2sMerkleHasher>();
+        opening.queried_values[0].push(BaseField::zero());
+
+        assert_eq!(
+            verifier.verify_opening(&rows, opening).unwrap_err(),
+            MerkleVerificationError::ColumnValuesTooLong
+        );
+    }
+
+    #[test]
+    fn test_merkle_opening_witness_too_long() {
+        let (rows, mut opening, _, verifier) = prepare_merkle_opening::<Blake2sMerkleHasher>();
+        opening
+            .decommitment
+            .hash_witness
+            .push(Blake2sHash::default());
+
+        assert_eq!(
+            verifier.verify_opening(&rows, opening).unwrap_err(),
+            MerkleVerificationError::WitnessTooLong
+        );
+    }
+
+    #[test]
+    fn test_merkle_with_config_success() {
+        for (log_arity, log_leaf_rows) in [(2, 0), (3, 0), (1, 2), (2, 1), (3, 4)] {
+            let config = MerkleConfig::new(log_arity, log_leaf_rows);
+            let (queries, decommitment, values, verifier) =
+                prepare_merkle_with_config::<Blake2sMerkleHasher>(config);
+
+            verifier.verify(queries, values, decommitment).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_merkle_with_config_invalid_value() {
+        let config = MerkleConfig::new(2, 1);
+        let (queries, decommitment, mut values, verifier) =
+            prepare_merkle_with_config::<Blake2sMerkleHasher>(config);
+        values[3][2] = BaseField::zero();
+
+        assert_eq!(
+            verifier.verify(queries, values, decommitment).unwrap_err(),
+            MerkleVerificationError::RootMismatch
+        );
+    }
+
+    #[test]
+    fn test_merkle_with_config_wrong_config() {
+        let config = MerkleConfig::new(2, 1);
+        let (queries, decommitment, values, verifier) =
+            prepare_merkle_with_config::<Blake2sMerkleHasher>(config);
+        let verifier = verifier.with_config(MerkleConfig::default());
+
+        assert!(verifier.verify(queries, values, decommitment).is_err());
+    }
+
+    #[test]
+    fn test_merkle_with_config_fewer_layers() {
+        let column = (0..1 << 6).map(BaseField::from).collect_vec();
+
+        let binary = MerkleProver::<CpuBackend, Blake2sMerkleHasher>::commit(vec![&column]);
+        let octary = MerkleProver::<CpuBackend, Blake2sMerkleHasher>::commit_with_config(
+            vec![&column],
+            MerkleConfig::new(3, 0),
+        );
+        let packed = MerkleProver::<CpuBackend, Blake2sMerkleHasher>::commit_with_config(
+            vec![&column],
+            MerkleConfig::new(1, 2),
+        );
+
+        assert_eq!(binary.layers.len(), 7);
+        assert_eq!(octary.layers.len(), 3);
+        assert_eq!(packed.layers.len(), 5);
+    }
+
+    #[test]
+    fn test_merkle_with_config_simd_matches_cpu() {
+        let cols = (3..9)
+            .map(|log_size| (0..1 << log_size).map(BaseField::from).collect_vec())
+            .collect_vec();
+        let simd_cols = cols
+            .iter()
+            .map(|col| col.iter().copied().collect::<Col<SimdBackend, BaseField>>())
+            .collect_vec();
+
+        for (log_arity, log_leaf_rows) in [(1, 0), (2, 0), (3, 1), (1, 3)] {
+            let config = MerkleConfig::new(log_arity, log_leaf_rows);
+            let cpu_merkle = MerkleProver::<CpuBackend, Blake2sMerkleHasher>::commit_with_config(
+                cols.iter().collect(),
+                config,
+            );
+            let simd_merkle = MerkleProver::<SimdBackend, Blake2sMerkleHasher>::commit_with_config(
+                simd_cols.iter().collect(),
+                config,
+            );
+
+            assert_eq!(simd_merkle.root(), cpu_merkle.root());
+        }
+    }
+
+    #[test]
+    fn test_merkle_with_cap_success() {
+        let (_, root_decommitment, ..) = prepare_merkle::<Blake2sMerkleHasher>();
+        // The cap of the last config is capped at the size of the smallest column.
+        for (config, cap_size) in [
+            (MerkleConfig::default().with_log_cap_size(2), 4),
+            (MerkleConfig::new(2, 1).with_log_cap_size(1), 2),
+            (MerkleConfig::default().with_log_cap_size(6), 8),
+        ] {
+            let (queries, decommitment, values, verifier) =
+                prepare_merkle_with_config::<Blake2sMerkleHasher>(config);
+
+            assert_eq!(verifier.cap.len(), cap_size);
+            verifier.verify(queries, values, decommitment).unwrap();
+        }
+
+        let config = MerkleConfig::default().with_log_cap_size(2);
+        let (_, cap_decommitment, ..) = prepare_merkle_with_config::<Blake2sMerkleHasher>(config);
+        assert!(cap_decommitment.hash_witness.len() < root_decommitment.hash_witness.len());
+    }
+
+    #[test]
+    fn test_merkle_with_cap_invalid_cap() {
+        let config = MerkleConfig::default().with_log_cap_size(2);
+        let (queries, decommitment, values, mut verifier) =
+            prepare_merkle_with_config::<Blake2sMerkleHasher>(config);
+        verifier.cap.fill(Blake2sHash::default());
+
+        assert_eq!(
+            verifier.verify(queries, values, decommitment).unwrap_err(),
+            MerkleVerificationError::RootMismatch
+        );
+    }
+
+    #[test]
+    fn test_merkle_with_cap_size_mismatch() {
+        let config = MerkleConfig::default().with_log_cap_size(2);
+        let (queries, decommitment, values, mut verifier) =
+            prepare_merkle_with_config::<Blake2sMerkleHasher>(config);
+        verifier.cap.pop();
+
+        assert_eq!(
+            verifier.verify(queries, values, decommitment).unwrap_err(),
+            MerkleVerificationError::CapSizeMismatch
+        );
+    }
 }
@@ -0,0 +1,114 @@
+pub use stwo_verifier::vcs::blake3_merkle::{Blake3MerkleChannel, Blake3MerkleHasher};
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use num_traits::Zero;
+
+    use crate::core::backend::CpuBackend;
+    use crate::core::fields::m31::BaseField;
+    use crate::core::vcs::blake3_hash::Blake3Hash;
+    use crate::core::vcs::blake3_merkle::Blake3MerkleHasher;
+    use crate::core::vcs::ops::MerkleHasher;
+    use crate::core::vcs::prover::MerkleProver;
+    use crate::core::vcs::test_utils::prepare_merkle;
+    use crate::core::vcs::verifier::{MerkleVerificationError, MerkleVerifier};
+
+    #[test]
+    fn test_merkle_success() {
+        let (queries, decommitment, values, verifier) = prepare_merkle::<Blake3MerkleHasher>();
+
+        verifier.verify(queries, values, decommitment).unwrap();
+    }
+
+    #[test]
+    fn test_merkle_invalid_witness() {
+        let (queries, mut decommitment, values, verifier) = prepare_merkle::<Blake3MerkleHasher>();
+        decommitment.hash_witness[4] = Blake3Hash::default();
+
+        assert_eq!(
+            verifier.verify(queries, values, decommitment).unwrap_err(),
+            MerkleVerificationError::RootMismatch
+        );
+    }
+
+    #[test]
+    fn test_merkle_invalid_value() {
+        let (queries, decommitment, mut values, verifier) = prepare_merkle::<Blake3MerkleHasher>();
+        values[3][2] = BaseField::zero();
+
+        assert_eq!(
+            verifier.verify(queries, values, decommitment).unwrap_err(),
+            MerkleVerificationError::RootMismatch
+        );
+    }
+
+    #[test]
+    fn test_merkle_witness_too_short() {
+        let (queries, mut decommitment, values, verifier) = prepare_merkle::<Blake3MerkleHasher>();
+        decommitment.hash_witness.pop();
+
+        assert_eq!(
+            verifier.verify(queries, values, decommitment).unwrap_err(),
+            MerkleVerificationError::WitnessTooShort
+        );
+    }
+
+    #[test]
+    fn test_merkle_witness_too_long() {
+        let (queries, mut decommitment, values, verifier) = prepare_merkle::<Blake3MerkleHasher>();
+        decommitment.hash_witness.push(Blake3Hash::default());
+
+        assert_eq!(
+            verifier.verify(queries, values, decommitment).unwrap_err(),
+            MerkleVerificationError::WitnessTooLong
+        );
+    }
+
+    #[test]
+    fn test_merkle_column_values_too_long() {
+        let (queries, decommitment, mut values, verifier) = prepare_merkle::<Blake3MerkleHasher>();
+        values[3].push(BaseField::zero());
+
+        assert_eq!(
+            verifier.verify(queries, values, decommitment).unwrap_err(),
+            MerkleVerificationError::ColumnValuesTooLong
+        );
+    }
+
+    #[test]
+    fn test_merkle_column_values_too_short() {
+        let (queries, decommitment, mut values, verifier) = prepare_merkle::<Blake3MerkleHasher>();
+        values[3].pop();
+
+        assert_eq!(
+            verifier.verify(queries, values, decommitment).unwrap_err(),
+            MerkleVerificationError::ColumnValuesTooShort
+        );
+    }
+
+    #[test]
+    fn test_merkle_empty_tree() {
+        let merkle = MerkleProver::<CpuBackend, Blake3MerkleHasher>::commit(vec![]);
+        let (values, decommitment) = merkle.decommit(BTreeMap::new(), vec![]);
+        let verifier = MerkleVerifier::<Blake3MerkleHasher>::new(merkle.root(), vec![]);
+
+        verifier
+            .verify(BTreeMap::new(), values, decommitment)
+            .unwrap();
+    }
+
+    #[test]
+    fn hash_node_is_blake3_of_node_bytes() {
+        let left = Blake3Hash::from(vec![1; 32]);
+        let right = Blake3Hash::from(vec![2; 32]);
+        let values = [BaseField::zero(), BaseField::from(7)];
+        let mut bytes = [[1; 32], [2; 32]].concat();
+        bytes.extend([0, 0, 0, 0, 7, 0, 0, 0]);
+
+        let hash = Blake3MerkleHasher::hash_node(&[left, right], &values);
+
+        assert_eq!(hash.as_ref(), blake3::hash(&bytes).as_bytes());
+    }
+}
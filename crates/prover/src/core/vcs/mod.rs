@@ -1,16 +1,16 @@
 //! Vector commitment scheme (VCS) module.
 
-pub mod blake2_hash;
+pub use stwo_verifier::vcs::{
+    blake2_hash, blake2s_ref, blake3_hash, hasher, poseidon2_m31, verifier,
+};
+
 pub mod blake2_merkle;
-pub mod blake2s_ref;
-pub mod blake3_hash;
-pub mod hasher;
+pub mod blake3_merkle;
 pub mod ops;
-#[cfg(not(target_arch = "wasm32"))]
 pub mod poseidon252_merkle;
+pub mod poseidon2_m31_merkle;
 pub mod prover;
-mod utils;
-pub mod verifier;
+pub mod streaming_prover;
 
 #[cfg(test)]
 mod test_utils;
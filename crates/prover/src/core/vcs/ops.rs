@@ -1,24 +1,9 @@
-use std::fmt::Debug;
+use itertools::Itertools;
+pub use stwo_verifier::vcs::ops::MerkleHasher;
 
-use crate::core::backend::{Col, ColumnOps};
+use crate::core::backend::{Col, Column, ColumnOps};
 use crate::core::fields::m31::BaseField;
 
-/// A Merkle node hash is a hash of:
-///   [left_child_hash, right_child_hash], column0_value, column1_value, ...
-/// "[]" denotes optional values.
-/// The largest Merkle layer has no left and right child hashes. The rest of the layers have
-/// children hashes.
-/// At each layer, the tree may have multiple columns of the same length as the layer.
-/// Each node in that layer contains one value from each column.
-pub trait MerkleHasher: Debug {
-    type Hash: Copy + Clone + Eq + std::fmt::Debug;
-    /// Hashes a single Merkle node. See [MerkleHasher] for more details.
-    fn hash_node(
-        children_hashes: Option<(Self::Hash, Self::Hash)>,
-        column_values: &[BaseField],
-    ) -> Self::Hash;
-}
-
 /// Trait for performing Merkle operations on a commitment scheme.
 pub trait MerkleOps<H: MerkleHasher>: ColumnOps<BaseField> + ColumnOps<H::Hash> {
     /// Commits on an entire layer of the Merkle tree.
@@ -39,4 +24,48 @@ pub trait MerkleOps<H: MerkleHasher>: ColumnOps<BaseField> + ColumnOps<H::Hash>
         prev_layer: Option<&Col<Self, H::Hash>>,
         columns: &[&Col<Self, BaseField>],
     ) -> Col<Self, H::Hash>;
+
+    /// Commits on an entire layer of a Merkle tree of any shape (see
+    /// [MerkleConfig](super::verifier::MerkleConfig)).
+    ///
+    /// Unlike in [Self::commit_on_layer], each node is a hash of 2^`log_arity` consecutive nodes of
+    /// `prev_layer`, which is then assumed to have 2^(`log_size`+`log_arity`) nodes. `columns` may
+    /// also be larger than the layer, in which case each node hashes 2^(`column_log_size` -
+    /// `log_size`) consecutive values of them.
+    ///
+    /// The default implementation defers binary layers with one value per column to
+    /// [Self::commit_on_layer], and hashes other layers node by node.
+    fn commit_on_layer_with_arity(
+        log_size: u32,
+        log_arity: u32,
+        prev_layer: Option<&Col<Self, H::Hash>>,
+        columns: &[&Col<Self, BaseField>],
+    ) -> Col<Self, H::Hash> {
+        let is_binary = prev_layer.is_none() || log_arity == 1;
+        if is_binary && columns.iter().all(|column| column.len() == 1 << log_size) {
+            return Self::commit_on_layer(log_size, prev_layer, columns);
+        }
+
+        if let Some(prev_layer) = prev_layer {
+            assert_eq!(prev_layer.len(), 1 << (log_size + log_arity));
+        }
+
+        (0..1 << log_size)
+            .map(|i| {
+                let children_hashes = prev_layer.map_or(vec![], |prev_layer| {
+                    (i << log_arity..(i + 1) << log_arity)
+                        .map(|j| prev_layer.at(j))
+                        .collect_vec()
+                });
+                let column_values = columns
+                    .iter()
+                    .flat_map(|column| {
+                        let log_node_rows = column.len().ilog2() - log_size;
+                        (i << log_node_rows..(i + 1) << log_node_rows).map(|row| column.at(row))
+                    })
+                    .collect_vec();
+                H::hash_node(&children_hashes, &column_values)
+            })
+            .collect()
+    }
 }
@@ -0,0 +1,109 @@
+pub use stwo_verifier::vcs::poseidon2_m31_merkle::{
+    Poseidon2M31MerkleChannel, Poseidon2M31MerkleHasher,
+};
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use num_traits::Zero;
+
+    use crate::core::backend::CpuBackend;
+    use crate::core::fields::m31::BaseField;
+    use crate::core::vcs::poseidon2_m31::Poseidon2M31Hash;
+    use crate::core::vcs::poseidon2_m31_merkle::Poseidon2M31MerkleHasher;
+    use crate::core::vcs::prover::MerkleProver;
+    use crate::core::vcs::test_utils::prepare_merkle;
+    use crate::core::vcs::verifier::{MerkleVerificationError, MerkleVerifier};
+
+    #[test]
+    fn test_merkle_success() {
+        let (queries, decommitment, values, verifier) =
+            prepare_merkle::<Poseidon2M31MerkleHasher>();
+
+        verifier.verify(queries, values, decommitment).unwrap();
+    }
+
+    #[test]
+    fn test_merkle_invalid_witness() {
+        let (queries, mut decommitment, values, verifier) =
+            prepare_merkle::<Poseidon2M31MerkleHasher>();
+        decommitment.hash_witness[4] = Poseidon2M31Hash::default();
+
+        assert_eq!(
+            verifier.verify(queries, values, decommitment).unwrap_err(),
+            MerkleVerificationError::RootMismatch
+        );
+    }
+
+    #[test]
+    fn test_merkle_invalid_value() {
+        let (queries, decommitment, mut values, verifier) =
+            prepare_merkle::<Poseidon2M31MerkleHasher>();
+        values[3][2] = BaseField::zero();
+
+        assert_eq!(
+            verifier.verify(queries, values, decommitment).unwrap_err(),
+            MerkleVerificationError::RootMismatch
+        );
+    }
+
+    #[test]
+    fn test_merkle_witness_too_short() {
+        let (queries, mut decommitment, values, verifier) =
+            prepare_merkle::<Poseidon2M31MerkleHasher>();
+        decommitment.hash_witness.pop();
+
+        assert_eq!(
+            verifier.verify(queries, values, decommitment).unwrap_err(),
+            MerkleVerificationError::WitnessTooShort
+        );
+    }
+
+    #[test]
+    fn test_merkle_witness_too_long() {
+        let (queries, mut decommitment, values, verifier) =
+            prepare_merkle::<Poseidon2M31MerkleHasher>();
+        decommitment.hash_witness.push(Poseidon2M31Hash::default());
+
+        assert_eq!(
+            verifier.verify(queries, values, decommitment).unwrap_err(),
+            MerkleVerificationError::WitnessTooLong
+        );
+    }
+
+    #[test]
+    fn test_merkle_column_values_too_long() {
+        let (queries, decommitment, mut values, verifier) =
+            prepare_merkle::<Poseidon2M31MerkleHasher>();
+        values[3].push(BaseField::zero());
+
+        assert_eq!(
+            verifier.verify(queries, values, decommitment).unwrap_err(),
+            MerkleVerificationError::ColumnValuesTooLong
+        );
+    }
+
+    #[test]
+    fn test_merkle_column_values_too_short() {
+        let (queries, decommitment, mut values, verifier) =
+            prepare_merkle::<Poseidon2M31MerkleHasher>();
+        values[3].pop();
+
+        assert_eq!(
+            verifier.verify(queries, values, decommitment).unwrap_err(),
+            MerkleVerificationError::ColumnValuesTooShort
+        );
+    }
+
+    #[test]
+    fn test_merkle_empty_tree() {
+        let merkle = MerkleProver::<CpuBackend, Poseidon2M31MerkleHasher>::commit(vec![]);
+        let (values, decommitment) = merkle.decommit(BTreeMap::new(), vec![]);
+        let verifier = MerkleVerifier::<Poseidon2M31MerkleHasher>::new(merkle.root(), vec![]);
+
+        verifier
+            .verify(BTreeMap::new(), values, decommitment)
+            .unwrap();
+    }
+}
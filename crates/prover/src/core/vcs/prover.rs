@@ -3,20 +3,26 @@ use std::collections::BTreeMap;
 
 use educe::Educe;
 use itertools::Itertools;
+use stwo_verifier::vcs::utils::{layer_node_queries, next_decommitment_node};
 
 use super::ops::{MerkleHasher, MerkleOps};
-use super::utils::{next_decommitment_node, option_flatten_peekable};
+pub use super::verifier::MerkleDecommitment;
+use super::verifier::{queries_per_log_size, MerkleConfig, MerkleOpening};
 use crate::core::backend::{Col, Column};
 use crate::core::fields::m31::BaseField;
 use crate::core::utils::PeekableExt;
 use crate::core::ColumnVec;
 
+#[derive(Educe)]
+#[educe(Clone)]
 pub struct MerkleProver<B: MerkleOps<H>, H: MerkleHasher> {
     /// Layers of the Merkle tree.
     /// The first layer is the root layer.
     /// The last layer is the largest layer.
     /// See [MerkleOps::commit_on_layer] for more details.
     pub layers: Vec<Col<B, H::Hash>>,
+    /// The shape of the tree.
+    pub config: MerkleConfig,
 }
 /// The MerkleProver struct represents a prover for a Merkle commitment scheme.
 /// It is generic over the types `B` and `H`, which represent the Merkle operations and Merkle
@@ -31,32 +37,44 @@ impl<B: MerkleOps<H>, H: MerkleHasher> MerkleProver<B, H> {
     ///
     /// # Panics
     ///
-    /// This function will panic if the columns are not sorted in descending order or if the columns
-    /// vector is empty.
+    /// This function will panic if the columns are not sorted in descending order.
+    ///
+    /// An empty `columns` vector commits to a tree with a single node, hashing no values.
     ///
     /// # Returns
     ///
     /// A new instance of `MerkleProver` with the committed layers.
     pub fn commit(columns: Vec<&Col<B, BaseField>>) -> Self {
-        assert!(!columns.is_empty());
+        Self::commit_with_config(columns, MerkleConfig::default())
+    }
 
+    /// Commits to columns in a tree of the shape given by `config`. See [Self::commit].
+    pub fn commit_with_config(columns: Vec<&Col<B, BaseField>>, config: MerkleConfig) -> Self {
+        let column_log_sizes = columns.iter().map(|c| c.len().ilog2()).collect_vec();
         let columns = &mut columns
             .into_iter()
             .sorted_by_key(|c| Reverse(c.len()))
             .peekable();
         let mut layers: Vec<Col<B, H::Hash>> = Vec::new();
 
-        let max_log_size = columns.peek().unwrap().len().ilog2();
-        for log_size in (0..=max_log_size).rev() {
-            // Take columns of the current log_size.
+        for (log_size, layer_column_log_sizes) in config.layers(&column_log_sizes) {
+            // Take columns hashed in the current layer.
             let layer_columns = columns
-                .peek_take_while(|column| column.len().ilog2() == log_size)
+                .peek_take_while(|column| layer_column_log_sizes.contains(&column.len().ilog2()))
                 .collect_vec();
 
-            layers.push(B::commit_on_layer(log_size, layers.last(), &layer_columns));
+            let log_arity = layers
+                .last()
+                .map_or(0, |prev_layer| prev_layer.len().ilog2() - log_size);
+            layers.push(B::commit_on_layer_with_arity(
+                log_size,
+                log_arity,
+                layers.last(),
+                &layer_columns,
+            ));
         }
         layers.reverse();
-        Self { layers }
+        Self { layers, config }
     }
 
     /// Decommits to columns on the given queries.
@@ -87,139 +105,148 @@ impl<B: MerkleOps<H>, H: MerkleHasher> MerkleProver<B, H> {
             );
         }
 
-        // Prepare output buffers.
-        let mut queried_values_by_layer = vec![];
-        let mut decommitment = MerkleDecommitment::empty();
-
-        // Sort columns by layer.
-        let mut columns_by_layer = columns
-            .iter()
-            .sorted_by_key(|c| Reverse(c.len()))
-            .peekable();
+        let column_log_sizes = columns.iter().map(|c| c.len().ilog2()).collect_vec();
+        decommit_layers(
+            self.config,
+            &column_log_sizes,
+            &queries_per_log_size,
+            |layer, index| self.layers[self.layers.len() - 1 - layer].at(index),
+            |column, row| columns[column].at(row),
+        )
+    }
 
-        let mut last_layer_queries = vec![];
-        for layer_log_size in (0..self.layers.len() as u32).rev() {
-            // Prepare write buffer for queried values to the current layer.
-            let mut layer_queried_values = vec![];
+    /// Opens `rows` of each of the committed `columns`, given in the order they were committed.
+    /// The opening is verified with `MerkleVerifier::verify_opening`, given the same rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rows` doesn't have an entry for each column, or if a row is out of its column's
+    /// bounds.
+    pub fn open(
+        &self,
+        rows: &ColumnVec<Vec<usize>>,
+        columns: Vec<&Col<B, BaseField>>,
+    ) -> MerkleOpening<H> {
+        let column_log_sizes = columns.iter().map(|c| c.len().ilog2()).collect_vec();
+        let queries_per_log_size =
+            queries_per_log_size(&column_log_sizes, rows).expect("Invalid rows.");
+        let (queried_values, decommitment) = self.decommit(queries_per_log_size, columns);
+        MerkleOpening {
+            queried_values,
+            decommitment,
+        }
+    }
 
-            // Prepare write buffer for queries to the current layer. This will propagate to the
-            // next layer.
-            let mut layer_total_queries = vec![];
+    /// Returns the root of a tree without a cap. See [Self::cap].
+    pub fn root(&self) -> H::Hash {
+        let top_layer = self.layers.first().unwrap();
+        assert_eq!(top_layer.len(), 1, "The tree has a cap.");
+        top_layer.at(0)
+    }
 
-            // Each layer node is a hash of column values as previous layer hashes.
-            // Prepare the relevant columns and previous layer hashes to read from.
-            let layer_columns = columns_by_layer
-                .peek_take_while(|column| column.len().ilog2() == layer_log_size)
-                .collect_vec();
-            let previous_layer_hashes = self.layers.get(layer_log_size as usize + 1);
-
-            // Queries to this layer come from queried node in the previous layer and queried
-            // columns in this one.
-            let mut prev_layer_queries = last_layer_queries.into_iter().peekable();
-            let mut layer_column_queries =
-                option_flatten_peekable(queries_per_log_size.get(&layer_log_size));
-
-            // Merge previous layer queries and column queries.
-            while let Some(node_index) =
-                next_decommitment_node(&mut prev_layer_queries, &mut layer_column_queries)
-            {
-                if let Some(previous_layer_hashes) = previous_layer_hashes {
-                    // If the left child was not computed, add it to the witness.
-                    if prev_layer_queries.next_if_eq(&(2 * node_index)).is_none() {
-                        decommitment
-                            .hash_witness
-                            .push(previous_layer_hashes.at(2 * node_index));
-                    }
+    /// Returns the nodes of the top layer of the tree, see [MerkleConfig].
+    pub fn cap(&self) -> Vec<H::Hash> {
+        self.layers.first().unwrap().to_cpu()
+    }
+}
 
-                    // If the right child was not computed, add it to the witness.
-                    if prev_layer_queries
-                        .next_if_eq(&(2 * node_index + 1))
-                        .is_none()
-                    {
+/// Decommits to columns of the given log sizes, committed in a tree of shape `config`, on the given
+/// queries (see [MerkleProver::decommit]).
+///
+/// The tree is read through `layer_hash(layer, index)`, where layers are counted from the leaves,
+/// and `column_value(column, row)`. Only the nodes and rows on the paths of the queries, and their
+/// siblings, are read.
+pub(super) fn decommit_layers<H: MerkleHasher>(
+    config: MerkleConfig,
+    column_log_sizes: &[u32],
+    queries_per_log_size: &BTreeMap<u32, Vec<usize>>,
+    layer_hash: impl Fn(usize, usize) -> H::Hash,
+    column_value: impl Fn(usize, usize) -> BaseField,
+) -> (ColumnVec<Vec<BaseField>>, MerkleDecommitment<H>) {
+    // Prepare output buffers.
+    let mut queried_values = vec![vec![]; column_log_sizes.len()];
+    let mut decommitment = MerkleDecommitment {
+        hash_witness: Vec::new(),
+        column_witness: Vec::new(),
+    };
+
+    // Sort columns by layer, remembering their position in the input.
+    let mut columns_by_layer = column_log_sizes
+        .iter()
+        .copied()
+        .enumerate()
+        .sorted_by_key(|(_, log_size)| Reverse(*log_size))
+        .peekable();
+
+    let mut last_layer_queries = vec![];
+    let mut last_layer_log_size = columns_by_layer.peek().map_or(0, |(_, log_size)| *log_size);
+    for (layer, (layer_log_size, layer_column_log_sizes)) in
+        config.layers(column_log_sizes).into_iter().enumerate()
+    {
+        // Prepare write buffer for queries to the current layer. This will propagate to the
+        // next layer.
+        let mut layer_total_queries = vec![];
+
+        // Each layer node is a hash of column values as previous layer hashes.
+        // Prepare the relevant columns to read from.
+        let layer_columns = columns_by_layer
+            .peek_take_while(|(_, log_size)| layer_column_log_sizes.contains(log_size))
+            .map(|(column_index, log_size)| {
+                let column_queries = queries_per_log_size
+                    .get(&log_size)
+                    .map_or(&[][..], |queries| queries);
+                (column_index, log_size, column_queries)
+            })
+            .collect_vec();
+        let previous_layer = layer.checked_sub(1);
+        let log_arity = last_layer_log_size - layer_log_size;
+
+        // Queries to this layer come from queried node in the previous layer and queried
+        // columns in this one.
+        let mut prev_layer_queries = last_layer_queries.into_iter().peekable();
+        let mut layer_column_queries =
+            layer_node_queries(queries_per_log_size, layer_column_log_sizes, layer_log_size)
+                .into_iter()
+                .peekable();
+
+        // Merge previous layer queries and column queries.
+        while let Some(node_index) = next_decommitment_node(
+            &mut prev_layer_queries,
+            &mut layer_column_queries,
+            log_arity,
+        ) {
+            if let Some(previous_layer) = previous_layer {
+                // If a child was not computed, add it to the witness.
+                for child_index in node_index << log_arity..(node_index + 1) << log_arity {
+                    if prev_layer_queries.next_if_eq(&child_index).is_none() {
                         decommitment
                             .hash_witness
-                            .push(previous_layer_hashes.at(2 * node_index + 1));
+                            .push(layer_hash(previous_layer, child_index));
                     }
                 }
+            }
 
-                // If the column values were queried, return them.
-                let node_values = layer_columns.iter().map(|c| c.at(node_index));
-                if layer_column_queries.next_if_eq(&node_index).is_some() {
-                    layer_queried_values.push(node_values.collect_vec());
-                } else {
-                    // Otherwise, add them to the witness.
-                    decommitment.column_witness.extend(node_values);
+            // If a row was queried, return its column values. Otherwise, add them to the
+            // witness.
+            layer_column_queries.next_if_eq(&node_index);
+            for &(column_index, log_size, column_queries) in &layer_columns {
+                let log_node_rows = log_size - layer_log_size;
+                for row in node_index << log_node_rows..(node_index + 1) << log_node_rows {
+                    let value = column_value(column_index, row);
+                    if column_queries.binary_search(&row).is_ok() {
+                        queried_values[column_index].push(value);
+                    } else {
+                        decommitment.column_witness.push(value);
+                    }
                 }
-
-                layer_total_queries.push(node_index);
             }
 
-            queried_values_by_layer.push(layer_queried_values);
-
-            // Propagate queries to the next layer.
-            last_layer_queries = layer_total_queries;
+            layer_total_queries.push(node_index);
         }
-        queried_values_by_layer.reverse();
-
-        // Rearrange returned queried values according to input, and not by layer.
-        let queried_values = Self::rearrange_queried_values(queried_values_by_layer, columns);
-
-        (queried_values, decommitment)
-    }
-
-    /// Given queried values by layer, rearranges in the order of input columns.
-    fn rearrange_queried_values(
-        queried_values_by_layer: Vec<Vec<Vec<BaseField>>>,
-        columns: Vec<&Col<B, BaseField>>,
-    ) -> Vec<Vec<BaseField>> {
-        // Turn each column queried values into an iterator.
-        let mut queried_values_by_layer = queried_values_by_layer
-            .into_iter()
-            .map(|layer_results| {
-                layer_results
-                    .into_iter()
-                    .map(|x| x.into_iter())
-                    .collect_vec()
-            })
-            .collect_vec();
 
-        // For each input column, fetch the queried values from the corresponding layer.
-        let queried_values = columns
-            .iter()
-            .map(|column| {
-                queried_values_by_layer
-                    .get_mut(column.len().ilog2() as usize)
-                    .unwrap()
-                    .iter_mut()
-                    .map(|x| x.next().unwrap())
-                    .collect_vec()
-            })
-            .collect_vec();
-        queried_values
-    }
-
-    pub fn root(&self) -> H::Hash {
-        self.layers.first().unwrap().at(0)
-    }
-}
-
-#[derive(Debug, Educe)]
-#[educe(Clone)]
-pub struct MerkleDecommitment<H: MerkleHasher> {
-    /// Hash values that the verifier needs but cannot deduce from previous computations, in the
-    /// order they are needed.
-    pub hash_witness: Vec<H::Hash>,
-    /// Column values that the verifier needs but cannot deduce from previous computations, in the
-    /// order they are needed.
-    /// This complements the column values that were queried. These must be supplied directly to
-    /// the verifier.
-    pub column_witness: Vec<BaseField>,
-}
-impl<H: MerkleHasher> MerkleDecommitment<H> {
-    fn empty() -> Self {
-        Self {
-            hash_witness: Vec::new(),
-            column_witness: Vec::new(),
-        }
+        // Propagate queries to the next layer.
+        last_layer_queries = layer_total_queries;
+        last_layer_log_size = layer_log_size;
     }
+    (queried_values, decommitment)
 }
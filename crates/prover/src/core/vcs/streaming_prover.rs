@@ -0,0 +1,350 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+
+use educe::Educe;
+use itertools::Itertools;
+
+use super::ops::{MerkleHasher, MerkleOps};
+use super::prover::{decommit_layers, MerkleDecommitment, MerkleProver};
+use super::verifier::MerkleConfig;
+use crate::core::backend::{Col, Column, ColumnOps};
+use crate::core::fields::m31::BaseField;
+use crate::core::ColumnVec;
+
+/// The columns of a [StreamingMerkleProver], read a range of rows at a time, so that they never
+/// need to be in memory at once. Implementations may keep the columns anywhere, e.g. in
+/// memory-mapped files, or generate them on the fly, as long as reading the same rows always
+/// returns the same values.
+pub trait ColumnSource<B: ColumnOps<BaseField>> {
+    /// Returns the log sizes of the columns.
+    fn column_log_sizes(&self) -> Vec<u32>;
+
+    /// Reads `rows` of the `column`-th column.
+    fn read_rows(&self, column: usize, rows: Range<usize>) -> Col<B, BaseField>;
+}
+
+impl<B: ColumnOps<BaseField>> ColumnSource<B> for [Col<B, BaseField>] {
+    fn column_log_sizes(&self) -> Vec<u32> {
+        self.iter().map(|column| column.len().ilog2()).collect()
+    }
+
+    fn read_rows(&self, column: usize, rows: Range<usize>) -> Col<B, BaseField> {
+        self[column].copy_range(rows)
+    }
+}
+
+/// A Merkle prover for columns that don't fit in memory.
+///
+/// The tree is the one [MerkleProver::commit_with_config] would commit to, but only its top layers
+/// are kept. The tree below them is split into subtrees, one for each node of the lowest kept
+/// layer, which are committed one at a time from their rows of the columns, and recomputed when
+/// decommitted.
+///
+/// See [CommitmentTreeProver::new_streaming](crate::core::pcs::CommitmentTreeProver::new_streaming)
+/// for its use in the commitment scheme.
+#[derive(Educe)]
+#[educe(Clone)]
+pub struct StreamingMerkleProver<B: MerkleOps<H>, H: MerkleHasher> {
+    /// The kept layers of the Merkle tree.
+    /// The first layer is the root layer.
+    /// The last layer is the layer of the roots of the subtrees.
+    pub top_layers: Vec<Col<B, H::Hash>>,
+    /// The shape of the tree.
+    pub config: MerkleConfig,
+    /// The log sizes of the committed columns.
+    pub column_log_sizes: Vec<u32>,
+}
+
+impl<B: MerkleOps<H>, H: MerkleHasher> StreamingMerkleProver<B, H> {
+    /// Commits to the columns of `source`, keeping the top `n_top_layers` layers of the tree in
+    /// memory. Besides them, only the rows of a single subtree are in memory at a time, as well as
+    /// the columns hashed in the top layers, which are read in full.
+    ///
+    /// Fewer top layers make smaller top layers, but larger subtrees to recompute on each
+    /// decommitment.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n_top_layers` is zero, or larger than the height of the tree.
+    pub fn commit<S: ColumnSource<B> + ?Sized>(
+        source: &S,
+        n_top_layers: usize,
+        config: MerkleConfig,
+    ) -> Self {
+        let column_log_sizes = source.column_log_sizes();
+        let layers = config.layers(&column_log_sizes);
+        assert!(
+            (1..=layers.len()).contains(&n_top_layers),
+            "Invalid number of top layers."
+        );
+        let (subtree_roots_log_size, _) = layers[layers.len() - n_top_layers];
+
+        // Commit to the subtrees, one at a time.
+        let subtree_roots = (0..1 << subtree_roots_log_size)
+            .map(|subtree| {
+                Subtree::commit(
+                    source,
+                    &column_log_sizes,
+                    subtree_roots_log_size,
+                    subtree,
+                    config,
+                )
+                .merkle
+                .root()
+            })
+            .collect::<Col<B, H::Hash>>();
+
+        // Commit to the top layers, with the columns hashed in them.
+        let mut top_layers = vec![subtree_roots];
+        for &(log_size, ref layer_column_log_sizes) in &layers[layers.len() - n_top_layers + 1..] {
+            let layer_columns = column_log_sizes
+                .iter()
+                .enumerate()
+                .filter(|(_, log_size)| layer_column_log_sizes.contains(log_size))
+                .sorted_by_key(|(_, &log_size)| Reverse(log_size))
+                .map(|(column, &log_size)| source.read_rows(column, 0..1 << log_size))
+                .collect_vec();
+
+            let prev_layer = top_layers.last().unwrap();
+            let log_arity = prev_layer.len().ilog2() - log_size;
+            top_layers.push(B::commit_on_layer_with_arity(
+                log_size,
+                log_arity,
+                Some(prev_layer),
+                &layer_columns.iter().collect_vec(),
+            ));
+        }
+        top_layers.reverse();
+
+        Self {
+            top_layers,
+            config,
+            column_log_sizes,
+        }
+    }
+
+    /// Decommits to the columns of `source` on the given queries, as [MerkleProver::decommit]
+    /// would, recomputing the subtrees on the paths of the queries.
+    pub fn decommit<S: ColumnSource<B> + ?Sized>(
+        &self,
+        queries_per_log_size: BTreeMap<u32, Vec<usize>>,
+        source: &S,
+    ) -> (ColumnVec<Vec<BaseField>>, MerkleDecommitment<H>) {
+        // Check that queries are sorted and deduped.
+        for queries in queries_per_log_size.values() {
+            assert!(
+                queries.windows(2).all(|w| w[0] < w[1]),
+                "Queries are not sorted."
+            );
+        }
+
+        let subtree_roots_log_size = self.top_layers.last().unwrap().len().ilog2();
+        let max_log_size = self.column_log_sizes.iter().max().copied().unwrap_or(0);
+        let layer_log_sizes = self
+            .config
+            .layers(&self.column_log_sizes)
+            .into_iter()
+            .map(|(log_size, _)| log_size)
+            .collect_vec();
+        let n_subtree_layers = layer_log_sizes.len() - self.top_layers.len() + 1;
+
+        // Recompute the subtrees with queried rows. All the nodes and rows below the top layers
+        // that the decommitment reads are in them.
+        let subtrees = queries_per_log_size
+            .range(subtree_roots_log_size..=max_log_size)
+            .flat_map(|(log_size, queries)| {
+                queries
+                    .iter()
+                    .map(move |query| query >> (log_size - subtree_roots_log_size))
+            })
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|subtree| {
+                let subtree_commitment = Subtree::<B, H>::commit(
+                    source,
+                    &self.column_log_sizes,
+                    subtree_roots_log_size,
+                    subtree,
+                    self.config,
+                );
+                (subtree, subtree_commitment)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        // Columns hashed in the top layers are read in full.
+        let top_columns = self
+            .column_log_sizes
+            .iter()
+            .enumerate()
+            .map(|(column, &log_size)| {
+                (log_size < subtree_roots_log_size)
+                    .then(|| source.read_rows(column, 0..1 << log_size))
+            })
+            .collect_vec();
+
+        decommit_layers(
+            self.config,
+            &self.column_log_sizes,
+            &queries_per_log_size,
+            |layer, index| {
+                if layer + 1 >= n_subtree_layers {
+                    let top_layer = layer + 1 - n_subtree_layers;
+                    return self.top_layers[self.top_layers.len() - 1 - top_layer].at(index);
+                }
+                let log_subtree_nodes = layer_log_sizes[layer] - subtree_roots_log_size;
+                let merkle = &subtrees[&(index >> log_subtree_nodes)].merkle;
+                merkle.layers[merkle.layers.len() - 1 - layer]
+                    .at(index & ((1 << log_subtree_nodes) - 1))
+            },
+            |column, row| {
+                let log_size = self.column_log_sizes[column];
+                if log_size < subtree_roots_log_size {
+                    return top_columns[column].as_ref().unwrap().at(row);
+                }
+                let log_subtree_rows = log_size - subtree_roots_log_size;
+                let columns = &subtrees[&(row >> log_subtree_rows)].columns;
+                columns[column]
+                    .as_ref()
+                    .unwrap()
+                    .at(row & ((1 << log_subtree_rows) - 1))
+            },
+        )
+    }
+
+    /// Returns the root of a tree without a cap. See [Self::cap].
+    pub fn root(&self) -> H::Hash {
+        let top_layer = self.top_layers.first().unwrap();
+        assert_eq!(top_layer.len(), 1, "The tree has a cap.");
+        top_layer.at(0)
+    }
+
+    /// Returns the nodes of the top layer of the tree, see [MerkleConfig].
+    pub fn cap(&self) -> Vec<H::Hash> {
+        self.top_layers.first().unwrap().to_cpu()
+    }
+}
+
+/// A subtree of a [StreamingMerkleProver], below a node of its lowest kept layer.
+struct Subtree<B: MerkleOps<H>, H: MerkleHasher> {
+    merkle: MerkleProver<B, H>,
+    /// For each column, its rows in the subtree, if it is hashed in it.
+    columns: ColumnVec<Option<Col<B, BaseField>>>,
+}
+
+impl<B: MerkleOps<H>, H: MerkleHasher> Subtree<B, H> {
+    /// Commits to the `subtree`-th subtree, whose roots are in a layer of log size
+    /// `subtree_roots_log_size`, from the rows of the columns hashed in it.
+    fn commit<S: ColumnSource<B> + ?Sized>(
+        source: &S,
+        column_log_sizes: &[u32],
+        subtree_roots_log_size: u32,
+        subtree: usize,
+        config: MerkleConfig,
+    ) -> Self {
+        let columns = column_log_sizes
+            .iter()
+            .enumerate()
+            .map(|(column, &log_size)| {
+                (log_size >= subtree_roots_log_size).then(|| {
+                    let n_rows = 1 << (log_size - subtree_roots_log_size);
+                    source.read_rows(column, subtree * n_rows..(subtree + 1) * n_rows)
+                })
+            })
+            .collect_vec();
+        // The subtree is rooted below the cap of the tree, if it has one.
+        let merkle = MerkleProver::commit_with_config(
+            columns.iter().flatten().collect(),
+            config.with_log_cap_size(0),
+        );
+        Self { merkle, columns }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use itertools::Itertools;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::StreamingMerkleProver;
+    use crate::core::backend::CpuBackend;
+    use crate::core::fields::m31::BaseField;
+    use crate::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+    use crate::core::vcs::prover::MerkleProver;
+    use crate::core::vcs::verifier::{MerkleConfig, MerkleVerifier};
+
+    #[test]
+    fn test_streaming_merkle_matches_merkle() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let log_sizes = (0..10).map(|_| rng.gen_range(2..8)).collect_vec();
+        let cols = log_sizes
+            .iter()
+            .map(|&log_size| (0..1 << log_size).map(|_| rng.gen()).collect_vec())
+            .collect_vec();
+        let queries = (2..8)
+            .map(|log_size| {
+                let log_size_queries = (0..3)
+                    .map(|_| rng.gen_range(0..1 << log_size))
+                    .sorted()
+                    .dedup()
+                    .collect_vec();
+                (log_size, log_size_queries)
+            })
+            .collect::<BTreeMap<u32, Vec<usize>>>();
+
+        for config in [
+            MerkleConfig::default(),
+            MerkleConfig::new(2, 1),
+            MerkleConfig::new(3, 0),
+            MerkleConfig::new(1, 1).with_log_cap_size(2),
+        ] {
+            let merkle = MerkleProver::<CpuBackend, Blake2sMerkleHasher>::commit_with_config(
+                cols.iter().collect(),
+                config,
+            );
+            let (values, decommitment) = merkle.decommit(queries.clone(), cols.iter().collect());
+
+            for n_top_layers in 1..=merkle.layers.len() {
+                let streaming_merkle =
+                    StreamingMerkleProver::<CpuBackend, Blake2sMerkleHasher>::commit(
+                        &cols[..],
+                        n_top_layers,
+                        config,
+                    );
+                let (streaming_values, streaming_decommitment) =
+                    streaming_merkle.decommit(queries.clone(), &cols[..]);
+
+                assert_eq!(streaming_merkle.cap(), merkle.cap());
+                assert_eq!(streaming_merkle.top_layers.len(), n_top_layers);
+                assert_eq!(streaming_values, values);
+                assert_eq!(
+                    streaming_decommitment.hash_witness,
+                    decommitment.hash_witness
+                );
+                assert_eq!(
+                    streaming_decommitment.column_witness,
+                    decommitment.column_witness
+                );
+                MerkleVerifier::from_cap(streaming_merkle.cap(), log_sizes.clone())
+                    .with_config(config)
+                    .verify(queries.clone(), streaming_values, streaming_decommitment)
+                    .unwrap();
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid number of top layers.")]
+    fn test_streaming_merkle_too_many_top_layers() {
+        let cols = vec![vec![BaseField::from(0); 1 << 3]];
+
+        StreamingMerkleProver::<CpuBackend, Blake2sMerkleHasher>::commit(
+            &cols[..],
+            5,
+            MerkleConfig::default(),
+        );
+    }
+}
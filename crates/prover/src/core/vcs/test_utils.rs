@@ -6,7 +6,7 @@ use rand::{Rng, SeedableRng};
 
 use super::ops::{MerkleHasher, MerkleOps};
 use super::prover::MerkleDecommitment;
-use super::verifier::MerkleVerifier;
+use super::verifier::{MerkleConfig, MerkleOpening, MerkleVerifier};
 use crate::core::backend::CpuBackend;
 use crate::core::fields::m31::BaseField;
 use crate::core::vcs::prover::MerkleProver;
@@ -19,6 +19,13 @@ pub type TestData<H> = (
 );
 
 pub fn prepare_merkle<H: MerkleHasher>() -> TestData<H>
+where
+    CpuBackend: MerkleOps<H>,
+{
+    prepare_merkle_with_config(MerkleConfig::default())
+}
+
+pub fn prepare_merkle_with_config<H: MerkleHasher>(config: MerkleConfig) -> TestData<H>
 where
     CpuBackend: MerkleOps<H>,
 {
@@ -38,7 +45,8 @@ where
                 .collect_vec()
         })
         .collect_vec();
-    let merkle = MerkleProver::<CpuBackend, H>::commit(cols.iter().collect_vec());
+    let merkle =
+        MerkleProver::<CpuBackend, H>::commit_with_config(cols.iter().collect_vec(), config);
 
     let mut queries = BTreeMap::<u32, Vec<usize>>::new();
     for log_size in log_size_range.rev() {
@@ -52,9 +60,43 @@ where
 
     let (values, decommitment) = merkle.decommit(queries.clone(), cols.iter().collect_vec());
 
-    let verifier = MerkleVerifier {
-        root: merkle.root(),
-        column_log_sizes: log_sizes,
-    };
+    let verifier = MerkleVerifier::from_cap(merkle.cap(), log_sizes).with_config(config);
     (queries, decommitment, values, verifier)
 }
+
+pub type OpeningTestData<H> = (
+    Vec<Vec<usize>>,
+    MerkleOpening<H>,
+    Vec<Vec<BaseField>>,
+    MerkleVerifier<H>,
+);
+
+/// Commits to columns of mixed sizes and opens a few unsorted, possibly repeated, rows of some of
+/// them.
+pub fn prepare_merkle_opening<H: MerkleHasher>() -> OpeningTestData<H>
+where
+    CpuBackend: MerkleOps<H>,
+{
+    const N_COLS: usize = 10;
+    let mut rng = SmallRng::seed_from_u64(0);
+    let log_sizes = (0..N_COLS).map(|_| rng.gen_range(2..5)).collect_vec();
+    let cols = log_sizes
+        .iter()
+        .map(|&log_size| (0..1 << log_size).map(|_| rng.gen()).collect_vec())
+        .collect_vec();
+    let merkle = MerkleProver::<CpuBackend, H>::commit(cols.iter().collect_vec());
+
+    let rows = log_sizes
+        .iter()
+        .map(|&log_size| {
+            let n_rows = rng.gen_range(0..4);
+            (0..n_rows)
+                .map(|_| rng.gen_range(0..1 << log_size))
+                .collect_vec()
+        })
+        .collect_vec();
+    let opening = merkle.open(&rows, cols.iter().collect_vec());
+
+    let verifier = MerkleVerifier::new(merkle.root(), log_sizes);
+    (rows, opening, cols, verifier)
+}
@@ -1,10 +1,11 @@
 use itertools::{zip_eq, Itertools};
 
 use super::component::{FibonacciComponent, FibonacciInput, FibonacciTraceGenerator};
-use crate::core::air::{Air, AirProver, Component, ComponentProver};
+use crate::core::air::{Air, AirProver, Component, ComponentProver, PublicInput};
 use crate::core::backend::CpuBackend;
-use crate::core::channel::Blake2sChannel;
+use crate::core::channel::Channel;
 use crate::core::fields::m31::BaseField;
+use crate::core::pcs::TreeVec;
 use crate::core::poly::circle::CircleEvaluation;
 use crate::core::poly::BitReversedOrder;
 use crate::core::prover::VerificationError;
@@ -27,7 +28,15 @@ impl FibonacciAirGenerator {
 }
 
 impl AirTraceVerifier for FibonacciAirGenerator {
-    fn interaction_elements(&self, _channel: &mut Blake2sChannel) -> InteractionElements {
+    fn n_interaction_phases(&self) -> usize {
+        0
+    }
+
+    fn interaction_elements(
+        &self,
+        _phase: usize,
+        _channel: &mut impl Channel,
+    ) -> InteractionElements {
         InteractionElements::default()
     }
 }
@@ -39,7 +48,8 @@ impl AirTraceGenerator<CpuBackend> for FibonacciAirGenerator {
 
     fn interact(
         &self,
-        _trace: &ColumnVec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>>,
+        _phase: usize,
+        _traces: &TreeVec<ColumnVec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>>>,
         _elements: &InteractionElements,
     ) -> Vec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>> {
         vec![]
@@ -81,13 +91,25 @@ impl Air for FibonacciAir {
         vec![&self.component]
     }
 
+    fn public_input(&self) -> impl PublicInput {
+        self.component.claim
+    }
+
     fn verify_lookups(&self, _lookup_values: &LookupValues) -> Result<(), VerificationError> {
         Ok(())
     }
 }
 
 impl AirTraceVerifier for FibonacciAir {
-    fn interaction_elements(&self, _channel: &mut Blake2sChannel) -> InteractionElements {
+    fn n_interaction_phases(&self) -> usize {
+        0
+    }
+
+    fn interaction_elements(
+        &self,
+        _phase: usize,
+        _channel: &mut impl Channel,
+    ) -> InteractionElements {
         InteractionElements::default()
     }
 }
@@ -95,7 +117,8 @@ impl AirTraceVerifier for FibonacciAir {
 impl AirTraceGenerator<CpuBackend> for FibonacciAir {
     fn interact(
         &self,
-        _trace: &ColumnVec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>>,
+        _phase: usize,
+        _traces: &TreeVec<ColumnVec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>>>,
         _elements: &InteractionElements,
     ) -> Vec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>> {
         vec![]
@@ -139,13 +162,25 @@ impl Air for MultiFibonacciAir {
             .collect_vec()
     }
 
+    fn public_input(&self) -> impl PublicInput {
+        self.components.iter().map(|c| c.claim).collect_vec()
+    }
+
     fn verify_lookups(&self, _lookup_values: &LookupValues) -> Result<(), VerificationError> {
         Ok(())
     }
 }
 
 impl AirTraceVerifier for MultiFibonacciAir {
-    fn interaction_elements(&self, _channel: &mut Blake2sChannel) -> InteractionElements {
+    fn n_interaction_phases(&self) -> usize {
+        0
+    }
+
+    fn interaction_elements(
+        &self,
+        _phase: usize,
+        _channel: &mut impl Channel,
+    ) -> InteractionElements {
         InteractionElements::default()
     }
 }
@@ -153,7 +188,8 @@ impl AirTraceVerifier for MultiFibonacciAir {
 impl AirTraceGenerator<CpuBackend> for MultiFibonacciAir {
     fn interact(
         &self,
-        _trace: &ColumnVec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>>,
+        _phase: usize,
+        _traces: &TreeVec<ColumnVec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>>>,
         _elements: &InteractionElements,
     ) -> Vec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>> {
         vec![]
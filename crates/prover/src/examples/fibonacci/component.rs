@@ -85,18 +85,21 @@ impl Component for FibonacciComponent {
     }
 
     fn trace_log_degree_bounds(&self) -> TreeVec<ColumnVec<u32>> {
-        TreeVec::new(vec![vec![self.log_size]])
+        TreeVec::new(vec![vec![], vec![self.log_size]])
     }
 
     fn mask_points(
         &self,
         point: CirclePoint<SecureField>,
     ) -> TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>> {
-        TreeVec::new(vec![shifted_mask_points(
-            &vec![vec![0, 1, 2]],
-            &[CanonicCoset::new(self.log_size)],
-            point,
-        )])
+        TreeVec::new(vec![
+            vec![],
+            shifted_mask_points(
+                &vec![vec![0, 1, 2]],
+                &[CanonicCoset::new(self.log_size)],
+                point,
+            ),
+        ])
     }
 
     fn evaluate_constraint_quotients_at_point(
@@ -107,12 +110,13 @@ impl Component for FibonacciComponent {
         _interaction_elements: &InteractionElements,
         _lookup_values: &LookupValues,
     ) {
-        evaluation_accumulator.accumulate(
-            self.step_constraint_eval_quotient_by_mask(point, &mask[0][0][..].try_into().unwrap()),
-        );
+        evaluation_accumulator.accumulate(self.step_constraint_eval_quotient_by_mask(
+            point,
+            &mask[BASE_TRACE][0][..].try_into().unwrap(),
+        ));
         evaluation_accumulator.accumulate(self.boundary_constraint_eval_quotient_by_mask(
             point,
-            &mask[0][0][..1].try_into().unwrap(),
+            &mask[BASE_TRACE][0][..1].try_into().unwrap(),
         ));
     }
 }
@@ -7,12 +7,13 @@ use self::component::FibonacciComponent;
 use crate::core::backend::cpu::CpuCircleEvaluation;
 use crate::core::channel::{Blake2sChannel, Channel};
 use crate::core::fields::m31::BaseField;
-use crate::core::fields::{FieldExpOps, IntoSlice};
+use crate::core::fields::FieldExpOps;
+use crate::core::pcs::PcsConfig;
 use crate::core::poly::circle::{CanonicCoset, CircleEvaluation};
 use crate::core::poly::BitReversedOrder;
 use crate::core::prover::{ProvingError, StarkProof, VerificationError};
-use crate::core::vcs::blake2_hash::Blake2sHasher;
-use crate::core::vcs::hasher::Hasher;
+use crate::core::vcs::blake2_hash::Blake2sHash;
+use crate::core::vcs::blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher};
 use crate::trace_generation::{commit_and_prove, commit_and_verify};
 
 pub mod air;
@@ -50,21 +51,20 @@ impl Fibonacci {
         CircleEvaluation::new_canonical_ordered(trace_domain, trace)
     }
 
-    pub fn prove(&self) -> Result<StarkProof, ProvingError> {
+    pub fn prove(&self) -> Result<StarkProof<Blake2sMerkleHasher>, ProvingError> {
         let trace = self.get_trace();
-        let channel = &mut Blake2sChannel::new(Blake2sHasher::hash(BaseField::into_slice(&[self
-            .air
-            .component
-            .claim])));
-        commit_and_prove(&self.air, channel, vec![trace])
+        let channel = &mut Blake2sChannel::new(Blake2sHash::default());
+        commit_and_prove::<_, Blake2sMerkleChannel>(
+            &self.air,
+            channel,
+            vec![trace],
+            PcsConfig::default(),
+        )
     }
 
-    pub fn verify(&self, proof: StarkProof) -> Result<(), VerificationError> {
-        let channel = &mut Blake2sChannel::new(Blake2sHasher::hash(BaseField::into_slice(&[self
-            .air
-            .component
-            .claim])));
-        commit_and_verify(proof, &self.air, channel)
+    pub fn verify(&self, proof: StarkProof<Blake2sMerkleHasher>) -> Result<(), VerificationError> {
+        let channel = &mut Blake2sChannel::new(Blake2sHash::default());
+        commit_and_verify::<Blake2sMerkleChannel>(proof, &self.air, channel, PcsConfig::default())
     }
 }
 
@@ -95,17 +95,15 @@ impl MultiFibonacci {
             .collect()
     }
 
-    pub fn prove(&self) -> Result<StarkProof, ProvingError> {
-        let channel =
-            &mut Blake2sChannel::new(Blake2sHasher::hash(BaseField::into_slice(&self.claims)));
+    pub fn prove(&self) -> Result<StarkProof<Blake2sMerkleHasher>, ProvingError> {
+        let channel = &mut Blake2sChannel::new(Blake2sHash::default());
         let trace = self.get_trace();
-        commit_and_prove(&self.air, channel, trace)
+        commit_and_prove::<_, Blake2sMerkleChannel>(&self.air, channel, trace, PcsConfig::default())
     }
 
-    pub fn verify(&self, proof: StarkProof) -> Result<(), VerificationError> {
-        let channel =
-            &mut Blake2sChannel::new(Blake2sHasher::hash(BaseField::into_slice(&self.claims)));
-        commit_and_verify(proof, &self.air, channel)
+    pub fn verify(&self, proof: StarkProof<Blake2sMerkleHasher>) -> Result<(), VerificationError> {
+        let channel = &mut Blake2sChannel::new(Blake2sHash::default());
+        commit_and_verify::<Blake2sMerkleChannel>(proof, &self.air, channel, PcsConfig::default())
     }
 }
 
@@ -118,27 +116,43 @@ mod tests {
     use num_traits::One;
     use rand::rngs::SmallRng;
     use rand::{Rng, SeedableRng};
+    use starknet_ff::FieldElement as FieldElement252;
 
     use super::{Fibonacci, MultiFibonacci};
     use crate::core::air::accumulation::PointEvaluationAccumulator;
     use crate::core::air::{AirExt, AirProverExt, Component, ComponentTrace};
-    use crate::core::channel::{Blake2sChannel, Channel};
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::backend::CpuBackend;
+    use crate::core::channel::{
+        Blake2sChannel, Blake3Channel, Channel, Poseidon252Channel, Poseidon2M31Channel,
+    };
     use crate::core::circle::CirclePoint;
     use crate::core::fields::m31::BaseField;
     use crate::core::fields::qm31::SecureField;
+    use crate::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
     use crate::core::fields::IntoSlice;
-    use crate::core::pcs::TreeVec;
+    use crate::core::fri::{FriConfig, FriVerifierTwiddles};
+    use crate::core::pcs::{PcsConfig, TreeVec};
     use crate::core::poly::circle::CanonicCoset;
-    use crate::core::prover::VerificationError;
+    use crate::core::prover::{ProofShapeError, StarkProof, VerificationError};
     use crate::core::queries::Queries;
+    use crate::core::test_utils::test_channel;
     use crate::core::utils::bit_reverse;
-    use crate::core::vcs::blake2_hash::Blake2sHasher;
+    use crate::core::vcs::blake2_hash::{Blake2sHash, Blake2sHasher};
+    use crate::core::vcs::blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher};
+    use crate::core::vcs::blake3_hash::Blake3Hash;
+    use crate::core::vcs::blake3_merkle::Blake3MerkleChannel;
     use crate::core::vcs::hasher::Hasher;
+    use crate::core::vcs::poseidon252_merkle::Poseidon252MerkleChannel;
+    use crate::core::vcs::poseidon2_m31::Poseidon2M31Hash;
+    use crate::core::vcs::poseidon2_m31_merkle::Poseidon2M31MerkleChannel;
+    use crate::core::vcs::verifier::{MerkleConfig, MerkleVerificationError};
     use crate::core::{InteractionElements, LookupValues};
     use crate::examples::fibonacci::air::FibonacciAirGenerator;
     use crate::examples::fibonacci::component::FibonacciInput;
     use crate::trace_generation::{
-        commit_and_prove, commit_and_verify, AirTraceGenerator, BASE_TRACE,
+        commit_and_prove, commit_and_verify, commit_and_verify_with_twiddles, AirTraceGenerator,
+        BASE_TRACE,
     };
     use crate::{m31, qm31};
 
@@ -160,8 +174,8 @@ mod tests {
         let trace_eval =
             trace_poly.evaluate(CanonicCoset::new(trace_poly.log_size() + 1).circle_domain());
         let trace = ComponentTrace::new(
-            TreeVec::new(vec![vec![&trace_poly]]),
-            TreeVec::new(vec![vec![&trace_eval]]),
+            TreeVec::new(vec![vec![], vec![&trace_poly]]),
+            TreeVec::new(vec![vec![], vec![&trace_eval]]),
         );
 
         let random_coeff = qm31!(2213980, 2213981, 2213982, 2213983);
@@ -178,7 +192,7 @@ mod tests {
         let point = CirclePoint::<SecureField>::get_point(98989892);
 
         let points = fib.air.mask_points(point);
-        let mask_values = zip(&component_traces[0].polys[BASE_TRACE], &points[0])
+        let mask_values = zip(&component_traces[0].polys[BASE_TRACE], &points[BASE_TRACE])
             .map(|(poly, points)| {
                 points
                     .iter()
@@ -190,7 +204,7 @@ mod tests {
         let mut evaluation_accumulator = PointEvaluationAccumulator::new(random_coeff);
         fib.air.component.evaluate_constraint_quotients_at_point(
             point,
-            &TreeVec::new(vec![mask_values]),
+            &TreeVec::new(vec![vec![], mask_values]),
             &mut evaluation_accumulator,
             &InteractionElements::default(),
             &LookupValues::default(),
@@ -255,12 +269,93 @@ mod tests {
         let trace = fib_trace_generator.write_trace();
         let channel =
             &mut Blake2sChannel::new(Blake2sHasher::hash(BaseField::into_slice(&[CLAIM])));
-        let proof = commit_and_prove(&fib_trace_generator, channel, trace).unwrap();
-
+        let proof = commit_and_prove::<_, Blake2sMerkleChannel>(
+            &fib_trace_generator,
+            channel,
+            trace,
+            PcsConfig::default(),
+        )
+        .unwrap();
+
+        let proof = StarkProof::from_bytes(&proof.to_bytes()).unwrap();
         let channel =
             &mut Blake2sChannel::new(Blake2sHasher::hash(BaseField::into_slice(&[CLAIM])));
         let fib_air = Fibonacci::new(FIB_LOG_SIZE, CLAIM).air;
-        commit_and_verify(proof, &fib_air, channel).unwrap();
+        commit_and_verify::<Blake2sMerkleChannel>(proof, &fib_air, channel, PcsConfig::default())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_fib_prove_with_poseidon() {
+        const FIB_LOG_SIZE: u32 = 5;
+        let fib = Fibonacci::new(FIB_LOG_SIZE, m31!(443693538));
+        let trace = fib.get_trace();
+
+        let channel = &mut Poseidon252Channel::new(FieldElement252::default());
+        let proof = commit_and_prove::<CpuBackend, Poseidon252MerkleChannel>(
+            &fib.air,
+            channel,
+            vec![trace],
+            PcsConfig::default(),
+        )
+        .unwrap();
+
+        let proof = StarkProof::from_bytes(&proof.to_bytes()).unwrap();
+        let channel = &mut Poseidon252Channel::new(FieldElement252::default());
+        commit_and_verify::<Poseidon252MerkleChannel>(
+            proof,
+            &fib.air,
+            channel,
+            PcsConfig::default(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_fib_prove_with_blake3() {
+        const FIB_LOG_SIZE: u32 = 5;
+        let fib = Fibonacci::new(FIB_LOG_SIZE, m31!(443693538));
+        let trace = fib.get_trace();
+
+        let channel = &mut Blake3Channel::new(Blake3Hash::default());
+        let proof = commit_and_prove::<CpuBackend, Blake3MerkleChannel>(
+            &fib.air,
+            channel,
+            vec![trace],
+            PcsConfig::default(),
+        )
+        .unwrap();
+
+        let proof = StarkProof::from_bytes(&proof.to_bytes()).unwrap();
+        let channel = &mut Blake3Channel::new(Blake3Hash::default());
+        commit_and_verify::<Blake3MerkleChannel>(proof, &fib.air, channel, PcsConfig::default())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_fib_prove_with_poseidon2_m31() {
+        const FIB_LOG_SIZE: u32 = 5;
+        let fib = Fibonacci::new(FIB_LOG_SIZE, m31!(443693538));
+        let trace = fib.get_trace();
+
+        let channel = &mut Poseidon2M31Channel::new(Poseidon2M31Hash::default());
+        let proof = commit_and_prove::<CpuBackend, Poseidon2M31MerkleChannel>(
+            &fib.air,
+            channel,
+            vec![trace],
+            PcsConfig::default(),
+        )
+        .unwrap();
+
+        let proof = StarkProof::from_bytes(&proof.to_bytes()).unwrap();
+        let channel = &mut Poseidon2M31Channel::new(Poseidon2M31Hash::default());
+        commit_and_verify::<Poseidon2M31MerkleChannel>(
+            proof,
+            &fib.air,
+            channel,
+            PcsConfig::default(),
+        )
+        .unwrap();
     }
 
     #[test]
@@ -273,7 +368,13 @@ mod tests {
             BaseField::one();
 
         let error = fib.verify(invalid_proof).unwrap_err();
-        assert_matches!(error, VerificationError::Merkle(_));
+        assert_matches!(
+            error,
+            VerificationError::Merkle {
+                tree: BASE_TRACE,
+                error: MerkleVerificationError::RootMismatch
+            }
+        );
     }
 
     #[test]
@@ -285,13 +386,17 @@ mod tests {
         invalid_proof
             .commitment_scheme_proof
             .sampled_values
-            .swap(0, 1);
+            .swap(BASE_TRACE, BASE_TRACE + 1);
 
         let error = fib.verify(invalid_proof).unwrap_err();
-        assert_matches!(error, VerificationError::InvalidStructure(_));
-        assert!(error
-            .to_string()
-            .contains("Unexpected sampled_values structure"));
+        assert_matches!(
+            error,
+            VerificationError::InvalidStructure(ProofShapeError::NumSampledColumns {
+                tree: BASE_TRACE,
+                expected: 1,
+                actual: SECURE_EXTENSION_DEGREE,
+            })
+        );
     }
 
     #[test]
@@ -303,7 +408,190 @@ mod tests {
         invalid_proof.commitment_scheme_proof.queried_values.0[BASE_TRACE][0].pop();
 
         let error = fib.verify(invalid_proof).unwrap_err();
-        assert_matches!(error, VerificationError::Merkle(_));
+        assert_matches!(
+            error,
+            VerificationError::Merkle {
+                tree: BASE_TRACE,
+                error: MerkleVerificationError::ColumnValuesTooShort
+            }
+        );
+    }
+
+    #[test]
+    fn test_prove_missing_tree() {
+        const FIB_LOG_SIZE: u32 = 5;
+        let fib = Fibonacci::new(FIB_LOG_SIZE, m31!(443693538));
+
+        let mut invalid_proof = fib.prove().unwrap();
+        invalid_proof.commitments.pop();
+
+        let error = fib.verify(invalid_proof).unwrap_err();
+        assert_matches!(
+            error,
+            VerificationError::InvalidStructure(ProofShapeError::NumTrees {
+                expected: 3,
+                actual: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn test_fib_prove_with_custom_pcs_config() {
+        const FIB_LOG_SIZE: u32 = 5;
+        let fib = Fibonacci::new(FIB_LOG_SIZE, m31!(443693538));
+        let config = PcsConfig {
+            pow_bits: 5,
+            fri_config: FriConfig::new(1, 1, 8),
+            ..PcsConfig::default()
+        };
+
+        let proof = commit_and_prove::<_, Blake2sMerkleChannel>(
+            &fib.air,
+            &mut test_channel(),
+            vec![fib.get_trace()],
+            config.clone(),
+        )
+        .unwrap();
+
+        commit_and_verify::<Blake2sMerkleChannel>(proof, &fib.air, &mut test_channel(), config)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_fib_prove_with_larger_blowup_factors() {
+        const FIB_LOG_SIZE: u32 = 5;
+        let fib = Fibonacci::new(FIB_LOG_SIZE, m31!(443693538));
+        for log_blowup_factor in 1..=4 {
+            let config = PcsConfig {
+                pow_bits: 5,
+                fri_config: FriConfig::new(0, log_blowup_factor, 8),
+                ..PcsConfig::default()
+            };
+
+            let proof = commit_and_prove::<_, Blake2sMerkleChannel>(
+                &fib.air,
+                &mut test_channel(),
+                vec![fib.get_trace()],
+                config.clone(),
+            )
+            .unwrap();
+
+            commit_and_verify::<Blake2sMerkleChannel>(proof, &fib.air, &mut test_channel(), config)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_fib_prove_with_fold_steps() {
+        const FIB_LOG_SIZE: u32 = 5;
+        let fib = Fibonacci::new(FIB_LOG_SIZE, m31!(443693538));
+        for fold_step in FriConfig::FOLD_STEP_RANGE {
+            let config = PcsConfig {
+                pow_bits: 5,
+                fri_config: FriConfig::new(0, 1, 8).with_fold_step(fold_step),
+                ..PcsConfig::default()
+            };
+
+            let proof = commit_and_prove::<_, Blake2sMerkleChannel>(
+                &fib.air,
+                &mut test_channel(),
+                vec![fib.get_trace()],
+                config.clone(),
+            )
+            .unwrap();
+
+            let proof = StarkProof::from_bytes(&proof.to_bytes()).unwrap();
+            commit_and_verify::<Blake2sMerkleChannel>(proof, &fib.air, &mut test_channel(), config)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_fib_prove_with_merkle_caps() {
+        const FIB_LOG_SIZE: u32 = 5;
+        const COMPOSITION_TREE: usize = BASE_TRACE + 1;
+        let fib = Fibonacci::new(FIB_LOG_SIZE, m31!(443693538));
+        let config = PcsConfig::default()
+            .with_merkle_config(BASE_TRACE, MerkleConfig::default().with_log_cap_size(3))
+            .with_merkle_config(
+                COMPOSITION_TREE,
+                MerkleConfig::new(2, 1).with_log_cap_size(2),
+            );
+
+        let proof = commit_and_prove::<_, Blake2sMerkleChannel>(
+            &fib.air,
+            &mut test_channel(),
+            vec![fib.get_trace()],
+            config.clone(),
+        )
+        .unwrap();
+        let bytes = proof.to_bytes();
+        let decoded = StarkProof::<Blake2sMerkleHasher>::from_bytes(&bytes).unwrap();
+
+        assert_eq!(
+            proof.commitments.iter().map(Vec::len).collect_vec(),
+            [1, 8, 4]
+        );
+        assert_eq!(decoded.to_bytes(), bytes);
+        commit_and_verify::<Blake2sMerkleChannel>(decoded, &fib.air, &mut test_channel(), config)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_verify_with_mismatched_pcs_config_fails() {
+        const FIB_LOG_SIZE: u32 = 5;
+        let fib = Fibonacci::new(FIB_LOG_SIZE, m31!(443693538));
+        let proof = commit_and_prove::<_, Blake2sMerkleChannel>(
+            &fib.air,
+            &mut test_channel(),
+            vec![fib.get_trace()],
+            PcsConfig::default(),
+        )
+        .unwrap();
+        let stronger_config = PcsConfig {
+            pow_bits: PcsConfig::default().pow_bits + 1,
+            ..PcsConfig::default()
+        };
+
+        let error = commit_and_verify::<Blake2sMerkleChannel>(
+            proof,
+            &fib.air,
+            &mut test_channel(),
+            stronger_config,
+        )
+        .unwrap_err();
+
+        assert_matches!(error, VerificationError::ConfigMismatch);
+    }
+
+    #[test]
+    fn test_fib_verify_with_twiddles_matches_verify() {
+        const FIB_LOG_SIZE: u32 = 5;
+        let fib = Fibonacci::new(FIB_LOG_SIZE, m31!(443693538));
+        let proof_bytes = fib.prove().unwrap().to_bytes();
+        let mut invalid_proof =
+            StarkProof::<Blake2sMerkleHasher>::from_bytes(&proof_bytes).unwrap();
+        invalid_proof.commitment_scheme_proof.fri_proof.inner_layers[0].evals_subset[0] +=
+            SecureField::one();
+        let invalid_proof_bytes = invalid_proof.to_bytes();
+        // Covers the largest committed evaluation, the composition polynomial's.
+        let twiddles = FriVerifierTwiddles::new(
+            FIB_LOG_SIZE + 1 + PcsConfig::default().fri_config.log_blowup_factor,
+        );
+
+        for (bytes, is_valid) in [(proof_bytes, true), (invalid_proof_bytes, false)] {
+            let expected = fib.verify(StarkProof::from_bytes(&bytes).unwrap());
+            let actual = commit_and_verify_with_twiddles::<Blake2sMerkleChannel, SimdBackend>(
+                StarkProof::from_bytes(&bytes).unwrap(),
+                &fib.air,
+                &mut Blake2sChannel::new(Blake2sHash::default()),
+                PcsConfig::default(),
+                &twiddles,
+            );
+
+            assert_eq!(expected.is_ok(), is_valid);
+            assert_eq!(format!("{actual:?}"), format!("{expected:?}"));
+        }
     }
 
     #[test]
@@ -321,6 +609,7 @@ mod tests {
             vec![m31!(1056169651), m31!(443693538), m31!(722122436)],
         );
         let proof = multi_fib.prove().unwrap();
+        let proof = StarkProof::from_bytes(&proof.to_bytes()).unwrap();
         multi_fib.verify(proof).unwrap();
     }
 }
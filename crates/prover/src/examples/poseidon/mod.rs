@@ -8,12 +8,12 @@ use tracing::{span, Level};
 use crate::constraint_framework::{EvalAtRow, PointEvaluator, SimdDomainEvaluator};
 use crate::core::air::accumulation::{DomainEvaluationAccumulator, PointEvaluationAccumulator};
 use crate::core::air::mask::fixed_mask_points;
-use crate::core::air::{Air, AirProver, Component, ComponentProver, ComponentTrace};
+use crate::core::air::{Air, AirProver, Component, ComponentProver, ComponentTrace, PublicInput};
 use crate::core::backend::simd::column::BaseFieldVec;
 use crate::core::backend::simd::m31::{PackedBaseField, LOG_N_LANES};
 use crate::core::backend::simd::SimdBackend;
 use crate::core::backend::{Col, Column, ColumnOps};
-use crate::core::channel::Blake2sChannel;
+use crate::core::channel::Channel;
 use crate::core::circle::CirclePoint;
 use crate::core::constraints::coset_vanishing;
 use crate::core::fields::m31::BaseField;
@@ -24,7 +24,9 @@ use crate::core::poly::circle::{CanonicCoset, CircleEvaluation, PolyOps};
 use crate::core::poly::BitReversedOrder;
 use crate::core::prover::VerificationError;
 use crate::core::{ColumnVec, InteractionElements, LookupValues};
-use crate::trace_generation::{AirTraceGenerator, AirTraceVerifier, ComponentTraceGenerator};
+use crate::trace_generation::{
+    AirTraceGenerator, AirTraceVerifier, ComponentTraceGenerator, BASE_TRACE,
+};
 
 const N_LOG_INSTANCES_PER_ROW: usize = 3;
 const N_INSTANCES_PER_ROW: usize = 1 << N_LOG_INSTANCES_PER_ROW;
@@ -66,13 +68,23 @@ impl Air for PoseidonAir {
         vec![&self.component]
     }
 
+    fn public_input(&self) -> impl PublicInput {}
+
     fn verify_lookups(&self, _lookup_values: &LookupValues) -> Result<(), VerificationError> {
         Ok(())
     }
 }
 
 impl AirTraceVerifier for PoseidonAir {
-    fn interaction_elements(&self, _channel: &mut Blake2sChannel) -> InteractionElements {
+    fn n_interaction_phases(&self) -> usize {
+        0
+    }
+
+    fn interaction_elements(
+        &self,
+        _phase: usize,
+        _channel: &mut impl Channel,
+    ) -> InteractionElements {
         InteractionElements::default()
     }
 }
@@ -80,7 +92,8 @@ impl AirTraceVerifier for PoseidonAir {
 impl AirTraceGenerator<SimdBackend> for PoseidonAir {
     fn interact(
         &self,
-        _trace: &ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+        _phase: usize,
+        _traces: &TreeVec<ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>>,
         _elements: &InteractionElements,
     ) -> Vec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
         vec![]
@@ -105,17 +118,17 @@ impl Component for PoseidonComponent {
     }
 
     fn trace_log_degree_bounds(&self) -> TreeVec<ColumnVec<u32>> {
-        TreeVec::new(vec![vec![self.log_column_size(); N_COLUMNS]])
+        TreeVec::new(vec![vec![], vec![self.log_column_size(); N_COLUMNS]])
     }
 
     fn mask_points(
         &self,
         point: CirclePoint<SecureField>,
     ) -> TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>> {
-        TreeVec::new(vec![fixed_mask_points(
-            &vec![vec![0_usize]; N_COLUMNS],
-            point,
-        )])
+        TreeVec::new(vec![
+            vec![],
+            fixed_mask_points(&vec![vec![0_usize]; N_COLUMNS], point),
+        ])
     }
 
     fn evaluate_constraint_quotients_at_point(
@@ -135,7 +148,7 @@ impl Component for PoseidonComponent {
         for _ in 0..N_INSTANCES_PER_ROW {
             poseidon_eval.eval();
         }
-        assert_eq!(poseidon_eval.eval.col_index[0], N_COLUMNS);
+        assert_eq!(poseidon_eval.eval.col_index[BASE_TRACE], N_COLUMNS);
     }
 }
 
@@ -364,7 +377,7 @@ impl ComponentProver<SimdBackend> for PoseidonComponent {
         _interaction_elements: &InteractionElements,
         _lookup_values: &LookupValues,
     ) {
-        assert_eq!(trace.polys[0].len(), self.n_columns());
+        assert_eq!(trace.polys[BASE_TRACE].len(), self.n_columns());
         let eval_domain = CanonicCoset::new(self.log_column_size() + LOG_EXPAND).circle_domain();
 
         // Create a new evaluation.
@@ -436,18 +449,22 @@ mod tests {
 
     use itertools::Itertools;
     use num_traits::One;
+    use starknet_ff::FieldElement as FieldElement252;
     use tracing::{span, Level};
 
     use super::{PoseidonEval, N_LOG_INSTANCES_PER_ROW};
     use crate::constraint_framework::assert_constraints;
     use crate::core::backend::simd::SimdBackend;
-    use crate::core::channel::{Blake2sChannel, Channel};
+    use crate::core::channel::{Blake2sChannel, Channel, Poseidon252Channel};
     use crate::core::fields::m31::BaseField;
     use crate::core::fields::IntoSlice;
-    use crate::core::pcs::TreeVec;
+    use crate::core::pcs::{PcsConfig, TreeVec};
     use crate::core::poly::circle::CanonicCoset;
+    use crate::core::prover::StarkProof;
     use crate::core::vcs::blake2_hash::Blake2sHasher;
+    use crate::core::vcs::blake2_merkle::Blake2sMerkleChannel;
     use crate::core::vcs::hasher::Hasher;
+    use crate::core::vcs::poseidon252_merkle::Poseidon252MerkleChannel;
     use crate::examples::poseidon::{
         apply_internal_round_matrix, apply_m4, gen_trace, PoseidonAir, PoseidonComponent,
     };
@@ -498,10 +515,10 @@ mod tests {
             log_n_rows: LOG_N_ROWS,
         };
         let trace = gen_trace(component.log_column_size());
-        let trace_polys = TreeVec::new(vec![trace
-            .into_iter()
-            .map(|c| c.interpolate())
-            .collect_vec()]);
+        let trace_polys = TreeVec::new(vec![
+            vec![],
+            trace.into_iter().map(|c| c.interpolate()).collect_vec(),
+        ]);
         assert_constraints(&trace_polys, CanonicCoset::new(LOG_N_ROWS), |eval| {
             PoseidonEval { eval }.eval();
         });
@@ -527,9 +544,38 @@ mod tests {
 
         let channel = &mut Blake2sChannel::new(Blake2sHasher::hash(BaseField::into_slice(&[])));
         let air = PoseidonAir { component };
-        let proof = commit_and_prove::<SimdBackend>(&air, channel, trace).unwrap();
-
+        let proof = commit_and_prove::<SimdBackend, Blake2sMerkleChannel>(
+            &air,
+            channel,
+            trace,
+            PcsConfig::default(),
+        )
+        .unwrap();
+
+        let proof = StarkProof::from_bytes(&proof.to_bytes()).unwrap();
         let channel = &mut Blake2sChannel::new(Blake2sHasher::hash(BaseField::into_slice(&[])));
-        commit_and_verify(proof, &air, channel).unwrap();
+        commit_and_verify::<Blake2sMerkleChannel>(proof, &air, channel, PcsConfig::default())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_simd_poseidon_prove_with_poseidon252_merkle() {
+        let component = PoseidonComponent { log_n_rows: 7 };
+        let trace = gen_trace(component.log_column_size());
+
+        let channel = &mut Poseidon252Channel::new(FieldElement252::default());
+        let air = PoseidonAir { component };
+        let proof = commit_and_prove::<SimdBackend, Poseidon252MerkleChannel>(
+            &air,
+            channel,
+            trace,
+            PcsConfig::default(),
+        )
+        .unwrap();
+
+        let proof = StarkProof::from_bytes(&proof.to_bytes()).unwrap();
+        let channel = &mut Poseidon252Channel::new(FieldElement252::default());
+        commit_and_verify::<Poseidon252MerkleChannel>(proof, &air, channel, PcsConfig::default())
+            .unwrap();
     }
 }
@@ -2,7 +2,7 @@ use itertools::Itertools;
 
 use crate::core::air::accumulation::PointEvaluationAccumulator;
 use crate::core::air::mask::fixed_mask_points;
-use crate::core::air::{Air, Component};
+use crate::core::air::{Air, Component, PublicInput};
 use crate::core::backend::cpu::CpuCircleEvaluation;
 use crate::core::backend::CpuBackend;
 use crate::core::circle::{CirclePoint, Coset};
@@ -19,7 +19,7 @@ use crate::core::utils::shifted_secure_combination;
 use crate::core::{ColumnVec, InteractionElements, LookupValues};
 use crate::examples::wide_fibonacci::trace_gen::write_lookup_column;
 use crate::trace_generation::registry::ComponentGenerationRegistry;
-use crate::trace_generation::ComponentTraceGenerator;
+use crate::trace_generation::{ComponentTraceGenerator, BASE_TRACE, INTERACTION_TRACE};
 
 pub const LOG_N_COLUMNS: usize = 8;
 pub const N_COLUMNS: usize = 1 << LOG_N_COLUMNS;
@@ -68,19 +68,21 @@ impl WideFibComponent {
         constraint_zero_domain: Coset,
         lookup_values: &LookupValues,
     ) {
-        let numerator = mask[0][0][0] - lookup_values[LOOKUP_VALUE_0_ID];
+        let numerator = mask[BASE_TRACE][0][0] - lookup_values[LOOKUP_VALUE_0_ID];
         let denom = point_vanishing(constraint_zero_domain.at(0), point);
         evaluation_accumulator.accumulate(numerator / denom);
-        let numerator = mask[0][1][0] - lookup_values[LOOKUP_VALUE_1_ID];
+        let numerator = mask[BASE_TRACE][1][0] - lookup_values[LOOKUP_VALUE_1_ID];
         evaluation_accumulator.accumulate(numerator / denom);
 
-        let numerator = mask[0][self.n_columns() - 2][0] - lookup_values[LOOKUP_VALUE_N_MINUS_2_ID];
+        let numerator =
+            mask[BASE_TRACE][self.n_columns() - 2][0] - lookup_values[LOOKUP_VALUE_N_MINUS_2_ID];
         let denom = point_vanishing(
             constraint_zero_domain.at(constraint_zero_domain.size() - 1),
             point,
         );
         evaluation_accumulator.accumulate(numerator / denom);
-        let numerator = mask[0][self.n_columns() - 1][0] - lookup_values[LOOKUP_VALUE_N_MINUS_1_ID];
+        let numerator =
+            mask[BASE_TRACE][self.n_columns() - 1][0] - lookup_values[LOOKUP_VALUE_N_MINUS_1_ID];
         evaluation_accumulator.accumulate(numerator / denom);
     }
 
@@ -109,17 +111,22 @@ impl WideFibComponent {
         lookup_values: &LookupValues,
     ) {
         let (alpha, z) = (interaction_elements[ALPHA_ID], interaction_elements[Z_ID]);
-        let value = SecureField::from_partial_evals(std::array::from_fn(|i| mask[1][i][0]));
+        let value =
+            SecureField::from_partial_evals(std::array::from_fn(|i| mask[INTERACTION_TRACE][i][0]));
         let numerator = (value
             * shifted_secure_combination(
                 &[
-                    mask[0][self.n_columns() - 2][0],
-                    mask[0][self.n_columns() - 1][0],
+                    mask[BASE_TRACE][self.n_columns() - 2][0],
+                    mask[BASE_TRACE][self.n_columns() - 1][0],
                 ],
                 alpha,
                 z,
             ))
-            - shifted_secure_combination(&[mask[0][0][0], mask[0][1][0]], alpha, z);
+            - shifted_secure_combination(
+                &[mask[BASE_TRACE][0][0], mask[BASE_TRACE][1][0]],
+                alpha,
+                z,
+            );
         let denom = point_vanishing(constraint_zero_domain.at(0), point);
         evaluation_accumulator.accumulate(numerator / denom);
 
@@ -156,18 +163,25 @@ impl WideFibComponent {
         interaction_elements: &InteractionElements,
     ) {
         let (alpha, z) = (interaction_elements[ALPHA_ID], interaction_elements[Z_ID]);
-        let value = SecureField::from_partial_evals(std::array::from_fn(|i| mask[1][i][0]));
-        let prev_value = SecureField::from_partial_evals(std::array::from_fn(|i| mask[1][i][1]));
+        let value =
+            SecureField::from_partial_evals(std::array::from_fn(|i| mask[INTERACTION_TRACE][i][0]));
+        let prev_value =
+            SecureField::from_partial_evals(std::array::from_fn(|i| mask[INTERACTION_TRACE][i][1]));
         let numerator = (value
             * shifted_secure_combination(
                 &[
-                    mask[0][self.n_columns() - 2][0],
-                    mask[0][self.n_columns() - 1][0],
+                    mask[BASE_TRACE][self.n_columns() - 2][0],
+                    mask[BASE_TRACE][self.n_columns() - 1][0],
                 ],
                 alpha,
                 z,
             ))
-            - (prev_value * shifted_secure_combination(&[mask[0][0][0], mask[0][1][0]], alpha, z));
+            - (prev_value
+                * shifted_secure_combination(
+                    &[mask[BASE_TRACE][0][0], mask[BASE_TRACE][1][0]],
+                    alpha,
+                    z,
+                ));
         let denom = coset_vanishing(constraint_zero_domain, point)
             / point_excluder(constraint_zero_domain.at(0), point);
         evaluation_accumulator.accumulate(numerator / denom);
@@ -184,7 +198,19 @@ impl Air for WideFibAir {
         vec![&self.component]
     }
 
-    fn verify_lookups(&self, _lookup_values: &LookupValues) -> Result<(), VerificationError> {
+    fn public_input(&self) -> impl PublicInput {}
+
+    fn verify_lookups(&self, lookup_values: &LookupValues) -> Result<(), VerificationError> {
+        // The values are tied to the trace by the boundary constraints, they only need to be
+        // present.
+        for id in [
+            LOOKUP_VALUE_0_ID,
+            LOOKUP_VALUE_1_ID,
+            LOOKUP_VALUE_N_MINUS_2_ID,
+            LOOKUP_VALUE_N_MINUS_1_ID,
+        ] {
+            lookup_values.get(id)?;
+        }
         Ok(())
     }
 }
@@ -200,6 +226,7 @@ impl Component for WideFibComponent {
 
     fn trace_log_degree_bounds(&self) -> TreeVec<ColumnVec<u32>> {
         TreeVec::new(vec![
+            vec![],
             vec![self.log_column_size(); self.n_columns()],
             vec![self.log_column_size(); SECURE_EXTENSION_DEGREE],
         ])
@@ -211,6 +238,7 @@ impl Component for WideFibComponent {
     ) -> TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>> {
         let domain = CanonicCoset::new(self.log_column_size());
         TreeVec::new(vec![
+            vec![],
             fixed_mask_points(&vec![vec![0_usize]; self.n_columns()], point),
             vec![vec![point, point - domain.step().into_ef()]; SECURE_EXTENSION_DEGREE],
         ])
@@ -249,7 +277,7 @@ impl Component for WideFibComponent {
         );
         self.evaluate_trace_step_constraints_at_point(
             point,
-            &mask[0],
+            &mask[BASE_TRACE],
             evaluation_accumulator,
             constraint_zero_domain,
         );
@@ -11,7 +11,7 @@ use super::trace_gen::write_trace_row;
 use crate::core::air::accumulation::{ColumnAccumulator, DomainEvaluationAccumulator};
 use crate::core::air::{AirProver, Component, ComponentProver, ComponentTrace};
 use crate::core::backend::CpuBackend;
-use crate::core::channel::{Blake2sChannel, Channel};
+use crate::core::channel::Channel;
 use crate::core::circle::Coset;
 use crate::core::constraints::{coset_vanishing, point_excluder};
 use crate::core::fields::m31::BaseField;
@@ -33,7 +33,15 @@ use crate::trace_generation::{
 // TODO(AlonH): Rename file to `cpu.rs`.
 
 impl AirTraceVerifier for WideFibAir {
-    fn interaction_elements(&self, channel: &mut Blake2sChannel) -> InteractionElements {
+    fn n_interaction_phases(&self) -> usize {
+        1
+    }
+
+    fn interaction_elements(
+        &self,
+        _phase: usize,
+        channel: &mut impl Channel,
+    ) -> InteractionElements {
         let ids = self.component.interaction_element_ids();
         let elements = channel.draw_felts(ids.len());
         InteractionElements::new(BTreeMap::from_iter(zip_eq(ids, elements)))
@@ -43,11 +51,12 @@ impl AirTraceVerifier for WideFibAir {
 impl AirTraceGenerator<CpuBackend> for WideFibAir {
     fn interact(
         &self,
-        trace: &ColumnVec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>>,
+        _phase: usize,
+        traces: &TreeVec<ColumnVec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>>>,
         elements: &InteractionElements,
     ) -> Vec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>> {
         self.component
-            .write_interaction_trace(&trace.iter().collect(), elements)
+            .write_interaction_trace(&traces[BASE_TRACE].iter().collect(), elements)
     }
 
     fn to_air_prover(&self) -> impl AirProver<CpuBackend> {
@@ -258,7 +267,22 @@ impl ComponentProver<CpuBackend> for WideFibComponent {
     ) {
         let max_constraint_degree = self.max_constraint_log_degree_bound();
         let trace_eval_domain = CanonicCoset::new(max_constraint_degree).circle_domain();
-        let trace_evals = &trace.evals;
+        // The committed evaluations can only be reused if they are on the constraint evaluation
+        // domain, i.e. if the blowup factor matches the constraint degree.
+        let reevaluated_trace;
+        let reevaluated_trace_ref;
+        let trace_evals = if trace.evals[BASE_TRACE][0].domain.log_size() == max_constraint_degree {
+            &trace.evals
+        } else {
+            reevaluated_trace = trace
+                .polys
+                .as_cols_ref()
+                .map_cols(|poly| poly.evaluate(trace_eval_domain));
+            reevaluated_trace_ref = reevaluated_trace
+                .as_ref()
+                .map(|tree| tree.iter().collect_vec());
+            &reevaluated_trace_ref
+        };
         let zero_domain = CanonicCoset::new(self.log_column_size()).coset;
         let [mut accum] =
             evaluation_accumulator.columns([(max_constraint_degree, self.n_constraints())]);
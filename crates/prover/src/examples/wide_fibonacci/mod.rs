@@ -10,7 +10,7 @@ mod tests {
     use itertools::Itertools;
     use num_traits::{One, Zero};
 
-    use super::component::{Input, WideFibAir, WideFibComponent, LOG_N_COLUMNS};
+    use super::component::{Input, WideFibAir, WideFibComponent, LOG_N_COLUMNS, LOOKUP_VALUE_0_ID};
     use super::constraint_eval::gen_trace;
     use crate::core::air::accumulation::DomainEvaluationAccumulator;
     use crate::core::air::{Component, ComponentProver, ComponentTrace};
@@ -20,12 +20,16 @@ mod tests {
     use crate::core::fields::m31::BaseField;
     use crate::core::fields::qm31::SecureField;
     use crate::core::fields::IntoSlice;
-    use crate::core::pcs::TreeVec;
+    use crate::core::fri::FriConfig;
+    use crate::core::pcs::{PcsConfig, TreeVec};
     use crate::core::poly::circle::CanonicCoset;
+    use crate::core::prover::{StarkProof, VerificationError};
+    use crate::core::test_utils::test_channel;
     use crate::core::utils::{
         bit_reverse, circle_domain_order_to_coset_order, shifted_secure_combination,
     };
     use crate::core::vcs::blake2_hash::Blake2sHasher;
+    use crate::core::vcs::blake2_merkle::Blake2sMerkleChannel;
     use crate::core::vcs::hasher::Hasher;
     use crate::core::InteractionElements;
     use crate::examples::wide_fibonacci::trace_gen::write_lookup_column;
@@ -183,10 +187,12 @@ mod tests {
             .collect_vec();
         let trace = ComponentTrace {
             polys: TreeVec::new(vec![
+                vec![],
                 trace_polys.iter().collect_vec(),
                 interaction_poly.iter().collect_vec(),
             ]),
             evals: TreeVec::new(vec![
+                vec![],
                 trace_evals.iter().collect_vec(),
                 interaction_trace.iter().collect_vec(),
             ]),
@@ -234,10 +240,139 @@ mod tests {
         let air = WideFibAir { component };
         let prover_channel =
             &mut Blake2sChannel::new(Blake2sHasher::hash(BaseField::into_slice(&[])));
-        let proof = commit_and_prove::<CpuBackend>(&air, prover_channel, trace).unwrap();
+        let proof = commit_and_prove::<CpuBackend, Blake2sMerkleChannel>(
+            &air,
+            prover_channel,
+            trace,
+            PcsConfig::default(),
+        )
+        .unwrap();
 
+        let proof = StarkProof::from_bytes(&proof.to_bytes()).unwrap();
         let verifier_channel =
             &mut Blake2sChannel::new(Blake2sHasher::hash(BaseField::into_slice(&[])));
-        commit_and_verify(proof, &air, verifier_channel).unwrap();
+        commit_and_verify::<Blake2sMerkleChannel>(
+            proof,
+            &air,
+            verifier_channel,
+            PcsConfig::default(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_wide_fib_prove_with_larger_blowup_factors() {
+        let component = WideFibComponent {
+            log_fibonacci_size: 3 + LOG_N_COLUMNS as u32,
+            log_n_instances: 0,
+        };
+        let private_input = vec![Input {
+            a: m31!(1),
+            b: m31!(1),
+        }];
+        let trace_domain = CanonicCoset::new(component.log_column_size());
+        let trace = gen_trace(&component, private_input)
+            .into_iter()
+            .map(|eval| CpuCircleEvaluation::new_canonical_ordered(trace_domain, eval))
+            .collect_vec();
+        let air = WideFibAir { component };
+        for log_blowup_factor in 1..=4 {
+            let config = PcsConfig {
+                pow_bits: 5,
+                fri_config: FriConfig::new(0, log_blowup_factor, 8),
+                ..PcsConfig::default()
+            };
+            let proof = commit_and_prove::<CpuBackend, Blake2sMerkleChannel>(
+                &air,
+                &mut test_channel(),
+                trace.clone(),
+                config.clone(),
+            )
+            .unwrap();
+
+            commit_and_verify::<Blake2sMerkleChannel>(proof, &air, &mut test_channel(), config)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_wide_fib_prove_with_stage_proofs_of_work() {
+        let component = WideFibComponent {
+            log_fibonacci_size: 3 + LOG_N_COLUMNS as u32,
+            log_n_instances: 0,
+        };
+        let private_input = vec![Input {
+            a: m31!(1),
+            b: m31!(1),
+        }];
+        let trace_domain = CanonicCoset::new(component.log_column_size());
+        let trace = gen_trace(&component, private_input)
+            .into_iter()
+            .map(|eval| CpuCircleEvaluation::new_canonical_ordered(trace_domain, eval))
+            .collect_vec();
+        let air = WideFibAir { component };
+        let config = PcsConfig {
+            interaction_pow_bits: 8,
+            oods_pow_bits: 6,
+            ..PcsConfig::default()
+        };
+        let proof = commit_and_prove::<CpuBackend, Blake2sMerkleChannel>(
+            &air,
+            &mut test_channel(),
+            trace,
+            config.clone(),
+        )
+        .unwrap();
+        assert_eq!(proof.interaction_proofs_of_work.len(), 1);
+
+        let mut invalid_proof = StarkProof::from_bytes(&proof.to_bytes()).unwrap();
+        invalid_proof.interaction_proofs_of_work[0].nonce += 1;
+        commit_and_verify::<Blake2sMerkleChannel>(proof, &air, &mut test_channel(), config.clone())
+            .unwrap();
+        let error = commit_and_verify::<Blake2sMerkleChannel>(
+            invalid_proof,
+            &air,
+            &mut test_channel(),
+            config,
+        )
+        .unwrap_err();
+        assert!(matches!(error, VerificationError::ProofOfWork(_)));
+    }
+
+    #[test]
+    fn test_wide_fib_missing_lookup_value() {
+        let component = WideFibComponent {
+            log_fibonacci_size: 3 + LOG_N_COLUMNS as u32,
+            log_n_instances: 0,
+        };
+        let private_input = vec![Input {
+            a: m31!(1),
+            b: m31!(1),
+        }];
+        let trace_domain = CanonicCoset::new(component.log_column_size());
+        let trace = gen_trace(&component, private_input)
+            .into_iter()
+            .map(|eval| CpuCircleEvaluation::new_canonical_ordered(trace_domain, eval))
+            .collect_vec();
+        let air = WideFibAir { component };
+        let mut proof = commit_and_prove::<CpuBackend, Blake2sMerkleChannel>(
+            &air,
+            &mut test_channel(),
+            trace,
+            PcsConfig::default(),
+        )
+        .unwrap();
+        proof.lookup_values.0.remove(LOOKUP_VALUE_0_ID);
+
+        let error = commit_and_verify::<Blake2sMerkleChannel>(
+            proof,
+            &air,
+            &mut test_channel(),
+            PcsConfig::default(),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(error, VerificationError::MissingLookupValue(name) if name == LOOKUP_VALUE_0_ID)
+        );
     }
 }
@@ -5,20 +5,20 @@ use tracing::{span, Level};
 use super::component::LOG_N_COLUMNS;
 use crate::core::air::accumulation::{DomainEvaluationAccumulator, PointEvaluationAccumulator};
 use crate::core::air::mask::fixed_mask_points;
-use crate::core::air::{Air, AirProver, Component, ComponentProver, ComponentTrace};
+use crate::core::air::{Air, AirProver, Component, ComponentProver, ComponentTrace, PublicInput};
 use crate::core::backend::simd::column::BaseFieldVec;
 use crate::core::backend::simd::m31::{PackedBaseField, LOG_N_LANES};
 use crate::core::backend::simd::qm31::PackedSecureField;
 use crate::core::backend::simd::SimdBackend;
 use crate::core::backend::{Col, Column, ColumnOps};
-use crate::core::channel::Blake2sChannel;
+use crate::core::channel::Channel;
 use crate::core::circle::CirclePoint;
 use crate::core::constraints::coset_vanishing;
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
 use crate::core::fields::{FieldExpOps, FieldOps};
 use crate::core::pcs::TreeVec;
-use crate::core::poly::circle::{CanonicCoset, CircleEvaluation};
+use crate::core::poly::circle::{CanonicCoset, CircleEvaluation, PolyOps};
 use crate::core::poly::BitReversedOrder;
 use crate::core::prover::VerificationError;
 use crate::core::{ColumnVec, InteractionElements, LookupValues};
@@ -62,13 +62,23 @@ impl Air for SimdWideFibAir {
         vec![&self.component]
     }
 
+    fn public_input(&self) -> impl PublicInput {}
+
     fn verify_lookups(&self, _lookup_values: &LookupValues) -> Result<(), VerificationError> {
         Ok(())
     }
 }
 
 impl AirTraceVerifier for SimdWideFibAir {
-    fn interaction_elements(&self, _channel: &mut Blake2sChannel) -> InteractionElements {
+    fn n_interaction_phases(&self) -> usize {
+        0
+    }
+
+    fn interaction_elements(
+        &self,
+        _phase: usize,
+        _channel: &mut impl Channel,
+    ) -> InteractionElements {
         InteractionElements::default()
     }
 }
@@ -76,7 +86,8 @@ impl AirTraceVerifier for SimdWideFibAir {
 impl AirTraceGenerator<SimdBackend> for SimdWideFibAir {
     fn interact(
         &self,
-        _trace: &ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+        _phase: usize,
+        _traces: &TreeVec<ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>>,
         _elements: &InteractionElements,
     ) -> Vec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
         vec![]
@@ -101,17 +112,17 @@ impl Component for SimdWideFibComponent {
     }
 
     fn trace_log_degree_bounds(&self) -> TreeVec<ColumnVec<u32>> {
-        TreeVec::new(vec![vec![self.log_column_size(); self.n_columns()]])
+        TreeVec::new(vec![vec![], vec![self.log_column_size(); self.n_columns()]])
     }
 
     fn mask_points(
         &self,
         point: CirclePoint<SecureField>,
     ) -> TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>> {
-        TreeVec::new(vec![fixed_mask_points(
-            &vec![vec![0_usize]; self.n_columns()],
-            point,
-        )])
+        TreeVec::new(vec![
+            vec![],
+            fixed_mask_points(&vec![vec![0_usize]; self.n_columns()], point),
+        ])
     }
 
     fn evaluate_constraint_quotients_at_point(
@@ -126,7 +137,8 @@ impl Component for SimdWideFibComponent {
         let denom = coset_vanishing(constraint_zero_domain, point);
         let denom_inverse = denom.inverse();
         for i in 0..self.n_columns() - 2 {
-            let numerator = mask[0][i][0].square() + mask[0][i + 1][0].square() - mask[0][i + 2][0];
+            let numerator = mask[BASE_TRACE][i][0].square() + mask[BASE_TRACE][i + 1][0].square()
+                - mask[BASE_TRACE][i + 2][0];
             evaluation_accumulator.accumulate(numerator * denom_inverse);
         }
     }
@@ -201,7 +213,23 @@ impl ComponentProver<SimdBackend> for SimdWideFibComponent {
         assert_eq!(trace.polys[BASE_TRACE].len(), self.n_columns());
         // TODO(spapini): Steal evaluation from commitment.
         let eval_domain = CanonicCoset::new(self.log_column_size() + 1).circle_domain();
-        let trace_eval = &trace.evals;
+        // The committed evaluations can only be reused if they are on the constraint evaluation
+        // domain, i.e. if the blowup factor matches the constraint degree.
+        let reevaluated_trace;
+        let reevaluated_trace_ref;
+        let trace_eval = if trace.evals[BASE_TRACE][0].domain.log_size() == eval_domain.log_size() {
+            &trace.evals
+        } else {
+            let twiddles = SimdBackend::precompute_twiddles(eval_domain.half_coset);
+            reevaluated_trace = trace
+                .polys
+                .as_cols_ref()
+                .map_cols(|poly| poly.evaluate_with_twiddles(eval_domain, &twiddles));
+            reevaluated_trace_ref = reevaluated_trace
+                .as_ref()
+                .map(|tree| tree.iter().collect_vec());
+            &reevaluated_trace_ref
+        };
 
         // Denoms.
         let span = span!(Level::INFO, "Constraint eval denominators").entered();
@@ -263,7 +291,12 @@ mod tests {
     use crate::core::channel::{Blake2sChannel, Channel};
     use crate::core::fields::m31::BaseField;
     use crate::core::fields::IntoSlice;
+    use crate::core::fri::FriConfig;
+    use crate::core::pcs::PcsConfig;
+    use crate::core::prover::StarkProof;
+    use crate::core::test_utils::test_channel;
     use crate::core::vcs::blake2_hash::Blake2sHasher;
+    use crate::core::vcs::blake2_merkle::Blake2sMerkleChannel;
     use crate::core::vcs::hasher::Hasher;
     use crate::examples::wide_fibonacci::component::LOG_N_COLUMNS;
     use crate::examples::wide_fibonacci::simd::{gen_trace, SimdWideFibAir, SimdWideFibComponent};
@@ -287,9 +320,47 @@ mod tests {
         span.exit();
         let channel = &mut Blake2sChannel::new(Blake2sHasher::hash(BaseField::into_slice(&[])));
         let air = SimdWideFibAir { component };
-        let proof = commit_and_prove::<SimdBackend>(&air, channel, trace).unwrap();
-
+        let proof = commit_and_prove::<SimdBackend, Blake2sMerkleChannel>(
+            &air,
+            channel,
+            trace,
+            PcsConfig::default(),
+        )
+        .unwrap();
+
+        let proof = StarkProof::from_bytes(&proof.to_bytes()).unwrap();
         let channel = &mut Blake2sChannel::new(Blake2sHasher::hash(BaseField::into_slice(&[])));
-        commit_and_verify(proof, &air, channel).unwrap();
+        commit_and_verify::<Blake2sMerkleChannel>(proof, &air, channel, PcsConfig::default())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_simd_wide_fib_prove_with_larger_blowup_factors() {
+        const LOG_N_ROWS: u32 = 6;
+        let component = SimdWideFibComponent {
+            log_fibonacci_size: LOG_N_COLUMNS as u32,
+            log_n_instances: LOG_N_ROWS,
+        };
+        let log_column_size = component.log_column_size();
+        let air = SimdWideFibAir { component };
+        for log_blowup_factor in 1..=4 {
+            let config = PcsConfig {
+                pow_bits: 5,
+                fri_config: FriConfig::new(0, log_blowup_factor, 8),
+                ..PcsConfig::default()
+            };
+            let trace = gen_trace(log_column_size);
+
+            let proof = commit_and_prove::<SimdBackend, Blake2sMerkleChannel>(
+                &air,
+                &mut test_channel(),
+                trace,
+                config.clone(),
+            )
+            .unwrap();
+
+            commit_and_verify::<Blake2sMerkleChannel>(proof, &air, &mut test_channel(), config)
+                .unwrap();
+        }
     }
 }
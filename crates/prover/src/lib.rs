@@ -17,3 +17,6 @@ pub mod core;
 pub mod examples;
 pub mod math;
 pub mod trace_generation;
+
+#[cfg(test)]
+pub(crate) use stwo_verifier::{m31, qm31};
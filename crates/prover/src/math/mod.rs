@@ -1,2 +1 @@
-pub mod matrix;
-pub mod utils;
+pub use stwo_verifier::math::{matrix, utils};
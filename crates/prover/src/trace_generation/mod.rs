@@ -2,19 +2,24 @@ mod prove;
 pub mod registry;
 
 use downcast_rs::{impl_downcast, Downcast};
-pub use prove::{commit_and_prove, commit_and_verify};
+pub use prove::{
+    commit_and_prove, commit_and_prove_with_preprocessed_trace, commit_and_verify,
+    commit_and_verify_with_preprocessed_root, commit_and_verify_with_twiddles, PreprocessedTrace,
+};
 use registry::ComponentGenerationRegistry;
 
 use crate::core::air::{AirProver, Component};
 use crate::core::backend::Backend;
-use crate::core::channel::Blake2sChannel;
+use crate::core::channel::Channel;
 use crate::core::fields::m31::BaseField;
+use crate::core::pcs::TreeVec;
 use crate::core::poly::circle::CircleEvaluation;
 use crate::core::poly::BitReversedOrder;
 use crate::core::{ColumnVec, InteractionElements};
 
-pub const BASE_TRACE: usize = 0;
-pub const INTERACTION_TRACE: usize = 1;
+pub const PREPROCESSED_TRACE: usize = 0;
+pub const BASE_TRACE: usize = 1;
+pub const INTERACTION_TRACE: usize = 2;
 
 pub trait ComponentGen: Downcast {}
 impl_downcast!(ComponentGen);
@@ -49,21 +54,46 @@ pub trait ComponentTraceGenerator<B: Backend> {
     fn component(&self) -> Self::Component;
 }
 
+/// Describes the ordered commitment phases of an AIR.
+///
+/// The preprocessed trace is committed first, in tree [PREPROCESSED_TRACE]. Its root only depends
+/// on the AIR, so the verifier checks it against a known value instead of trusting the prover. The
+/// base trace follows in tree [BASE_TRACE], and then [Self::n_interaction_phases] interaction
+/// phases, committed in order in trees [INTERACTION_TRACE], [INTERACTION_TRACE] + 1, and so on.
+/// Each interaction phase draws its own [InteractionElements] from the channel after the root of
+/// the previous tree was mixed in.
 pub trait AirTraceVerifier {
-    fn interaction_elements(&self, channel: &mut Blake2sChannel) -> InteractionElements;
+    /// Returns the number of interaction phases following the base trace.
+    fn n_interaction_phases(&self) -> usize;
+
+    /// Draws the interaction elements of the interaction phase committed in tree `phase`.
+    fn interaction_elements(&self, phase: usize, channel: &mut impl Channel)
+        -> InteractionElements;
 }
 
 pub trait AirTraceGenerator<B: Backend>: AirTraceVerifier {
     fn composition_log_degree_bound(&self) -> u32;
 
+    /// Returns the preprocessed trace, i.e. the columns that do not depend on the witness, such as
+    /// selectors and lookup tables. Components read them from the [PREPROCESSED_TRACE] tree of
+    /// their mask.
+    fn write_preprocessed_trace(&self) -> Vec<CircleEvaluation<B, BaseField, BitReversedOrder>> {
+        vec![]
+    }
+
     // TODO(AlonH): Remove default implementation once all the components are implemented.
     fn write_trace(&mut self) -> Vec<CircleEvaluation<B, BaseField, BitReversedOrder>> {
         vec![]
     }
 
+    /// Returns the trace of the interaction phase committed in tree `phase`.
+    ///
+    /// `traces` holds the traces of all the previous phases, and `elements` holds the interaction
+    /// elements drawn so far, including the ones of this phase.
     fn interact(
         &self,
-        trace: &ColumnVec<CircleEvaluation<B, BaseField, BitReversedOrder>>,
+        phase: usize,
+        traces: &TreeVec<ColumnVec<CircleEvaluation<B, BaseField, BitReversedOrder>>>,
         elements: &InteractionElements,
     ) -> Vec<CircleEvaluation<B, BaseField, BitReversedOrder>>;
 
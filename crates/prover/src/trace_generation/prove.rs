@@ -1,37 +1,94 @@
 use itertools::Itertools;
 use tracing::{span, Level};
 
-use super::{AirTraceGenerator, AirTraceVerifier, BASE_TRACE, INTERACTION_TRACE};
-use crate::core::air::{Air, AirExt, AirProverExt};
-use crate::core::backend::Backend;
-use crate::core::channel::{Blake2sChannel, Channel as _};
+use super::{AirTraceGenerator, AirTraceVerifier, INTERACTION_TRACE, PREPROCESSED_TRACE};
+use crate::core::air::{Air, AirExt, AirProverExt, PublicInput};
+use crate::core::backend::BackendForChannel;
+use crate::core::channel::{Channel as _, MerkleChannel};
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
-use crate::core::pcs::{CommitmentSchemeProver, CommitmentSchemeVerifier};
+use crate::core::fri::{FriVerifierOps, FriVerifierTwiddles};
+use crate::core::pcs::{
+    CommitmentSchemeProver, CommitmentSchemeVerifier, CommitmentTreeProver, PcsConfig, TreeVec,
+};
 use crate::core::poly::circle::{CanonicCoset, CircleEvaluation, MAX_CIRCLE_DOMAIN_LOG_SIZE};
 use crate::core::poly::twiddles::TwiddleTree;
 use crate::core::poly::BitReversedOrder;
+use crate::core::proof_of_work::{prove_pow, ProofOfWork, ProofOfWorkProof};
 use crate::core::prover::{
-    prove, verify, ProvingError, StarkProof, VerificationError, LOG_BLOWUP_FACTOR,
+    prove, verify, verify_with_twiddles, ProofShapeError, ProvingError, StarkProof,
+    VerificationError,
 };
-use crate::core::vcs::blake2_merkle::Blake2sMerkleHasher;
-use crate::core::vcs::ops::MerkleOps;
+use crate::core::vcs::ops::MerkleHasher;
 use crate::core::{ColumnVec, InteractionElements};
 
-type MerkleHasher = Blake2sMerkleHasher;
-type Channel = Blake2sChannel;
+/// A committed preprocessed trace. It only depends on the AIR and the PCS config, so it can be
+/// computed once and reused across proofs with [commit_and_prove_with_preprocessed_trace].
+pub struct PreprocessedTrace<B: BackendForChannel<MC>, MC: MerkleChannel> {
+    pub trace: ColumnVec<CircleEvaluation<B, BaseField, BitReversedOrder>>,
+    pub tree: CommitmentTreeProver<B, MC>,
+}
+
+impl<B: BackendForChannel<MC>, MC: MerkleChannel> PreprocessedTrace<B, MC> {
+    pub fn new(air: &impl AirTraceGenerator<B>, config: PcsConfig) -> Self {
+        let _span = span!(Level::INFO, "Preprocessed trace").entered();
+        let log_blowup_factor = config.fri_config.log_blowup_factor;
+        let trace = air.write_preprocessed_trace();
+        let max_log_size = trace
+            .iter()
+            .map(|eval| eval.domain.log_size())
+            .max()
+            .unwrap_or(0);
+        let twiddles = B::precompute_twiddles(
+            CanonicCoset::new(max_log_size + log_blowup_factor)
+                .circle_domain()
+                .half_coset,
+        );
+        let polys = trace
+            .iter()
+            .map(|eval| eval.clone().interpolate_with_twiddles(&twiddles))
+            .collect();
+        let tree = CommitmentTreeProver::new_with_merkle_config(
+            polys,
+            log_blowup_factor,
+            config.merkle_config(PREPROCESSED_TRACE),
+            &twiddles,
+        );
+        Self { trace, tree }
+    }
+
+    /// The cap the verifier expects in the [PREPROCESSED_TRACE] tree of the proof.
+    pub fn cap(&self) -> Vec<<MC::H as MerkleHasher>::Hash> {
+        self.tree.commitment.cap()
+    }
+}
+
+pub fn commit_and_prove<B: BackendForChannel<MC>, MC: MerkleChannel>(
+    air: &impl AirTraceGenerator<B>,
+    channel: &mut MC::C,
+    trace: ColumnVec<CircleEvaluation<B, BaseField, BitReversedOrder>>,
+    config: PcsConfig,
+) -> Result<StarkProof<MC::H>, ProvingError> {
+    let preprocessed_trace = PreprocessedTrace::<B, MC>::new(air, config.clone());
+    commit_and_prove_with_preprocessed_trace(air, channel, &preprocessed_trace, trace, config)
+}
 
-pub fn commit_and_prove<B: Backend + MerkleOps<MerkleHasher>>(
+pub fn commit_and_prove_with_preprocessed_trace<B: BackendForChannel<MC>, MC: MerkleChannel>(
     air: &impl AirTraceGenerator<B>,
-    channel: &mut Channel,
+    channel: &mut MC::C,
+    preprocessed_trace: &PreprocessedTrace<B, MC>,
     trace: ColumnVec<CircleEvaluation<B, BaseField, BitReversedOrder>>,
-) -> Result<StarkProof, ProvingError> {
+    config: PcsConfig,
+) -> Result<StarkProof<MC::H>, ProvingError> {
+    let log_blowup_factor = config.fri_config.log_blowup_factor;
+
     // Check that traces are not too big.
     for (i, trace) in trace.iter().enumerate() {
-        if trace.domain.log_size() + LOG_BLOWUP_FACTOR > MAX_CIRCLE_DOMAIN_LOG_SIZE {
+        if trace.domain.log_size() + log_blowup_factor > MAX_CIRCLE_DOMAIN_LOG_SIZE {
             return Err(ProvingError::MaxTraceDegreeExceeded {
                 trace_index: i,
                 degree: trace.domain.log_size(),
+                log_blowup_factor,
             });
         }
     }
@@ -39,23 +96,31 @@ pub fn commit_and_prove<B: Backend + MerkleOps<MerkleHasher>>(
     // Check that the composition polynomial is not too big.
     // TODO(AlonH): Get traces log degree bounds from trace writer.
     let composition_polynomial_log_degree_bound = air.composition_log_degree_bound();
-    if composition_polynomial_log_degree_bound + LOG_BLOWUP_FACTOR > MAX_CIRCLE_DOMAIN_LOG_SIZE {
+    if composition_polynomial_log_degree_bound + log_blowup_factor > MAX_CIRCLE_DOMAIN_LOG_SIZE {
         return Err(ProvingError::MaxCompositionDegreeExceeded {
             degree: composition_polynomial_log_degree_bound,
+            log_blowup_factor,
         });
     }
 
     let span = span!(Level::INFO, "Precompute twiddle").entered();
     let composition_polynomial_log_degree_bound = air.composition_log_degree_bound();
     let twiddles = B::precompute_twiddles(
-        CanonicCoset::new(composition_polynomial_log_degree_bound + LOG_BLOWUP_FACTOR)
+        CanonicCoset::new(composition_polynomial_log_degree_bound + log_blowup_factor)
             .circle_domain()
             .half_coset,
     );
     span.exit();
 
-    let (mut commitment_scheme, interaction_elements) =
-        evaluate_and_commit_on_trace(air, channel, &twiddles, trace)?;
+    let (mut commitment_scheme, interaction_elements, interaction_proofs_of_work) =
+        evaluate_and_commit_on_trace::<B, MC>(
+            air,
+            channel,
+            &twiddles,
+            preprocessed_trace,
+            trace,
+            config,
+        )?;
 
     let air = air.to_air_prover();
     channel.mix_felts(
@@ -66,61 +131,181 @@ pub fn commit_and_prove<B: Backend + MerkleOps<MerkleHasher>>(
             .collect_vec(),
     );
 
-    prove(
+    prove::<B, MC>(
         &air,
         channel,
         &interaction_elements,
+        interaction_proofs_of_work,
         &twiddles,
         &mut commitment_scheme,
     )
 }
 
-pub fn evaluate_and_commit_on_trace<B: Backend + MerkleOps<MerkleHasher>>(
+/// Commits to the preprocessed, base and interaction traces.
+///
+/// Returns the commitment scheme, the drawn interaction elements and the proofs of work that
+/// preceded the interaction elements of each phase.
+#[allow(clippy::type_complexity)]
+pub fn evaluate_and_commit_on_trace<B: BackendForChannel<MC>, MC: MerkleChannel>(
     air: &impl AirTraceGenerator<B>,
-    channel: &mut Channel,
+    channel: &mut MC::C,
     twiddles: &TwiddleTree<B>,
+    preprocessed_trace: &PreprocessedTrace<B, MC>,
     trace: ColumnVec<CircleEvaluation<B, BaseField, BitReversedOrder>>,
-) -> Result<(CommitmentSchemeProver<B>, InteractionElements), ProvingError> {
-    let mut commitment_scheme = CommitmentSchemeProver::new(LOG_BLOWUP_FACTOR);
+    config: PcsConfig,
+) -> Result<
+    (
+        CommitmentSchemeProver<B, MC>,
+        InteractionElements,
+        Vec<ProofOfWorkProof>,
+    ),
+    ProvingError,
+> {
+    air.to_air_prover().public_input().mix_into(channel);
+    config.mix_into(channel);
+    let interaction_pow_bits = config.interaction_pow_bits;
+    let mut commitment_scheme = CommitmentSchemeProver::new(config);
+    commitment_scheme.commit_tree(preprocessed_trace.tree.clone(), channel);
+
     // TODO(spapini): Remove clone.
     let span = span!(Level::INFO, "Trace").entered();
     commitment_scheme.commit_on_evals(trace.clone(), channel, twiddles);
     span.exit();
 
-    let interaction_elements = air.interaction_elements(channel);
-    let interaction_trace = air.interact(&trace, &interaction_elements);
-    // TODO(spapini): Make this symmetric with verify, once the TraceGenerator traits support
-    // retrieveing the column log sizes.
-    if !interaction_trace.is_empty() {
-        let _span = span!(Level::INFO, "Interaction").entered();
-        commitment_scheme.commit_on_evals(interaction_trace, channel, twiddles);
+    let mut traces = TreeVec::new(vec![preprocessed_trace.trace.clone(), trace]);
+    let mut interaction_elements = InteractionElements::default();
+    let mut interaction_proofs_of_work = Vec::new();
+    for phase in INTERACTION_TRACE..INTERACTION_TRACE + air.n_interaction_phases() {
+        interaction_proofs_of_work.push(prove_pow::<B, _>(channel, interaction_pow_bits));
+        interaction_elements.extend(air.interaction_elements(phase, channel));
+        let interaction_trace = air.interact(phase, &traces, &interaction_elements);
+        let _span = span!(Level::INFO, "Interaction", phase).entered();
+        commitment_scheme.commit_on_evals(interaction_trace.clone(), channel, twiddles);
+        traces.push(interaction_trace);
     }
 
-    Ok((commitment_scheme, interaction_elements))
+    Ok((
+        commitment_scheme,
+        interaction_elements,
+        interaction_proofs_of_work,
+    ))
+}
+
+/// Verifies a proof of an AIR without preprocessed columns.
+///
+/// Such an AIR commits to an empty preprocessed tree, whose cap is the hash of a single node with
+/// no values, whatever the shape of the tree. AIRs with preprocessed columns must use
+/// [commit_and_verify_with_preprocessed_root].
+pub fn commit_and_verify<MC: MerkleChannel>(
+    proof: StarkProof<MC::H>,
+    air: &(impl Air + AirTraceVerifier),
+    channel: &mut MC::C,
+    config: PcsConfig,
+) -> Result<(), VerificationError> {
+    let preprocessed_cap = vec![MC::H::hash_node(&[], &[])];
+    commit_and_verify_with_preprocessed_root::<MC>(proof, air, channel, preprocessed_cap, config)
+}
+
+/// Like [commit_and_verify], but folds the FRI queries with `B` and the precomputed `twiddles`.
+/// See [verify_with_twiddles].
+pub fn commit_and_verify_with_twiddles<MC: MerkleChannel, B: FriVerifierOps>(
+    proof: StarkProof<MC::H>,
+    air: &(impl Air + AirTraceVerifier),
+    channel: &mut MC::C,
+    config: PcsConfig,
+    twiddles: &FriVerifierTwiddles,
+) -> Result<(), VerificationError> {
+    let preprocessed_cap = vec![MC::H::hash_node(&[], &[])];
+    commit_and_verify_with(
+        proof,
+        air,
+        channel,
+        preprocessed_cap,
+        config,
+        |air, channel, interaction_elements, commitment_scheme, proof| {
+            verify_with_twiddles::<MC, B>(
+                air,
+                channel,
+                interaction_elements,
+                commitment_scheme,
+                proof,
+                twiddles,
+            )
+        },
+    )
 }
 
-pub fn commit_and_verify(
-    proof: StarkProof,
+/// Verifies a proof whose [PREPROCESSED_TRACE] tree must have the cap `preprocessed_cap`,
+/// typically obtained from [PreprocessedTrace::cap].
+pub fn commit_and_verify_with_preprocessed_root<MC: MerkleChannel>(
+    proof: StarkProof<MC::H>,
     air: &(impl Air + AirTraceVerifier),
-    channel: &mut Channel,
+    channel: &mut MC::C,
+    preprocessed_cap: Vec<<MC::H as MerkleHasher>::Hash>,
+    config: PcsConfig,
+) -> Result<(), VerificationError> {
+    commit_and_verify_with(proof, air, channel, preprocessed_cap, config, verify::<MC>)
+}
+
+fn commit_and_verify_with<MC: MerkleChannel, A: Air + AirTraceVerifier>(
+    proof: StarkProof<MC::H>,
+    air: &A,
+    channel: &mut MC::C,
+    preprocessed_cap: Vec<<MC::H as MerkleHasher>::Hash>,
+    config: PcsConfig,
+    verify: impl FnOnce(
+        &A,
+        &mut MC::C,
+        &InteractionElements,
+        &mut CommitmentSchemeVerifier<MC>,
+        StarkProof<MC::H>,
+    ) -> Result<(), VerificationError>,
 ) -> Result<(), VerificationError> {
+    air.public_input().mix_into(channel);
+    config.mix_into(channel);
+
     // Read trace commitment.
-    let mut commitment_scheme = CommitmentSchemeVerifier::new();
+    let interaction_pow_bits = config.interaction_pow_bits;
+    let mut commitment_scheme = CommitmentSchemeVerifier::<MC>::new(config);
 
     // TODO(spapini): Retrieve column_log_sizes from AirTraceVerifier, and remove the dependency on
     // Air.
     let column_log_sizes = air.column_log_sizes();
-    commitment_scheme.commit(
-        proof.commitments[BASE_TRACE],
-        &column_log_sizes[BASE_TRACE],
-        channel,
+    assert_eq!(
+        column_log_sizes.len(),
+        air.n_interaction_phases() + 2,
+        "Each commitment phase should have a tree in the AIR"
     );
-    let interaction_elements = air.interaction_elements(channel);
-
-    if air.column_log_sizes().len() == 2 {
+    // The last commitment is of the composition polynomial.
+    if proof.commitments.len() != column_log_sizes.len() + 1 {
+        return Err(ProofShapeError::NumTrees {
+            expected: column_log_sizes.len() + 1,
+            actual: proof.commitments.len(),
+        }
+        .into());
+    }
+    if proof.commitments[PREPROCESSED_TRACE] != preprocessed_cap {
+        return Err(VerificationError::PreprocessedRootMismatch);
+    }
+    if proof.interaction_proofs_of_work.len() != air.n_interaction_phases() {
+        return Err(ProofShapeError::NumInteractionProofsOfWork {
+            expected: air.n_interaction_phases(),
+            actual: proof.interaction_proofs_of_work.len(),
+        }
+        .into());
+    }
+    let mut interaction_elements = InteractionElements::default();
+    for (phase, tree_column_log_sizes) in column_log_sizes.iter().enumerate() {
+        if phase >= INTERACTION_TRACE {
+            ProofOfWork::new(interaction_pow_bits).verify(
+                channel,
+                &proof.interaction_proofs_of_work[phase - INTERACTION_TRACE],
+            )?;
+            interaction_elements.extend(air.interaction_elements(phase, channel));
+        }
         commitment_scheme.commit(
-            proof.commitments[INTERACTION_TRACE],
-            &column_log_sizes[INTERACTION_TRACE],
+            proof.commitments[phase].clone(),
+            tree_column_log_sizes,
             channel,
         );
     }
@@ -144,33 +329,48 @@ pub fn commit_and_verify(
 
 #[cfg(test)]
 mod tests {
-    use num_traits::Zero;
+    use std::collections::BTreeMap;
+
+    use num_traits::{One, Zero};
 
     use crate::core::air::accumulation::{DomainEvaluationAccumulator, PointEvaluationAccumulator};
-    use crate::core::air::{Air, AirProver, Component, ComponentProver, ComponentTrace};
+    use crate::core::air::{
+        Air, AirProver, Component, ComponentProver, ComponentTrace, PublicInput,
+    };
     use crate::core::backend::cpu::CpuCircleEvaluation;
     use crate::core::backend::CpuBackend;
-    use crate::core::channel::Blake2sChannel;
+    use crate::core::channel::{Channel, MerkleChannel};
     use crate::core::circle::{CirclePoint, CirclePointIndex, Coset};
+    use crate::core::constraints::coset_vanishing;
     use crate::core::fields::m31::BaseField;
     use crate::core::fields::qm31::SecureField;
-    use crate::core::pcs::TreeVec;
+    use crate::core::fields::FieldExpOps;
+    use crate::core::pcs::{PcsConfig, TreeVec};
     use crate::core::poly::circle::{
-        CanonicCoset, CircleDomain, CircleEvaluation, MAX_CIRCLE_DOMAIN_LOG_SIZE,
+        CanonicCoset, CircleDomain, CircleEvaluation, PolyOps, MAX_CIRCLE_DOMAIN_LOG_SIZE,
     };
     use crate::core::poly::BitReversedOrder;
     use crate::core::prover::{ProvingError, VerificationError};
     use crate::core::test_utils::test_channel;
+    use crate::core::utils::bit_reverse_index;
+    use crate::core::vcs::blake2_merkle::Blake2sMerkleChannel;
+    use crate::core::vcs::verifier::MerkleConfig;
     use crate::core::{ColumnVec, InteractionElements, LookupValues};
     use crate::qm31;
+    use crate::trace_generation::prove::evaluate_and_commit_on_trace;
     use crate::trace_generation::registry::ComponentGenerationRegistry;
     use crate::trace_generation::{
-        commit_and_prove, AirTraceGenerator, AirTraceVerifier, ComponentTraceGenerator,
+        commit_and_prove, commit_and_prove_with_preprocessed_trace, commit_and_verify,
+        commit_and_verify_with_preprocessed_root, AirTraceGenerator, AirTraceVerifier,
+        ComponentTraceGenerator, PreprocessedTrace, BASE_TRACE, INTERACTION_TRACE,
+        PREPROCESSED_TRACE,
     };
 
     #[derive(Clone)]
     struct TestAir<C: ComponentProver<CpuBackend>> {
         component: C,
+        n_interaction_phases: usize,
+        public_input: BaseField,
     }
 
     impl Air for TestAir<TestComponent> {
@@ -178,24 +378,41 @@ mod tests {
             vec![&self.component]
         }
 
+        fn public_input(&self) -> impl PublicInput {
+            self.public_input
+        }
+
         fn verify_lookups(&self, _lookup_values: &LookupValues) -> Result<(), VerificationError> {
             Ok(())
         }
     }
 
     impl AirTraceVerifier for TestAir<TestComponent> {
-        fn interaction_elements(&self, _channel: &mut Blake2sChannel) -> InteractionElements {
-            InteractionElements::default()
+        fn n_interaction_phases(&self) -> usize {
+            self.n_interaction_phases
+        }
+
+        fn interaction_elements(
+            &self,
+            phase: usize,
+            channel: &mut impl Channel,
+        ) -> InteractionElements {
+            InteractionElements::new(BTreeMap::from([(
+                format!("phase_{phase}"),
+                channel.draw_felt(),
+            )]))
         }
     }
 
     impl AirTraceGenerator<CpuBackend> for TestAir<TestComponent> {
         fn interact(
             &self,
-            _trace: &ColumnVec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>>,
+            phase: usize,
+            traces: &TreeVec<ColumnVec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>>>,
             _elements: &InteractionElements,
         ) -> Vec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>> {
-            vec![]
+            assert_eq!(traces.len(), phase);
+            traces[phase - 1].clone()
         }
 
         fn to_air_prover(&self) -> impl AirProver<CpuBackend> {
@@ -229,14 +446,14 @@ mod tests {
         }
 
         fn trace_log_degree_bounds(&self) -> TreeVec<ColumnVec<u32>> {
-            TreeVec::new(vec![vec![self.log_size]])
+            TreeVec::new(vec![vec![], vec![self.log_size]])
         }
 
         fn mask_points(
             &self,
             point: CirclePoint<SecureField>,
         ) -> TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>> {
-            TreeVec::new(vec![vec![vec![point]]])
+            TreeVec::new(vec![vec![], vec![vec![point]]])
         }
 
         fn evaluate_constraint_quotients_at_point(
@@ -293,6 +510,151 @@ mod tests {
         }
     }
 
+    /// A component with a single preprocessed column and a single base trace column, constrained
+    /// to be its square.
+    #[derive(Clone)]
+    struct PreprocessedTestComponent {
+        log_size: u32,
+    }
+
+    impl Component for PreprocessedTestComponent {
+        fn n_constraints(&self) -> usize {
+            1
+        }
+
+        fn max_constraint_log_degree_bound(&self) -> u32 {
+            self.log_size + 1
+        }
+
+        fn trace_log_degree_bounds(&self) -> TreeVec<ColumnVec<u32>> {
+            TreeVec::new(vec![vec![self.log_size], vec![self.log_size]])
+        }
+
+        fn mask_points(
+            &self,
+            point: CirclePoint<SecureField>,
+        ) -> TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>> {
+            TreeVec::new(vec![vec![vec![point]], vec![vec![point]]])
+        }
+
+        fn evaluate_constraint_quotients_at_point(
+            &self,
+            point: CirclePoint<SecureField>,
+            mask: &TreeVec<Vec<Vec<SecureField>>>,
+            evaluation_accumulator: &mut PointEvaluationAccumulator,
+            _interaction_elements: &InteractionElements,
+            _lookup_values: &LookupValues,
+        ) {
+            let constraint = mask[BASE_TRACE][0][0] - mask[PREPROCESSED_TRACE][0][0].square();
+            let denom = coset_vanishing(CanonicCoset::new(self.log_size).coset, point);
+            evaluation_accumulator.accumulate(constraint / denom);
+        }
+    }
+
+    impl ComponentProver<CpuBackend> for PreprocessedTestComponent {
+        fn evaluate_constraint_quotients_on_domain(
+            &self,
+            trace: &ComponentTrace<'_, CpuBackend>,
+            evaluation_accumulator: &mut DomainEvaluationAccumulator<CpuBackend>,
+            _interaction_elements: &InteractionElements,
+            _lookup_values: &LookupValues,
+        ) {
+            let eval_domain = CanonicCoset::new(self.log_size + 1).circle_domain();
+            let preprocessed = trace.polys[PREPROCESSED_TRACE][0].evaluate(eval_domain);
+            let base = trace.polys[BASE_TRACE][0].evaluate(eval_domain);
+            let [mut accum] = evaluation_accumulator.columns([(eval_domain.log_size(), 1)]);
+            for i in 0..eval_domain.size() {
+                let point = eval_domain.at(bit_reverse_index(i, eval_domain.log_size()));
+                let denom = coset_vanishing(CanonicCoset::new(self.log_size).coset, point);
+                let constraint = (base[i] - preprocessed[i].square()) / denom;
+                accum.accumulate(i, accum.random_coeff_powers[0] * constraint);
+            }
+        }
+
+        fn lookup_values(&self, _trace: &ComponentTrace<'_, CpuBackend>) -> LookupValues {
+            LookupValues::default()
+        }
+    }
+
+    impl Air for TestAir<PreprocessedTestComponent> {
+        fn components(&self) -> Vec<&dyn Component> {
+            vec![&self.component]
+        }
+
+        fn public_input(&self) -> impl PublicInput {
+            self.public_input
+        }
+
+        fn verify_lookups(&self, _lookup_values: &LookupValues) -> Result<(), VerificationError> {
+            Ok(())
+        }
+    }
+
+    impl AirTraceVerifier for TestAir<PreprocessedTestComponent> {
+        fn n_interaction_phases(&self) -> usize {
+            0
+        }
+
+        fn interaction_elements(
+            &self,
+            _phase: usize,
+            _channel: &mut impl Channel,
+        ) -> InteractionElements {
+            InteractionElements::default()
+        }
+    }
+
+    impl AirTraceGenerator<CpuBackend> for TestAir<PreprocessedTestComponent> {
+        fn write_preprocessed_trace(
+            &self,
+        ) -> Vec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>> {
+            let domain = CanonicCoset::new(self.component.log_size).circle_domain();
+            let values = (0..domain.size()).map(BaseField::from).collect();
+            vec![CpuCircleEvaluation::new(domain, values)]
+        }
+
+        fn interact(
+            &self,
+            _phase: usize,
+            _traces: &TreeVec<ColumnVec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>>>,
+            _elements: &InteractionElements,
+        ) -> Vec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>> {
+            vec![]
+        }
+
+        fn to_air_prover(&self) -> impl AirProver<CpuBackend> {
+            self.clone()
+        }
+
+        fn composition_log_degree_bound(&self) -> u32 {
+            self.component.max_constraint_log_degree_bound()
+        }
+    }
+
+    impl AirProver<CpuBackend> for TestAir<PreprocessedTestComponent> {
+        fn prover_components(&self) -> Vec<&dyn ComponentProver<CpuBackend>> {
+            vec![&self.component]
+        }
+    }
+
+    fn preprocessed_test_air_and_trace(
+        log_size: u32,
+    ) -> (
+        TestAir<PreprocessedTestComponent>,
+        ColumnVec<CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>>,
+    ) {
+        let air = TestAir {
+            component: PreprocessedTestComponent { log_size },
+            n_interaction_phases: 0,
+            public_input: BaseField::zero(),
+        };
+        let domain = CanonicCoset::new(log_size).circle_domain();
+        let values = (0..domain.size())
+            .map(|i| BaseField::from(i).square())
+            .collect();
+        (air, vec![CpuCircleEvaluation::new(domain, values)])
+    }
+
     // Ignored because it takes too long and too much memory (in the CI) to run.
     #[test]
     #[ignore]
@@ -303,6 +665,8 @@ mod tests {
                 log_size: LOG_DOMAIN_SIZE,
                 max_constraint_log_degree_bound: LOG_DOMAIN_SIZE,
             },
+            n_interaction_phases: 0,
+            public_input: BaseField::zero(),
         };
         let domain = CircleDomain::new(Coset::new(
             CirclePointIndex::generator(),
@@ -311,12 +675,19 @@ mod tests {
         let values = vec![BaseField::zero(); 1 << LOG_DOMAIN_SIZE];
         let trace = vec![CpuCircleEvaluation::new(domain, values)];
 
-        let proof_error = commit_and_prove(&air, &mut test_channel(), trace).unwrap_err();
+        let proof_error = commit_and_prove::<_, Blake2sMerkleChannel>(
+            &air,
+            &mut test_channel(),
+            trace,
+            PcsConfig::default(),
+        )
+        .unwrap_err();
         assert!(matches!(
             proof_error,
             ProvingError::MaxTraceDegreeExceeded {
                 trace_index: 0,
-                degree: LOG_DOMAIN_SIZE
+                degree: LOG_DOMAIN_SIZE,
+                ..
             }
         ));
     }
@@ -330,6 +701,8 @@ mod tests {
                 log_size: LOG_DOMAIN_SIZE,
                 max_constraint_log_degree_bound: COMPOSITION_POLYNOMIAL_DEGREE,
             },
+            n_interaction_phases: 0,
+            public_input: BaseField::zero(),
         };
         let domain = CircleDomain::new(Coset::new(
             CirclePointIndex::generator(),
@@ -338,11 +711,18 @@ mod tests {
         let values = vec![BaseField::zero(); 1 << LOG_DOMAIN_SIZE];
         let trace = vec![CpuCircleEvaluation::new(domain, values)];
 
-        let proof_error = commit_and_prove(&air, &mut test_channel(), trace).unwrap_err();
+        let proof_error = commit_and_prove::<_, Blake2sMerkleChannel>(
+            &air,
+            &mut test_channel(),
+            trace,
+            PcsConfig::default(),
+        )
+        .unwrap_err();
         assert!(matches!(
             proof_error,
             ProvingError::MaxCompositionDegreeExceeded {
-                degree: COMPOSITION_POLYNOMIAL_DEGREE
+                degree: COMPOSITION_POLYNOMIAL_DEGREE,
+                ..
             }
         ));
     }
@@ -355,12 +735,244 @@ mod tests {
                 log_size: LOG_DOMAIN_SIZE,
                 max_constraint_log_degree_bound: LOG_DOMAIN_SIZE + 1,
             },
+            n_interaction_phases: 0,
+            public_input: BaseField::zero(),
         };
         let domain = CanonicCoset::new(LOG_DOMAIN_SIZE).circle_domain();
         let values = vec![BaseField::zero(); 1 << LOG_DOMAIN_SIZE];
         let trace = vec![CpuCircleEvaluation::new(domain, values)];
 
-        let proof = commit_and_prove(&air, &mut test_channel(), trace).unwrap_err();
+        let proof = commit_and_prove::<_, Blake2sMerkleChannel>(
+            &air,
+            &mut test_channel(),
+            trace,
+            PcsConfig::default(),
+        )
+        .unwrap_err();
         assert!(matches!(proof, ProvingError::ConstraintsNotSatisfied));
     }
+
+    #[test]
+    fn test_multi_phase_interaction() {
+        const LOG_DOMAIN_SIZE: u32 = 5;
+        const N_INTERACTION_PHASES: usize = 3;
+        let air = TestAir {
+            component: TestComponent {
+                log_size: LOG_DOMAIN_SIZE,
+                max_constraint_log_degree_bound: LOG_DOMAIN_SIZE + 1,
+            },
+            n_interaction_phases: N_INTERACTION_PHASES,
+            public_input: BaseField::zero(),
+        };
+        let domain = CanonicCoset::new(LOG_DOMAIN_SIZE).circle_domain();
+        let values = vec![BaseField::zero(); 1 << LOG_DOMAIN_SIZE];
+        let trace = vec![CpuCircleEvaluation::new(domain, values)];
+        let config = PcsConfig::default();
+        let twiddles = CpuBackend::precompute_twiddles(
+            CanonicCoset::new(LOG_DOMAIN_SIZE + config.fri_config.log_blowup_factor)
+                .circle_domain()
+                .half_coset,
+        );
+
+        let preprocessed_trace = PreprocessedTrace::new(&air, config.clone());
+
+        let (commitment_scheme, interaction_elements, interaction_proofs_of_work) =
+            evaluate_and_commit_on_trace::<CpuBackend, Blake2sMerkleChannel>(
+                &air,
+                &mut test_channel(),
+                &twiddles,
+                &preprocessed_trace,
+                trace,
+                config.clone(),
+            )
+            .unwrap();
+
+        // Each phase draws its elements after the cap of the previous phase is mixed in.
+        let caps = commitment_scheme.caps();
+        assert_eq!(caps.len(), N_INTERACTION_PHASES + 2);
+        let mut channel = test_channel();
+        air.public_input.mix_into(&mut channel);
+        config.mix_into(&mut channel);
+        Blake2sMerkleChannel::mix_cap(&mut channel, &caps[PREPROCESSED_TRACE]);
+        for phase in INTERACTION_TRACE..INTERACTION_TRACE + N_INTERACTION_PHASES {
+            Blake2sMerkleChannel::mix_cap(&mut channel, &caps[phase - 1]);
+            channel.mix_nonce(interaction_proofs_of_work[phase - INTERACTION_TRACE].nonce);
+            let element = interaction_elements[format!("phase_{phase}").as_str()];
+            assert_eq!(element, channel.draw_felt());
+        }
+    }
+
+    #[test]
+    fn test_preprocessed_trace_reused_across_proofs() {
+        const LOG_DOMAIN_SIZE: u32 = 5;
+        let (air, trace) = preprocessed_test_air_and_trace(LOG_DOMAIN_SIZE);
+        let config = PcsConfig::default();
+        let preprocessed_trace =
+            PreprocessedTrace::<CpuBackend, Blake2sMerkleChannel>::new(&air, config.clone());
+
+        for _ in 0..2 {
+            let proof = commit_and_prove_with_preprocessed_trace::<_, Blake2sMerkleChannel>(
+                &air,
+                &mut test_channel(),
+                &preprocessed_trace,
+                trace.clone(),
+                config.clone(),
+            )
+            .unwrap();
+            assert_eq!(
+                proof.commitments[PREPROCESSED_TRACE],
+                preprocessed_trace.cap()
+            );
+
+            commit_and_verify_with_preprocessed_root::<Blake2sMerkleChannel>(
+                proof,
+                &air,
+                &mut test_channel(),
+                preprocessed_trace.cap(),
+                config.clone(),
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_preprocessed_trace_with_merkle_cap() {
+        const LOG_DOMAIN_SIZE: u32 = 5;
+        let (air, trace) = preprocessed_test_air_and_trace(LOG_DOMAIN_SIZE);
+        let config = PcsConfig::default().with_merkle_config(
+            PREPROCESSED_TRACE,
+            MerkleConfig::new(1, 1).with_log_cap_size(2),
+        );
+        let preprocessed_trace =
+            PreprocessedTrace::<CpuBackend, Blake2sMerkleChannel>::new(&air, config.clone());
+
+        let proof = commit_and_prove_with_preprocessed_trace::<_, Blake2sMerkleChannel>(
+            &air,
+            &mut test_channel(),
+            &preprocessed_trace,
+            trace,
+            config.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(preprocessed_trace.cap().len(), 4);
+        assert_eq!(
+            proof.commitments[PREPROCESSED_TRACE],
+            preprocessed_trace.cap()
+        );
+        commit_and_verify_with_preprocessed_root::<Blake2sMerkleChannel>(
+            proof,
+            &air,
+            &mut test_channel(),
+            preprocessed_trace.cap(),
+            config,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_preprocessed_root_mismatch() {
+        const LOG_DOMAIN_SIZE: u32 = 5;
+        let (air, trace) = preprocessed_test_air_and_trace(LOG_DOMAIN_SIZE);
+        let config = PcsConfig::default();
+        let proof = commit_and_prove::<_, Blake2sMerkleChannel>(
+            &air,
+            &mut test_channel(),
+            trace,
+            config.clone(),
+        )
+        .unwrap();
+
+        // The verifier expects the AIR to have no preprocessed columns.
+        let error =
+            commit_and_verify::<Blake2sMerkleChannel>(proof, &air, &mut test_channel(), config)
+                .unwrap_err();
+        assert!(matches!(error, VerificationError::PreprocessedRootMismatch));
+    }
+
+    #[test]
+    fn test_trace_not_matching_preprocessed_column() {
+        const LOG_DOMAIN_SIZE: u32 = 5;
+        let (air, _) = preprocessed_test_air_and_trace(LOG_DOMAIN_SIZE);
+        let domain = CanonicCoset::new(LOG_DOMAIN_SIZE).circle_domain();
+        let values = vec![BaseField::zero(); 1 << LOG_DOMAIN_SIZE];
+        let trace = vec![CpuCircleEvaluation::new(domain, values)];
+
+        let error = commit_and_prove::<_, Blake2sMerkleChannel>(
+            &air,
+            &mut test_channel(),
+            trace,
+            PcsConfig::default(),
+        )
+        .unwrap_err();
+        assert!(matches!(error, ProvingError::ConstraintsNotSatisfied));
+    }
+
+    #[test]
+    fn test_tampered_preprocessed_column() {
+        const LOG_DOMAIN_SIZE: u32 = 5;
+        let (air, trace) = preprocessed_test_air_and_trace(LOG_DOMAIN_SIZE);
+        let config = PcsConfig::default();
+        let preprocessed_trace =
+            PreprocessedTrace::<CpuBackend, Blake2sMerkleChannel>::new(&air, config.clone());
+        let mut proof = commit_and_prove_with_preprocessed_trace::<_, Blake2sMerkleChannel>(
+            &air,
+            &mut test_channel(),
+            &preprocessed_trace,
+            trace,
+            config.clone(),
+        )
+        .unwrap();
+
+        // The constraint reads the preprocessed column at the OODS point.
+        proof.commitment_scheme_proof.sampled_values[PREPROCESSED_TRACE][0][0] +=
+            SecureField::one();
+        let error = commit_and_verify_with_preprocessed_root::<Blake2sMerkleChannel>(
+            proof,
+            &air,
+            &mut test_channel(),
+            preprocessed_trace.cap(),
+            config,
+        )
+        .unwrap_err();
+        assert!(matches!(error, VerificationError::OodsNotMatching));
+    }
+
+    #[test]
+    fn test_proof_bound_to_public_input() {
+        const LOG_DOMAIN_SIZE: u32 = 5;
+        let (mut air, trace) = preprocessed_test_air_and_trace(LOG_DOMAIN_SIZE);
+        // Without grinding, so that the verifier reaches the OODS check.
+        let config = PcsConfig {
+            pow_bits: 0,
+            ..Default::default()
+        };
+        air.public_input = BaseField::from(1);
+        let proof = commit_and_prove::<_, Blake2sMerkleChannel>(
+            &air,
+            &mut test_channel(),
+            trace,
+            config.clone(),
+        )
+        .unwrap();
+        let preprocessed_root =
+            PreprocessedTrace::<CpuBackend, Blake2sMerkleChannel>::new(&air, config.clone()).cap();
+
+        // The constraints do not depend on the public input, so only the transcript tells the
+        // two statements apart: the verifier draws another OODS point, at which the sampled
+        // values of the proof don't satisfy the constraint.
+        air.public_input = BaseField::from(2);
+        let error = commit_and_verify_with_preprocessed_root::<Blake2sMerkleChannel>(
+            proof,
+            &air,
+            &mut test_channel(),
+            preprocessed_root,
+            config,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            VerificationError::OodsNotMatching | VerificationError::Fri(_)
+        ));
+    }
 }
@@ -0,0 +1,34 @@
+//! Accumulators for a random linear combination of circle polynomials.
+//! Given N polynomials, u_0(P), ... u_{N-1}(P), and a random alpha, the combined polynomial is
+//! defined as
+//!   f(p) = sum_i alpha^{N-1-i} u_i(P).
+
+use crate::fields::qm31::SecureField;
+
+/// Accumulates N evaluations of u_i(P0) at a single point.
+/// Computes f(P0), the combined polynomial at that point.
+/// For n accumulated evaluations, the i'th evaluation is multiplied by alpha^(N-1-i).
+pub struct PointEvaluationAccumulator {
+    random_coeff: SecureField,
+    accumulation: SecureField,
+}
+
+impl PointEvaluationAccumulator {
+    /// Creates a new accumulator.
+    /// `random_coeff` should be a secure random field element, drawn from the channel.
+    pub fn new(random_coeff: SecureField) -> Self {
+        Self {
+            random_coeff,
+            accumulation: SecureField::default(),
+        }
+    }
+
+    /// Accumulates u_i(P0), a polynomial evaluation at a P0 in reverse order.
+    pub fn accumulate(&mut self, evaluation: SecureField) {
+        self.accumulation = self.accumulation * self.random_coeff + evaluation;
+    }
+
+    pub fn finalize(self) -> SecureField {
+        self.accumulation
+    }
+}
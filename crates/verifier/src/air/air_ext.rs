@@ -0,0 +1,81 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use itertools::zip_eq;
+
+use super::accumulation::PointEvaluationAccumulator;
+use super::Air;
+use crate::circle::CirclePoint;
+use crate::fields::qm31::{SecureField, SECURE_EXTENSION_DEGREE};
+use crate::pcs::TreeVec;
+use crate::{ColumnVec, InteractionElements, LookupValues};
+
+pub trait AirExt: Air {
+    fn composition_log_degree_bound(&self) -> u32 {
+        self.components()
+            .iter()
+            .map(|component| component.max_constraint_log_degree_bound())
+            .max()
+            .unwrap()
+    }
+
+    fn mask_points(
+        &self,
+        point: CirclePoint<SecureField>,
+    ) -> TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>> {
+        let mut air_points = TreeVec::default();
+        for component in self.components() {
+            let component_points = component.mask_points(point);
+            if air_points.len() < component_points.len() {
+                air_points.resize(component_points.len(), vec![]);
+            }
+            air_points.as_mut().zip_eq(component_points).map(
+                |(air_tree_points, component_tree_points)| {
+                    air_tree_points.extend(component_tree_points);
+                },
+            );
+        }
+        // Add the composition polynomial mask points.
+        air_points.push(vec![vec![point]; SECURE_EXTENSION_DEGREE]);
+        air_points
+    }
+
+    fn eval_composition_polynomial_at_point(
+        &self,
+        point: CirclePoint<SecureField>,
+        mask_values: &Vec<TreeVec<Vec<Vec<SecureField>>>>,
+        random_coeff: SecureField,
+        interaction_elements: &InteractionElements,
+        lookup_values: &LookupValues,
+    ) -> SecureField {
+        let mut evaluation_accumulator = PointEvaluationAccumulator::new(random_coeff);
+        zip_eq(self.components(), mask_values).for_each(|(component, mask)| {
+            component.evaluate_constraint_quotients_at_point(
+                point,
+                mask,
+                &mut evaluation_accumulator,
+                interaction_elements,
+                lookup_values,
+            )
+        });
+        evaluation_accumulator.finalize()
+    }
+
+    fn column_log_sizes(&self) -> TreeVec<ColumnVec<u32>> {
+        let mut air_sizes = TreeVec::default();
+        self.components().iter().for_each(|component| {
+            let component_sizes = component.trace_log_degree_bounds();
+            if air_sizes.len() < component_sizes.len() {
+                air_sizes.resize(component_sizes.len(), vec![]);
+            }
+            air_sizes.as_mut().zip_eq(component_sizes).map(
+                |(air_tree_sizes, component_tree_sizes)| {
+                    air_tree_sizes.extend(component_tree_sizes)
+                },
+            );
+        });
+        air_sizes
+    }
+}
+
+impl<A: Air + ?Sized> AirExt for A {}
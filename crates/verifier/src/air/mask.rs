@@ -1,12 +1,13 @@
-use std::collections::HashSet;
-use std::vec;
+use alloc::collections::BTreeSet;
+use alloc::vec;
+use alloc::vec::Vec;
 
 use itertools::Itertools;
 
-use crate::core::circle::CirclePoint;
-use crate::core::fields::qm31::SecureField;
-use crate::core::poly::circle::CanonicCoset;
-use crate::core::ColumnVec;
+use crate::circle::CirclePoint;
+use crate::fields::qm31::SecureField;
+use crate::poly::circle::CanonicCoset;
+use crate::ColumnVec;
 
 /// Mask holds a vector with an entry for each column.
 /// Each entry holds a list of mask items, which are the offsets of the mask at that column.
@@ -20,8 +21,8 @@ pub fn fixed_mask_points(
 ) -> ColumnVec<Vec<CirclePoint<SecureField>>> {
     assert_eq!(
         mask.iter()
-            .flat_map(|mask_entry| mask_entry.iter().collect::<HashSet<_>>())
-            .collect::<HashSet<&usize>>()
+            .flat_map(|mask_entry| mask_entry.iter().collect::<BTreeSet<_>>())
+            .collect::<BTreeSet<&usize>>()
             .into_iter()
             .collect_vec(),
         vec![&0]
@@ -51,9 +52,12 @@ pub fn shifted_mask_points(
 
 #[cfg(test)]
 mod tests {
-    use crate::core::air::mask::{fixed_mask_points, shifted_mask_points};
-    use crate::core::circle::CirclePoint;
-    use crate::core::poly::circle::CanonicCoset;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use crate::air::mask::{fixed_mask_points, shifted_mask_points};
+    use crate::circle::CirclePoint;
+    use crate::poly::circle::CanonicCoset;
 
     #[test]
     fn test_mask_fixed_points() {
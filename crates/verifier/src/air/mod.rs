@@ -0,0 +1,131 @@
+use alloc::vec::Vec;
+
+use itertools::Itertools;
+
+use self::accumulation::PointEvaluationAccumulator;
+use super::channel::Channel;
+use super::circle::CirclePoint;
+use super::fields::m31::BaseField;
+use super::fields::qm31::SecureField;
+use super::pcs::TreeVec;
+use super::prover::VerificationError;
+use super::{ColumnVec, InteractionElements, LookupValues};
+
+pub mod accumulation;
+mod air_ext;
+pub mod mask;
+
+pub use air_ext::AirExt;
+
+/// Arithmetic Intermediate Representation (AIR).
+/// An Air instance is assumed to already contain all the information needed to
+/// evaluate the constraints.
+/// For instance, all interaction elements are assumed to be present in it.
+/// Therefore, an AIR is generated only after the initial trace commitment phase.
+// TODO(spapini): consider renaming this struct.
+pub trait Air {
+    fn components(&self) -> Vec<&dyn Component>;
+
+    /// Returns the public statement proven about this AIR. It is mixed into the channel before
+    /// anything else on both the prover and the verifier side.
+    fn public_input(&self) -> impl PublicInput;
+
+    /// Verifies the lookups done in the Air.
+    fn verify_lookups(&self, lookup_values: &LookupValues) -> Result<(), VerificationError>;
+}
+
+/// The public statement of a proof, such as the claimed output of a computation.
+///
+/// Mixing it into the channel before the first commitment binds the proof to the statement, so a
+/// proof for one statement does not verify for another.
+pub trait PublicInput {
+    fn mix_into(&self, channel: &mut impl Channel);
+}
+
+impl PublicInput for () {
+    fn mix_into(&self, _channel: &mut impl Channel) {}
+}
+
+impl PublicInput for BaseField {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_felts(&[(*self).into()]);
+    }
+}
+
+/// Mixed with a length prefix, so that statements of different lengths never collide.
+impl PublicInput for [BaseField] {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        let felts = [BaseField::from(self.len())]
+            .iter()
+            .chain(self)
+            .map(|&value| value.into())
+            .collect_vec();
+        channel.mix_felts(&felts);
+    }
+}
+
+impl PublicInput for Vec<BaseField> {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        self.as_slice().mix_into(channel);
+    }
+}
+
+impl<T: PublicInput + ?Sized> PublicInput for &T {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        (**self).mix_into(channel);
+    }
+}
+
+/// A component is a set of trace columns of various sizes along with a set of
+/// constraints on them.
+pub trait Component {
+    fn n_constraints(&self) -> usize;
+
+    fn max_constraint_log_degree_bound(&self) -> u32;
+
+    /// Returns the degree bounds of each trace column. The returned TreeVec should be of size
+    /// `n_interaction_phases`.
+    fn trace_log_degree_bounds(&self) -> TreeVec<ColumnVec<u32>>;
+
+    /// Returns the mask points for each trace column. The returned TreeVec should be of size
+    /// `n_interaction_phases`.
+    fn mask_points(
+        &self,
+        point: CirclePoint<SecureField>,
+    ) -> TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>>;
+
+    /// Evaluates the constraint quotients combination of the component at a point.
+    fn evaluate_constraint_quotients_at_point(
+        &self,
+        point: CirclePoint<SecureField>,
+        mask: &TreeVec<ColumnVec<Vec<SecureField>>>,
+        evaluation_accumulator: &mut PointEvaluationAccumulator,
+        interaction_elements: &InteractionElements,
+        lookup_values: &LookupValues,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PublicInput;
+    use crate::channel::{Blake2sChannel, Channel};
+    use crate::fields::m31::BaseField;
+    use crate::vcs::blake2_hash::Blake2sHash;
+
+    fn digest_after_mixing(public_input: &[BaseField]) -> Blake2sHash {
+        let mut channel = Blake2sChannel::new(Blake2sHash::default());
+        public_input.mix_into(&mut channel);
+        channel.get_digest()
+    }
+
+    #[test]
+    fn test_public_input_of_different_lengths_mixes_differently() {
+        let zero = BaseField::from(0);
+
+        assert_ne!(digest_after_mixing(&[]), digest_after_mixing(&[zero]));
+        assert_ne!(
+            digest_after_mixing(&[zero]),
+            digest_after_mixing(&[zero, zero])
+        );
+    }
+}
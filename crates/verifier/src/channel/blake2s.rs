@@ -1,12 +1,13 @@
-use std::iter;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter;
 
-use super::{Channel, ChannelTime};
-use crate::core::fields::m31::{BaseField, N_BYTES_FELT, P};
-use crate::core::fields::qm31::SecureField;
-use crate::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
-use crate::core::fields::IntoSlice;
-use crate::core::vcs::blake2_hash::{Blake2sHash, Blake2sHasher};
-use crate::core::vcs::hasher::Hasher;
+use super::{count_leading_zeros, Channel, ChannelTime};
+use crate::fields::m31::{BaseField, N_BYTES_FELT, P};
+use crate::fields::qm31::{SecureField, SECURE_EXTENSION_DEGREE};
+use crate::fields::IntoSlice;
+use crate::vcs::blake2_hash::{Blake2sHash, Blake2sHasher};
+use crate::vcs::hasher::Hasher;
 
 pub const BLAKE_BYTES_PER_HASH: usize = 32;
 pub const FELTS_PER_HASH: usize = 8;
@@ -116,17 +117,31 @@ impl Channel for Blake2sChannel {
         self.channel_time.inc_sent();
         Blake2sHasher::hash(&hash_input).into()
     }
+
+    fn verify_pow_nonce(&self, n_bits: u32, nonce: u64) -> bool {
+        let hash_input = self
+            .digest
+            .as_ref()
+            .iter()
+            .chain(nonce.to_le_bytes().iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        let hash = Blake2sHasher::hash(&hash_input);
+        count_leading_zeros(hash.as_ref()) >= n_bits
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::BTreeSet;
+    use alloc::collections::BTreeSet;
+    use alloc::vec;
+    use alloc::vec::Vec;
 
-    use crate::core::channel::blake2s::Blake2sChannel;
-    use crate::core::channel::Channel;
-    use crate::core::fields::qm31::SecureField;
-    use crate::core::vcs::blake2_hash::Blake2sHash;
+    use crate::channel::blake2s::Blake2sChannel;
+    use crate::channel::Channel;
+    use crate::fields::qm31::SecureField;
     use crate::m31;
+    use crate::vcs::blake2_hash::Blake2sHash;
 
     #[test]
     fn test_initialize_channel() {
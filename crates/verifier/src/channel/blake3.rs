@@ -0,0 +1,194 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter;
+
+use super::blake2s::{BLAKE_BYTES_PER_HASH, FELTS_PER_HASH};
+use super::{count_leading_zeros, Channel, ChannelTime};
+use crate::fields::m31::{BaseField, N_BYTES_FELT, P};
+use crate::fields::qm31::{SecureField, SECURE_EXTENSION_DEGREE};
+use crate::fields::IntoSlice;
+use crate::vcs::blake3_hash::{Blake3Hash, Blake3Hasher};
+use crate::vcs::hasher::Hasher;
+
+/// A channel that can be used to draw random elements from a [Blake3Hash] digest.
+pub struct Blake3Channel {
+    digest: Blake3Hash,
+    channel_time: ChannelTime,
+}
+
+impl Blake3Channel {
+    /// Generates a uniform random vector of BaseField elements.
+    fn draw_base_felts(&mut self) -> [BaseField; FELTS_PER_HASH] {
+        // Repeats hashing with an increasing counter until getting a good result.
+        // Retry probability for each round is ~ 2^(-28).
+        loop {
+            let u32s: [u32; FELTS_PER_HASH] = self
+                .draw_random_bytes()
+                .chunks_exact(N_BYTES_FELT) // 4 bytes per u32.
+                .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap();
+
+            // Retry if not all the u32 are in the range [0, 2P).
+            if u32s.iter().all(|x| *x < 2 * P) {
+                return u32s
+                    .into_iter()
+                    .map(|x| BaseField::reduce(x as u64))
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .unwrap();
+            }
+        }
+    }
+}
+
+impl Channel for Blake3Channel {
+    type Digest = Blake3Hash;
+    const BYTES_PER_HASH: usize = BLAKE_BYTES_PER_HASH;
+
+    fn new(digest: Self::Digest) -> Self {
+        Blake3Channel {
+            digest,
+            channel_time: ChannelTime::default(),
+        }
+    }
+
+    fn get_digest(&self) -> Self::Digest {
+        self.digest
+    }
+
+    fn mix_digest(&mut self, digest: Self::Digest) {
+        self.digest = Blake3Hasher::concat_and_hash(&self.digest, &digest);
+        self.channel_time.inc_challenges();
+    }
+
+    fn mix_felts(&mut self, felts: &[SecureField]) {
+        let mut hasher = Blake3Hasher::new();
+        hasher.update(self.digest.as_ref());
+        hasher.update(IntoSlice::<u8>::into_slice(felts));
+
+        self.digest = hasher.finalize();
+        self.channel_time.inc_challenges();
+    }
+
+    fn mix_nonce(&mut self, nonce: u64) {
+        // Copy the elements from the original array to the new array
+        let mut padded_nonce = vec![0; BLAKE_BYTES_PER_HASH];
+        padded_nonce[..8].copy_from_slice(&nonce.to_le_bytes());
+
+        self.digest = Blake3Hasher::concat_and_hash(&self.digest, &Blake3Hash::from(padded_nonce));
+        self.channel_time.inc_challenges();
+    }
+
+    fn draw_felt(&mut self) -> SecureField {
+        let felts: [BaseField; FELTS_PER_HASH] = self.draw_base_felts();
+        SecureField::from_m31_array(felts[..SECURE_EXTENSION_DEGREE].try_into().unwrap())
+    }
+
+    fn draw_felts(&mut self, n_felts: usize) -> Vec<SecureField> {
+        let mut felts = iter::from_fn(|| Some(self.draw_base_felts())).flatten();
+        let secure_felts = iter::from_fn(|| {
+            Some(SecureField::from_m31_array([
+                felts.next()?,
+                felts.next()?,
+                felts.next()?,
+                felts.next()?,
+            ]))
+        });
+        secure_felts.take(n_felts).collect()
+    }
+
+    fn draw_random_bytes(&mut self) -> Vec<u8> {
+        let mut hash_input = self.digest.as_ref().to_vec();
+
+        // Pad the counter to 32 bytes.
+        let mut padded_counter = [0; BLAKE_BYTES_PER_HASH];
+        let counter_bytes = self.channel_time.n_sent.to_le_bytes();
+        padded_counter[0..counter_bytes.len()].copy_from_slice(&counter_bytes);
+
+        hash_input.extend_from_slice(&padded_counter);
+
+        self.channel_time.inc_sent();
+        Blake3Hasher::hash(&hash_input).into()
+    }
+
+    fn verify_pow_nonce(&self, n_bits: u32, nonce: u64) -> bool {
+        let hash_input = self
+            .digest
+            .as_ref()
+            .iter()
+            .chain(nonce.to_le_bytes().iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        let hash = Blake3Hasher::hash(&hash_input);
+        count_leading_zeros(hash.as_ref()) >= n_bits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use crate::channel::blake3::Blake3Channel;
+    use crate::channel::Channel;
+    use crate::fields::qm31::SecureField;
+    use crate::m31;
+    use crate::vcs::blake3_hash::Blake3Hash;
+
+    #[test]
+    fn test_draw_random_bytes() {
+        let initial_digest = Blake3Hash::from(vec![1; 32]);
+        let mut channel = Blake3Channel::new(initial_digest);
+
+        let first_random_bytes = channel.draw_random_bytes();
+
+        // Assert that next random bytes are different.
+        assert_ne!(first_random_bytes, channel.draw_random_bytes());
+    }
+
+    #[test]
+    pub fn test_draw_felts() {
+        let initial_digest = Blake3Hash::from(vec![2; 32]);
+        let mut channel = Blake3Channel::new(initial_digest);
+
+        let mut random_felts = channel.draw_felts(5);
+        random_felts.extend(channel.draw_felts(4));
+
+        // Assert that all the random felts are unique.
+        assert_eq!(
+            random_felts.len(),
+            random_felts.iter().collect::<BTreeSet<_>>().len()
+        );
+    }
+
+    #[test]
+    pub fn test_mix_digest() {
+        let initial_digest = Blake3Hash::from(vec![0; 32]);
+        let mut channel = Blake3Channel::new(initial_digest);
+
+        for _ in 0..10 {
+            channel.draw_random_bytes();
+            channel.draw_felt();
+        }
+
+        // Reseed channel and check the digest was changed.
+        channel.mix_digest(Blake3Hash::from(vec![1; 32]));
+        assert_ne!(initial_digest, channel.digest);
+    }
+
+    #[test]
+    pub fn test_mix_felts() {
+        let initial_digest = Blake3Hash::from(vec![0; 32]);
+        let mut channel = Blake3Channel::new(initial_digest);
+        let felts: Vec<SecureField> = (0..2)
+            .map(|i| SecureField::from(m31!(i + 1923782)))
+            .collect();
+
+        channel.mix_felts(felts.as_slice());
+
+        assert_ne!(initial_digest, channel.digest);
+    }
+}
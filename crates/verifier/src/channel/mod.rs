@@ -0,0 +1,87 @@
+use alloc::vec::Vec;
+
+use super::fields::qm31::SecureField;
+use super::vcs::ops::MerkleHasher;
+
+mod blake2s;
+mod blake3;
+mod poseidon252;
+pub mod poseidon2_m31;
+
+pub use blake2s::Blake2sChannel;
+pub use blake3::Blake3Channel;
+pub use poseidon252::Poseidon252Channel;
+pub use poseidon2_m31::Poseidon2M31Channel;
+
+pub const EXTENSION_FELTS_PER_HASH: usize = 2;
+
+#[derive(Default)]
+pub struct ChannelTime {
+    n_challenges: usize,
+    n_sent: usize,
+}
+
+impl ChannelTime {
+    fn inc_sent(&mut self) {
+        self.n_sent += 1;
+    }
+
+    fn inc_challenges(&mut self) {
+        self.n_challenges += 1;
+        self.n_sent = 0;
+    }
+}
+
+pub trait Channel {
+    type Digest;
+
+    const BYTES_PER_HASH: usize;
+
+    fn new(digest: Self::Digest) -> Self;
+    fn get_digest(&self) -> Self::Digest;
+
+    // Mix functions.
+    fn mix_digest(&mut self, digest: Self::Digest);
+    fn mix_felts(&mut self, felts: &[SecureField]);
+    fn mix_nonce(&mut self, nonce: u64);
+
+    // Draw functions.
+    fn draw_felt(&mut self) -> SecureField;
+    /// Generates a uniform random vector of SecureField elements.
+    fn draw_felts(&mut self, n_felts: usize) -> Vec<SecureField>;
+    /// Returns a vector of random bytes of length `BYTES_PER_HASH`.
+    fn draw_random_bytes(&mut self) -> Vec<u8>;
+
+    /// Checks that hashing the current digest with `nonce` gives at least `n_bits` zero bits.
+    /// Does not mix the nonce into the channel.
+    fn verify_pow_nonce(&self, n_bits: u32, nonce: u64) -> bool;
+}
+
+/// Counts the leading zero bits of `bytes`, read as a little endian number.
+fn count_leading_zeros(bytes: &[u8]) -> u32 {
+    let mut n_bits = 0;
+    for byte in bytes.iter().rev() {
+        if *byte == 0 {
+            n_bits += 8;
+        } else {
+            n_bits += byte.leading_zeros();
+            break;
+        }
+    }
+    n_bits
+}
+
+/// Pairs a Fiat-Shamir [Channel] with the [MerkleHasher] used for commitments.
+/// The channel digest is the Merkle hash type, so roots can be mixed directly into the channel.
+pub trait MerkleChannel: Default {
+    type C: Channel<Digest = <Self::H as MerkleHasher>::Hash>;
+    type H: MerkleHasher;
+
+    /// Mixes the cap of a Merkle tree into the channel, node by node. A cap of a single node, a
+    /// root, is mixed as a single digest.
+    fn mix_cap(channel: &mut Self::C, cap: &[<Self::H as MerkleHasher>::Hash]) {
+        for &node in cap {
+            channel.mix_digest(node);
+        }
+    }
+}
@@ -1,12 +1,12 @@
-use std::iter;
+use alloc::vec::Vec;
+use core::iter;
 
 use starknet_crypto::{poseidon_hash, poseidon_hash_many};
 use starknet_ff::FieldElement as FieldElement252;
 
 use super::{Channel, ChannelTime};
-use crate::core::fields::m31::BaseField;
-use crate::core::fields::qm31::SecureField;
-use crate::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
+use crate::fields::m31::BaseField;
+use crate::fields::qm31::{SecureField, SECURE_EXTENSION_DEGREE};
 
 pub const BYTES_PER_FELT252: usize = 31;
 pub const FELTS_PER_HASH: usize = 8;
@@ -30,7 +30,7 @@ impl Poseidon252Channel {
         let shift = (1u64 << 31).into();
 
         let mut cur = self.draw_felt252();
-        let u32s: [u32; 8] = std::array::from_fn(|_| {
+        let u32s: [u32; 8] = core::array::from_fn(|_| {
             let next = cur.floor_div(shift);
             let res = cur - next * shift;
             cur = next;
@@ -112,7 +112,7 @@ impl Channel for Poseidon252Channel {
     fn draw_random_bytes(&mut self) -> Vec<u8> {
         let shift = (1u64 << 8).into();
         let mut cur = self.draw_felt252();
-        let bytes: [u8; 31] = std::array::from_fn(|_| {
+        let bytes: [u8; 31] = core::array::from_fn(|_| {
             let next = cur.floor_div(shift);
             let res = cur - next * shift;
             cur = next;
@@ -120,17 +120,25 @@ impl Channel for Poseidon252Channel {
         });
         bytes.to_vec()
     }
+
+    fn verify_pow_nonce(&self, n_bits: u32, nonce: u64) -> bool {
+        let hash = poseidon_hash(self.digest, nonce.into());
+        let bytes = hash.to_bytes_be();
+        let low_bits = u128::from_be_bytes(bytes[16..].try_into().unwrap());
+        low_bits.trailing_zeros() >= n_bits
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::BTreeSet;
+    use alloc::collections::BTreeSet;
+    use alloc::vec::Vec;
 
     use starknet_ff::FieldElement as FieldElement252;
 
-    use crate::core::channel::poseidon252::Poseidon252Channel;
-    use crate::core::channel::Channel;
-    use crate::core::fields::qm31::SecureField;
+    use crate::channel::poseidon252::Poseidon252Channel;
+    use crate::channel::Channel;
+    use crate::fields::qm31::SecureField;
     use crate::m31;
 
     #[test]
@@ -0,0 +1,199 @@
+use alloc::vec::Vec;
+use core::iter;
+
+use super::{Channel, ChannelTime};
+use crate::fields::m31::BaseField;
+use crate::fields::qm31::SecureField;
+use crate::vcs::poseidon2_m31::{hash, Poseidon2M31Hash, N_HASH_FELTS};
+
+/// Number of bits in each of the 3 limbs a nonce is split into, so that every limb is an M31
+/// element.
+const NONCE_LIMB_BITS: u32 = 22;
+
+/// A channel that can be used to draw random elements from a [Poseidon2M31Hash] digest.
+/// Every hash is a vector of uniform M31 elements, so no rejection sampling is needed.
+pub struct Poseidon2M31Channel {
+    digest: Poseidon2M31Hash,
+    channel_time: ChannelTime,
+}
+
+impl Poseidon2M31Channel {
+    fn draw_base_felts(&mut self) -> [BaseField; N_HASH_FELTS] {
+        let mut input = self.digest.0.to_vec();
+        input.push(BaseField::from(self.channel_time.n_sent));
+        self.channel_time.inc_sent();
+        hash(&input).0
+    }
+
+    fn hash_with_nonce(&self, nonce: u64) -> Poseidon2M31Hash {
+        let mut input = self.digest.0.to_vec();
+        input.extend(nonce_limbs(nonce));
+        hash(&input)
+    }
+}
+
+impl Channel for Poseidon2M31Channel {
+    type Digest = Poseidon2M31Hash;
+    const BYTES_PER_HASH: usize = 4 * N_HASH_FELTS;
+
+    fn new(digest: Self::Digest) -> Self {
+        Poseidon2M31Channel {
+            digest,
+            channel_time: ChannelTime::default(),
+        }
+    }
+
+    fn get_digest(&self) -> Self::Digest {
+        self.digest
+    }
+
+    fn mix_digest(&mut self, digest: Self::Digest) {
+        self.digest = hash(&[self.digest.0, digest.0].concat());
+        self.channel_time.inc_challenges();
+    }
+
+    fn mix_felts(&mut self, felts: &[SecureField]) {
+        let mut input = self.digest.0.to_vec();
+        input.extend(felts.iter().flat_map(|felt| felt.to_m31_array()));
+        self.digest = hash(&input);
+        self.channel_time.inc_challenges();
+    }
+
+    fn mix_nonce(&mut self, nonce: u64) {
+        self.digest = self.hash_with_nonce(nonce);
+        self.channel_time.inc_challenges();
+    }
+
+    fn draw_felt(&mut self) -> SecureField {
+        let felts = self.draw_base_felts();
+        SecureField::from_m31_array([felts[0], felts[1], felts[2], felts[3]])
+    }
+
+    fn draw_felts(&mut self, n_felts: usize) -> Vec<SecureField> {
+        let mut felts = iter::from_fn(|| Some(self.draw_base_felts())).flatten();
+        let secure_felts = iter::from_fn(|| {
+            Some(SecureField::from_m31_array([
+                felts.next()?,
+                felts.next()?,
+                felts.next()?,
+                felts.next()?,
+            ]))
+        });
+        secure_felts.take(n_felts).collect()
+    }
+
+    /// Returns the little endian bytes of the drawn elements. The top bit of every 4 bytes is
+    /// zero.
+    fn draw_random_bytes(&mut self) -> Vec<u8> {
+        self.draw_base_felts()
+            .iter()
+            .flat_map(|felt| felt.0.to_le_bytes())
+            .collect()
+    }
+
+    /// Counts the trailing zeros of the first two elements of the hash, as 31 bit limbs.
+    fn verify_pow_nonce(&self, n_bits: u32, nonce: u64) -> bool {
+        pow_trailing_zeros(&self.hash_with_nonce(nonce)) >= n_bits
+    }
+}
+
+/// Splits `nonce` into 3 little endian limbs of 22 bits.
+pub fn nonce_limbs(nonce: u64) -> [BaseField; 3] {
+    core::array::from_fn(|i| {
+        let limb = (nonce >> (i as u32 * NONCE_LIMB_BITS)) & ((1 << NONCE_LIMB_BITS) - 1);
+        BaseField::from_u32_unchecked(limb as u32)
+    })
+}
+
+/// Returns the number of trailing zeros of the first two elements of `hash`, as a 62 bit number.
+pub fn pow_trailing_zeros(hash: &Poseidon2M31Hash) -> u32 {
+    let low_bits = hash.0[0].0 as u64 | (hash.0[1].0 as u64) << 31;
+    low_bits.trailing_zeros().min(62)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+    use alloc::vec::Vec;
+
+    use super::{nonce_limbs, Poseidon2M31Channel};
+    use crate::channel::Channel;
+    use crate::fields::qm31::SecureField;
+    use crate::m31;
+    use crate::vcs::poseidon2_m31::Poseidon2M31Hash;
+
+    #[test]
+    fn test_draw_random_bytes() {
+        let mut channel = Poseidon2M31Channel::new(Poseidon2M31Hash::default());
+
+        let first_random_bytes = channel.draw_random_bytes();
+
+        assert_eq!(
+            first_random_bytes.len(),
+            Poseidon2M31Channel::BYTES_PER_HASH
+        );
+        assert_ne!(first_random_bytes, channel.draw_random_bytes());
+    }
+
+    #[test]
+    pub fn test_draw_felts() {
+        let mut channel = Poseidon2M31Channel::new(Poseidon2M31Hash([m31!(2); 8]));
+
+        let mut random_felts = channel.draw_felts(5);
+        random_felts.extend(channel.draw_felts(4));
+
+        // Assert that all the random felts are unique.
+        assert_eq!(
+            random_felts.len(),
+            random_felts.iter().collect::<BTreeSet<_>>().len()
+        );
+    }
+
+    #[test]
+    pub fn test_mix_digest() {
+        let initial_digest = Poseidon2M31Hash::default();
+        let mut channel = Poseidon2M31Channel::new(initial_digest);
+
+        channel.mix_digest(Poseidon2M31Hash([m31!(1); 8]));
+
+        assert_ne!(initial_digest, channel.digest);
+    }
+
+    #[test]
+    pub fn test_mix_felts() {
+        let initial_digest = Poseidon2M31Hash::default();
+        let mut channel = Poseidon2M31Channel::new(initial_digest);
+        let felts: Vec<SecureField> = (0..2)
+            .map(|i| SecureField::from(m31!(i + 1923782)))
+            .collect();
+
+        channel.mix_felts(felts.as_slice());
+
+        assert_ne!(initial_digest, channel.digest);
+    }
+
+    #[test]
+    fn test_nonce_limbs() {
+        let nonce = 0xfedc_ba98_7654_3210;
+
+        let limbs = nonce_limbs(nonce);
+
+        let recombined = limbs
+            .iter()
+            .rev()
+            .fold(0u64, |acc, limb| (acc << 22) | limb.0 as u64);
+        assert_eq!(recombined, nonce);
+    }
+
+    #[test]
+    fn test_verify_pow_nonce_probability() {
+        let channel = Poseidon2M31Channel::new(Poseidon2M31Hash([m31!(7); 8]));
+
+        let n_valid = (0..1 << 10)
+            .filter(|&nonce| channel.verify_pow_nonce(4, nonce))
+            .count();
+
+        // About 1 in 2^4 nonces is valid.
+        assert!((32..128).contains(&n_valid));
+    }
+}
@@ -1,10 +1,10 @@
-use std::ops::{Add, Div, Mul, Neg, Sub};
+use core::ops::{Add, Div, Mul, Neg, Sub};
 
 use super::fields::m31::{BaseField, M31};
 use super::fields::qm31::SecureField;
 use super::fields::{ComplexConjugate, ExtensionOf, Field};
-use crate::core::channel::Channel;
-use crate::core::fields::qm31::P4;
+use crate::channel::Channel;
+use crate::fields::qm31::P4;
 use crate::math::utils::egcd;
 
 /// A point on the complex circle. Treaed as an additive group.
@@ -31,8 +31,8 @@ impl<F: Field> CirclePoint<F> {
     /// # Examples
     ///
     /// ```
-    /// use stwo_prover::core::circle::{CirclePoint, M31_CIRCLE_GEN};
-    /// use stwo_prover::core::fields::m31::M31;
+    /// use stwo_verifier::circle::{CirclePoint, M31_CIRCLE_GEN};
+    /// use stwo_verifier::fields::m31::M31;
     /// let p = M31_CIRCLE_GEN.mul(17);
     /// assert_eq!(CirclePoint::double_x(p.x), (p + p).x);
     /// ```
@@ -47,8 +47,8 @@ impl<F: Field> CirclePoint<F> {
     /// # Examples
     ///
     /// ```
-    /// use stwo_prover::core::circle::{CirclePoint, M31_CIRCLE_GEN, M31_CIRCLE_LOG_ORDER};
-    /// use stwo_prover::core::fields::m31::M31;
+    /// use stwo_verifier::circle::{CirclePoint, M31_CIRCLE_GEN, M31_CIRCLE_LOG_ORDER};
+    /// use stwo_verifier::fields::m31::M31;
     /// assert_eq!(M31_CIRCLE_GEN.log_order(), M31_CIRCLE_LOG_ORDER);
     /// ```
     pub fn log_order(&self) -> u32 {
@@ -184,8 +184,8 @@ impl CirclePoint<SecureField> {
 /// # Examples
 ///
 /// ```
-/// use stwo_prover::core::circle::{CirclePoint, M31_CIRCLE_GEN};
-/// use stwo_prover::core::fields::m31::M31;
+/// use stwo_verifier::circle::{CirclePoint, M31_CIRCLE_GEN};
+/// use stwo_verifier::fields::m31::M31;
 ///
 /// // Adding a generator to itself (2^30) times should NOT yield the identity.
 /// let circle_point = M31_CIRCLE_GEN.repeated_double(30);
@@ -459,17 +459,19 @@ impl<T: Add<Output = T> + Copy> Iterator for CosetIterator<T> {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::BTreeSet;
+    use alloc::collections::BTreeSet;
+    use alloc::vec;
+    use alloc::vec::Vec;
 
     use num_traits::{One, Pow};
 
     use super::{CirclePointIndex, Coset};
-    use crate::core::channel::{Blake2sChannel, Channel};
-    use crate::core::circle::{CirclePoint, SECURE_FIELD_CIRCLE_GEN};
-    use crate::core::fields::qm31::{SecureField, P4};
-    use crate::core::fields::FieldExpOps;
-    use crate::core::poly::circle::CanonicCoset;
-    use crate::core::vcs::blake2_hash::Blake2sHash;
+    use crate::channel::{Blake2sChannel, Channel};
+    use crate::circle::{CirclePoint, SECURE_FIELD_CIRCLE_GEN};
+    use crate::fields::qm31::{SecureField, P4};
+    use crate::fields::FieldExpOps;
+    use crate::poly::circle::CanonicCoset;
+    use crate::vcs::blake2_hash::Blake2sHash;
 
     #[test]
     fn test_iterator() {
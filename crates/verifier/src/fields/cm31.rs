@@ -1,10 +1,10 @@
-use std::fmt::{Debug, Display};
-use std::ops::{
+use core::fmt::{Debug, Display};
+use core::ops::{
     Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
 };
 
 use super::{ComplexConjugate, FieldExpOps};
-use crate::core::fields::m31::M31;
+use crate::fields::m31::M31;
 use crate::{impl_extension_field, impl_field};
 
 pub const P2: u64 = 4611686014132420609; // (2 ** 31 - 1) ** 2
@@ -29,13 +29,13 @@ impl CM31 {
 }
 
 impl Display for CM31 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{} + {}i", self.0, self.1)
     }
 }
 
 impl Debug for CM31 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{} + {}i", self.0, self.1)
     }
 }
@@ -71,22 +71,23 @@ impl FieldExpOps for CM31 {
     }
 }
 
-#[cfg(test)]
 #[macro_export]
 macro_rules! cm31 {
     ($m0:expr, $m1:expr) => {
-        CM31::from_u32_unchecked($m0, $m1)
+        $crate::fields::cm31::CM31::from_u32_unchecked($m0, $m1)
     };
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::vec::Vec;
+
     use rand::rngs::SmallRng;
     use rand::{Rng, SeedableRng};
 
     use super::CM31;
-    use crate::core::fields::m31::P;
-    use crate::core::fields::{FieldExpOps, IntoSlice};
+    use crate::fields::m31::P;
+    use crate::fields::{FieldExpOps, IntoSlice};
     use crate::m31;
 
     #[test]
@@ -1,9 +1,10 @@
-use std::fmt::Display;
-use std::ops::{
+use core::fmt::Display;
+use core::ops::{
     Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
 };
 
 use bytemuck::{Pod, Zeroable};
+#[cfg(any(test, feature = "rand"))]
 use rand::distributions::{Distribution, Standard};
 
 use super::{ComplexConjugate, FieldExpOps};
@@ -42,7 +43,7 @@ impl M31 {
 }
 
 impl Display for M31 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.0)
     }
 }
@@ -126,6 +127,7 @@ impl From<i32> for M31 {
     }
 }
 
+#[cfg(any(test, feature = "rand"))]
 impl Distribution<M31> for Standard {
     // Not intended for cryptographic use. Should only be used in tests and benchmarks.
     fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> M31 {
@@ -133,11 +135,10 @@ impl Distribution<M31> for Standard {
     }
 }
 
-#[cfg(test)]
 #[macro_export]
 macro_rules! m31 {
     ($m:expr) => {
-        $crate::core::fields::m31::M31::from_u32_unchecked($m)
+        $crate::fields::m31::M31::from_u32_unchecked($m)
     };
 }
 
@@ -166,12 +167,14 @@ fn sqn<const N: usize, T: FieldExpOps>(mut v: T) -> T {
 
 #[cfg(test)]
 mod tests {
+    use alloc::vec::Vec;
+
     use rand::rngs::SmallRng;
     use rand::{Rng, SeedableRng};
 
     use super::{M31, P};
-    use crate::core::fields::m31::{pow2147483645, BaseField};
-    use crate::core::fields::{FieldExpOps, IntoSlice};
+    use crate::fields::m31::{pow2147483645, BaseField};
+    use crate::fields::{FieldExpOps, IntoSlice};
 
     fn mul_p(a: u32, b: u32) -> u32 {
         ((a as u64 * b as u64) % P as u64) as u32
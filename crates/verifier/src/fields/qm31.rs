@@ -1,14 +1,15 @@
-use std::fmt::{Debug, Display};
-use std::ops::{
+use core::fmt::{Debug, Display};
+use core::ops::{
     Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
 };
 
-use super::secure_column::SECURE_EXTENSION_DEGREE;
 use super::{ComplexConjugate, FieldExpOps};
-use crate::core::fields::cm31::CM31;
-use crate::core::fields::m31::M31;
+use crate::fields::cm31::CM31;
+use crate::fields::m31::M31;
 use crate::{impl_extension_field, impl_field};
 
+pub const SECURE_EXTENSION_DEGREE: usize = <SecureField as ExtensionOf<M31>>::EXTENSION_DEGREE;
+
 pub const P4: u128 = 21267647892944572736998860269687930881; // (2 ** 31 - 1) ** 4
 pub const R: CM31 = CM31::from_u32_unchecked(2, 1);
 
@@ -54,13 +55,13 @@ impl QM31 {
 }
 
 impl Display for QM31 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "({}) + ({})u", self.0, self.1)
     }
 }
 
 impl Debug for QM31 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "({}) + ({})u", self.0, self.1)
     }
 }
@@ -100,24 +101,25 @@ impl FieldExpOps for QM31 {
     }
 }
 
-#[cfg(test)]
 #[macro_export]
 macro_rules! qm31 {
     ($m0:expr, $m1:expr, $m2:expr, $m3:expr) => {{
-        use $crate::core::fields::qm31::QM31;
+        use $crate::fields::qm31::QM31;
         QM31::from_u32_unchecked($m0, $m1, $m2, $m3)
     }};
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::vec::Vec;
+
     use num_traits::One;
     use rand::rngs::SmallRng;
     use rand::{Rng, SeedableRng};
 
     use super::QM31;
-    use crate::core::fields::m31::P;
-    use crate::core::fields::{FieldExpOps, IntoSlice};
+    use crate::fields::m31::P;
+    use crate::fields::{FieldExpOps, IntoSlice};
     use crate::m31;
 
     #[test]
@@ -0,0 +1,1223 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::zip;
+use core::ops::RangeInclusive;
+
+use itertools::Itertools;
+use num_traits::Zero;
+
+use super::channel::Channel;
+use super::fields::m31::BaseField;
+use super::fields::qm31::{SecureField, SECURE_EXTENSION_DEGREE};
+use super::poly::circle::{CanonicCoset, CircleDomain, MAX_CIRCLE_DOMAIN_LOG_SIZE};
+use super::poly::line::{LineDomain, LinePoly};
+// TODO(andrew): Create fri/ directory, move queries.rs there and split this file up.
+use super::queries::{Queries, SparseSubCircleDomain};
+use crate::circle::Coset;
+use crate::fft::ibutterfly;
+use crate::fields::FieldExpOps;
+use crate::prover::ProofShapeError;
+use crate::utils::{bit_reverse, bit_reverse_index};
+use crate::vcs::ops::MerkleHasher;
+use crate::vcs::verifier::{MerkleDecommitment, MerkleVerificationError, MerkleVerifier};
+
+/// FRI proof config
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FriConfig {
+    pub log_blowup_factor: u32,
+    pub log_last_layer_degree_bound: u32,
+    pub n_queries: usize,
+    /// Maximal number of folds of an inner layer, i.e. each layer folds by up to `2^fold_step`.
+    /// See [`FriConfig::inner_layer_fold_steps`] for the folds of each layer.
+    pub fold_step: u32,
+}
+
+impl FriConfig {
+    const LOG_MIN_LAST_LAYER_DEGREE_BOUND: u32 = 0;
+    const LOG_MAX_LAST_LAYER_DEGREE_BOUND: u32 = 16;
+    pub(crate) const LOG_LAST_LAYER_DEGREE_BOUND_RANGE: RangeInclusive<u32> =
+        Self::LOG_MIN_LAST_LAYER_DEGREE_BOUND..=Self::LOG_MAX_LAST_LAYER_DEGREE_BOUND;
+
+    const LOG_MIN_BLOWUP_FACTOR: u32 = 1;
+    const LOG_MAX_BLOWUP_FACTOR: u32 = 16;
+    pub const LOG_BLOWUP_FACTOR_RANGE: RangeInclusive<u32> =
+        Self::LOG_MIN_BLOWUP_FACTOR..=Self::LOG_MAX_BLOWUP_FACTOR;
+
+    const MIN_FOLD_STEP: u32 = 1;
+    const MAX_FOLD_STEP: u32 = 4;
+    pub const FOLD_STEP_RANGE: RangeInclusive<u32> = Self::MIN_FOLD_STEP..=Self::MAX_FOLD_STEP;
+
+    /// Creates a new FRI configuration, folding each inner layer [`FOLD_STEP`] times.
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// * `log_last_layer_degree_bound` is greater than 16.
+    /// * `log_blowup_factor` is equal to zero or greater than 16.
+    /// * The last layer is folded from a domain larger than the largest circle domain.
+    pub fn new(log_last_layer_degree_bound: u32, log_blowup_factor: u32, n_queries: usize) -> Self {
+        assert!(Self::LOG_LAST_LAYER_DEGREE_BOUND_RANGE.contains(&log_last_layer_degree_bound));
+        assert!(Self::LOG_BLOWUP_FACTOR_RANGE.contains(&log_blowup_factor));
+        assert!(
+            Self::last_layer_domain_fits(log_last_layer_degree_bound, log_blowup_factor),
+            "last layer domain too large"
+        );
+        Self {
+            log_blowup_factor,
+            log_last_layer_degree_bound,
+            n_queries,
+            fold_step: FOLD_STEP,
+        }
+    }
+
+    /// Returns whether the last layer is folded from a circle domain no larger than
+    /// [`MAX_CIRCLE_DOMAIN_LOG_SIZE`].
+    pub(crate) fn last_layer_domain_fits(
+        log_last_layer_degree_bound: u32,
+        log_blowup_factor: u32,
+    ) -> bool {
+        log_last_layer_degree_bound + log_blowup_factor + CIRCLE_TO_LINE_FOLD_STEP
+            <= MAX_CIRCLE_DOMAIN_LOG_SIZE
+    }
+
+    /// Returns the config with inner layers folded up to `fold_step` times.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fold_step` is equal to zero or greater than 4.
+    pub fn with_fold_step(self, fold_step: u32) -> Self {
+        assert!(Self::FOLD_STEP_RANGE.contains(&fold_step));
+        Self { fold_step, ..self }
+    }
+
+    pub fn last_layer_domain_size(&self) -> usize {
+        1 << (self.log_last_layer_degree_bound + self.log_blowup_factor)
+    }
+
+    /// Returns the number of folds of each inner layer, given the log degree bounds of the
+    /// committed circle polynomials in descending order.
+    ///
+    /// Each layer is folded `fold_step` times, unless the degree bound at which the next circle
+    /// polynomial is folded into the layers, or the last layer's degree bound, is reached first.
+    /// Circle polynomials with degree bounds not above the last layer's are ignored.
+    pub fn inner_layer_fold_steps(&self, column_log_degree_bounds: &[u32]) -> Vec<u32> {
+        let last_layer_bound = self.log_last_layer_degree_bound;
+        // Degree bounds of the univariate polynomials the columns are folded into.
+        let mut line_bounds = column_log_degree_bounds
+            .iter()
+            .map(|bound| bound - CIRCLE_TO_LINE_FOLD_STEP)
+            .filter(|&bound| bound > last_layer_bound)
+            .dedup()
+            .peekable();
+        let Some(mut layer_bound) = line_bounds.next() else {
+            return Vec::new();
+        };
+
+        let mut fold_steps = Vec::new();
+        while layer_bound > last_layer_bound {
+            let next_bound = line_bounds.peek().copied().unwrap_or(last_layer_bound);
+            let fold_step = self.fold_step.min(layer_bound - next_bound);
+            layer_bound -= fold_step;
+            line_bounds.next_if_eq(&layer_bound);
+            fold_steps.push(fold_step);
+        }
+        fold_steps
+    }
+
+    /// Conjectured security bits of the query phase, excluding proof of work.
+    ///
+    /// Under the ethSTARK conjecture each query contributes `log_blowup_factor` bits.
+    /// See `pcs::security` for a full analysis.
+    pub const fn security_bits(&self) -> u32 {
+        self.log_blowup_factor * self.n_queries as u32
+    }
+}
+
+pub struct FriVerifier<H: MerkleHasher> {
+    config: FriConfig,
+    /// Alpha used to fold all circle polynomials to univariate polynomials.
+    circle_poly_alpha: SecureField,
+    /// Domain size queries should be sampled from.
+    expected_query_log_domain_size: u32,
+    /// The list of degree bounds of all committed circle polynomials.
+    column_bounds: Vec<CirclePolyDegreeBound>,
+    inner_layers: Vec<FriLayerVerifier<H>>,
+    last_layer_domain: LineDomain,
+    last_layer_poly: LinePoly,
+    /// The queries used for decommitment. Initialized when calling
+    /// [`FriVerifier::column_opening_positions`].
+    queries: Option<Queries>,
+}
+
+impl<H: MerkleHasher> FriVerifier<H> {
+    /// Verifies the commitment stage of FRI.
+    ///
+    /// `column_bounds` should be the committed circle polynomial degree bounds in descending order.
+    ///
+    /// # Errors
+    ///
+    /// An `Err` will be returned if:
+    /// * The proof contains an invalid number of FRI layers.
+    /// * The degree of the last layer polynomial is too high.
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// * There are no degree bounds.
+    /// * The degree bounds are not sorted in descending order.
+    /// * A degree bound is less than or equal to the last layer's degree bound.
+    pub fn commit(
+        channel: &mut impl Channel<Digest = H::Hash>,
+        config: FriConfig,
+        proof: FriProof<H>,
+        column_bounds: Vec<CirclePolyDegreeBound>,
+    ) -> Result<Self, FriVerificationError> {
+        assert!(column_bounds.windows(2).all(|w| w[0] >= w[1]));
+
+        let max_column_bound = column_bounds[0];
+        let expected_query_log_domain_size =
+            max_column_bound.log_degree_bound + config.log_blowup_factor;
+
+        // Circle polynomials can all be folded with the same alpha.
+        let circle_poly_alpha = channel.draw_felt();
+
+        let mut inner_layers = Vec::new();
+        let mut layer_bound = max_column_bound.fold_to_line();
+        let mut layer_domain = LineDomain::new(Coset::half_odds(
+            layer_bound.log_degree_bound + config.log_blowup_factor,
+        ));
+
+        let fold_steps = config.inner_layer_fold_steps(
+            &column_bounds
+                .iter()
+                .map(|bound| bound.log_degree_bound)
+                .collect_vec(),
+        );
+        if proof.inner_layers.len() != fold_steps.len() {
+            return Err(FriVerificationError::InvalidNumFriLayers);
+        }
+
+        for (layer_index, (proof, fold_step)) in zip(proof.inner_layers, fold_steps).enumerate() {
+            channel.mix_digest(proof.commitment);
+
+            // The merkle verification, combined with the decomposition being unique, asserts the
+            // decomposition correctness.
+            channel.mix_felts(&[proof.decomposition_coeff]);
+
+            let folding_alpha = channel.draw_felt();
+
+            inner_layers.push(FriLayerVerifier {
+                degree_bound: layer_bound,
+                domain: layer_domain,
+                folding_alpha,
+                fold_step,
+                layer_index,
+                proof,
+            });
+
+            layer_bound = layer_bound
+                .fold(fold_step)
+                .ok_or(FriVerificationError::InvalidNumFriLayers)?;
+            layer_domain = LineDomain::new(layer_domain.coset().repeated_double(fold_step));
+        }
+
+        if layer_bound.log_degree_bound != config.log_last_layer_degree_bound {
+            return Err(FriVerificationError::InvalidNumFriLayers);
+        }
+
+        let last_layer_domain = layer_domain;
+        let last_layer_poly = proof.last_layer_poly;
+        let last_layer_degree_bound = 1 << config.log_last_layer_degree_bound;
+
+        let degree = last_layer_poly.degree();
+        if degree >= last_layer_degree_bound {
+            return Err(FriVerificationError::LastLayerDegreeInvalid {
+                degree,
+                log_degree_bound: config.log_last_layer_degree_bound,
+            });
+        }
+
+        // Reject padded or shortened polynomials, so that the last layer has a single encoding.
+        if last_layer_poly.len() != last_layer_degree_bound {
+            return Err(FriVerificationError::InvalidStructure(
+                ProofShapeError::LastLayerLength {
+                    expected: last_layer_degree_bound,
+                    actual: last_layer_poly.len(),
+                },
+            ));
+        }
+
+        channel.mix_felts(&last_layer_poly);
+
+        Ok(Self {
+            config,
+            circle_poly_alpha,
+            column_bounds,
+            expected_query_log_domain_size,
+            inner_layers,
+            last_layer_domain,
+            last_layer_poly,
+            queries: None,
+        })
+    }
+
+    /// Verifies the decommitment stage of FRI.
+    ///
+    /// The decommitment values need to be provided in the same order as their commitment. The
+    /// twiddles of the queried evaluations are computed for each proof, see
+    /// [`Self::decommit_with_twiddles`] to index precomputed ones instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// * The queries were not yet sampled.
+    /// * The queries were sampled on the wrong domain size.
+    /// * There aren't the same number of decommitted values as degree bounds.
+    pub fn decommit(
+        mut self,
+        decommitted_values: Vec<SparseCircleEvaluation>,
+    ) -> Result<(), FriVerificationError> {
+        let queries = self.queries.take().expect("queries not sampled");
+        self.decommit_on_queries(&queries, decommitted_values)
+    }
+
+    /// Like [`Self::decommit`], but verifies the decommitment against the given `queries`
+    /// instead of the ones sampled in [`Self::column_query_positions`].
+    pub fn decommit_on_queries(
+        self,
+        queries: &Queries,
+        decommitted_values: Vec<SparseCircleEvaluation>,
+    ) -> Result<(), FriVerificationError> {
+        let folder = Folder {
+            twiddles: None,
+            fold_values,
+        };
+        self.decommit_with_folder(queries, decommitted_values, folder)
+    }
+
+    /// Verifies the decommitment stage of FRI, folding the queried evaluations of each layer
+    /// together with `B`, with inverse twiddles indexed in `twiddles`.
+    ///
+    /// The decommitment values need to be provided in the same order as their commitment.
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// * The queries were not yet sampled.
+    /// * The queries were sampled on the wrong domain size.
+    /// * There aren't the same number of decommitted values as degree bounds.
+    /// * `twiddles` don't cover the queried domain.
+    pub fn decommit_with_twiddles<B: FriVerifierOps>(
+        mut self,
+        decommitted_values: Vec<SparseCircleEvaluation>,
+        twiddles: &FriVerifierTwiddles,
+    ) -> Result<(), FriVerificationError> {
+        let queries = self.queries.take().expect("queries not sampled");
+        self.decommit_on_queries_with_twiddles::<B>(&queries, decommitted_values, twiddles)
+    }
+
+    /// Like [`Self::decommit_with_twiddles`], but verifies the decommitment against the given
+    /// `queries` instead of the ones sampled in [`Self::column_query_positions`].
+    pub fn decommit_on_queries_with_twiddles<B: FriVerifierOps>(
+        self,
+        queries: &Queries,
+        decommitted_values: Vec<SparseCircleEvaluation>,
+        twiddles: &FriVerifierTwiddles,
+    ) -> Result<(), FriVerificationError> {
+        assert!(
+            self.expected_query_log_domain_size <= twiddles.max_log_size,
+            "twiddles don't cover the queried domain"
+        );
+        let folder = Folder {
+            twiddles: Some(twiddles),
+            fold_values: B::fold_values,
+        };
+        self.decommit_with_folder(queries, decommitted_values, folder)
+    }
+
+    fn decommit_with_folder(
+        self,
+        queries: &Queries,
+        decommitted_values: Vec<SparseCircleEvaluation>,
+        folder: Folder<'_>,
+    ) -> Result<(), FriVerificationError> {
+        assert_eq!(queries.log_domain_size, self.expected_query_log_domain_size);
+        assert_eq!(decommitted_values.len(), self.column_bounds.len());
+
+        let (last_layer_queries, last_layer_query_evals) =
+            self.decommit_inner_layers(queries, decommitted_values, folder)?;
+
+        self.decommit_last_layer(last_layer_queries, last_layer_query_evals)
+    }
+
+    /// Verifies all inner layer decommitments.
+    ///
+    /// Returns the queries and query evaluations needed for verifying the last FRI layer.
+    fn decommit_inner_layers(
+        &self,
+        queries: &Queries,
+        decommitted_values: Vec<SparseCircleEvaluation>,
+        folder: Folder<'_>,
+    ) -> Result<(Queries, Vec<SecureField>), FriVerificationError> {
+        let circle_poly_alpha = self.circle_poly_alpha;
+        let circle_poly_alpha_sq = circle_poly_alpha * circle_poly_alpha;
+
+        let mut decommitted_values = decommitted_values.into_iter();
+        let mut column_bounds = self.column_bounds.iter().copied().peekable();
+        let mut layer_queries = queries.fold(CIRCLE_TO_LINE_FOLD_STEP);
+        let mut layer_query_evals = vec![SecureField::zero(); layer_queries.len()];
+
+        for layer in self.inner_layers.iter() {
+            if column_bounds
+                .next_if(|b| b.fold_to_line() == layer.degree_bound)
+                .is_some()
+            {
+                let mut n_columns_in_layer = 1;
+                let mut combined_sparse_evals = decommitted_values.next().unwrap();
+
+                // Check for more columns that were folded into the current layer.
+                while column_bounds
+                    .next_if(|b| b.fold_to_line() == layer.degree_bound)
+                    .is_some()
+                {
+                    combined_sparse_evals
+                        .accumulate(decommitted_values.next().unwrap(), circle_poly_alpha_sq);
+                    n_columns_in_layer += 1;
+                }
+
+                self.project_to_fft_space(
+                    &layer_queries,
+                    &mut combined_sparse_evals,
+                    layer.proof.decomposition_coeff,
+                );
+
+                let folded_evals =
+                    combined_sparse_evals.fold(circle_poly_alpha, &layer_queries, folder);
+                let prev_layer_combination_factor = circle_poly_alpha_sq.pow(n_columns_in_layer);
+                assert_eq!(folded_evals.len(), layer_query_evals.len());
+                for (layer_eval, folded_eval) in zip(&mut layer_query_evals, folded_evals) {
+                    *layer_eval = *layer_eval * prev_layer_combination_factor + folded_eval;
+                }
+            }
+
+            (layer_queries, layer_query_evals) =
+                layer.verify_and_fold(layer_queries, layer_query_evals, folder)?;
+        }
+
+        // Check all values have been consumed.
+        assert!(column_bounds.peek().is_none());
+        assert!(decommitted_values.as_slice().is_empty());
+
+        Ok((layer_queries, layer_query_evals))
+    }
+
+    /// Projects a polynomial to the fft space using the coefficient from the proof.
+    /// See the prover's `FriOps::decompose` for explanation about the decomposition.
+    fn project_to_fft_space(
+        &self,
+        layer_queries: &Queries,
+        evals: &mut SparseCircleEvaluation,
+        lambda: SecureField,
+    ) {
+        let log_domain_size = layer_queries.log_domain_size + CIRCLE_TO_LINE_FOLD_STEP;
+        let log_blowup_factor = self.config.log_blowup_factor;
+        let decomposition_values = decomposition_values(log_domain_size, log_blowup_factor);
+        // Each block of `v` spans `2^log_degree_bound` circle points, i.e. half as many folded
+        // (line) points.
+        let log_block_size = log_domain_size - log_blowup_factor - CIRCLE_TO_LINE_FOLD_STEP;
+        layer_queries
+            .iter()
+            .zip(evals.subcircle_evals.iter_mut())
+            .for_each(|(&q, e)| {
+                let lambda = lambda * decomposition_values[q >> log_block_size];
+                for v in e.values.iter_mut() {
+                    *v -= lambda;
+                }
+            });
+    }
+
+    /// Verifies the last layer.
+    fn decommit_last_layer(
+        self,
+        queries: Queries,
+        query_evals: Vec<SecureField>,
+    ) -> Result<(), FriVerificationError> {
+        let Self {
+            last_layer_domain: domain,
+            last_layer_poly,
+            ..
+        } = self;
+
+        // Evaluating the polynomial at a single point takes time linear in its length. With many
+        // queries, it's cheaper to evaluate it on the whole domain at once.
+        let evaluation_cost = queries.len() * last_layer_poly.len();
+        let fft_cost = domain.size() * domain.log_size() as usize;
+        let expected_evals = if evaluation_cost > fft_cost {
+            let domain_evals = last_layer_poly.evaluate(domain);
+            queries
+                .iter()
+                .map(|&query| domain_evals[bit_reverse_index(query, domain.log_size())])
+                .collect_vec()
+        } else {
+            queries
+                .iter()
+                .map(|&query| {
+                    let x = domain.at(bit_reverse_index(query, domain.log_size()));
+                    last_layer_poly.eval_at_point(x.into())
+                })
+                .collect_vec()
+        };
+
+        if query_evals != expected_evals {
+            return Err(FriVerificationError::LastLayerEvaluationsInvalid);
+        }
+
+        Ok(())
+    }
+
+    /// Samples queries and returns the opening positions for each unique column size.
+    ///
+    /// The order of the opening positions corresponds to the order of the column commitment.
+    pub fn column_query_positions(
+        &mut self,
+        channel: &mut impl Channel<Digest = H::Hash>,
+    ) -> BTreeMap<u32, SparseSubCircleDomain> {
+        let column_log_sizes = self
+            .column_bounds
+            .iter()
+            .dedup()
+            .map(|b| b.log_degree_bound + self.config.log_blowup_factor)
+            .collect_vec();
+        let queries = Queries::generate(channel, column_log_sizes[0], self.config.n_queries);
+        let positions = get_opening_positions(&queries, &column_log_sizes);
+        self.queries = Some(queries);
+        positions
+    }
+}
+
+/// Returns the column opening positions needed for verification.
+///
+/// The column log sizes must be unique and in descending order. Returned
+/// column opening positions are mapped by their log size.
+pub fn get_opening_positions(
+    queries: &Queries,
+    column_log_sizes: &[u32],
+) -> BTreeMap<u32, SparseSubCircleDomain> {
+    let mut prev_log_size = column_log_sizes[0];
+    assert!(prev_log_size == queries.log_domain_size);
+    let mut prev_queries = queries.clone();
+    let mut positions = BTreeMap::new();
+    positions.insert(
+        prev_log_size,
+        prev_queries.opening_positions(CIRCLE_TO_LINE_FOLD_STEP),
+    );
+    for log_size in column_log_sizes.iter().skip(1) {
+        let n_folds = prev_log_size - log_size;
+        let queries = prev_queries.fold(n_folds);
+        positions.insert(
+            *log_size,
+            queries.opening_positions(CIRCLE_TO_LINE_FOLD_STEP),
+        );
+        prev_log_size = *log_size;
+        prev_queries = queries;
+    }
+    positions
+}
+
+pub trait FriChannel {
+    type Digest;
+
+    type Field;
+
+    /// Reseeds the channel with a commitment to an inner FRI layer.
+    fn reseed_with_inner_layer(&mut self, commitment: &Self::Digest);
+
+    /// Reseeds the channel with the FRI last layer polynomial.
+    fn reseed_with_last_layer(&mut self, last_layer: &LinePoly);
+
+    /// Draws a random field element.
+    fn draw(&mut self) -> Self::Field;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FriVerificationError {
+    InvalidNumFriLayers,
+    InnerLayerCommitmentInvalid {
+        layer: usize,
+        error: MerkleVerificationError,
+    },
+    InnerLayerEvaluationsInvalid {
+        layer: usize,
+    },
+    LastLayerDegreeInvalid {
+        degree: usize,
+        log_degree_bound: u32,
+    },
+    LastLayerEvaluationsInvalid,
+    /// The proof does not have the shape the FRI config expects.
+    InvalidStructure(ProofShapeError),
+}
+
+impl fmt::Display for FriVerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidNumFriLayers => {
+                f.write_str("proof contains an invalid number of FRI layers")
+            }
+            Self::InnerLayerCommitmentInvalid { layer, .. } => {
+                write!(
+                    f,
+                    "queries do not resolve to their commitment in layer {layer}"
+                )
+            }
+            Self::InnerLayerEvaluationsInvalid { layer } => {
+                write!(f, "evaluations are invalid in layer {layer}")
+            }
+            Self::LastLayerDegreeInvalid {
+                degree,
+                log_degree_bound,
+            } => write!(
+                f,
+                "degree {degree} of last layer is not below the bound 2^{log_degree_bound}"
+            ),
+            Self::LastLayerEvaluationsInvalid => {
+                f.write_str("evaluations in the last layer are invalid")
+            }
+            Self::InvalidStructure(error) => write!(f, "proof has invalid structure: {error}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FriVerificationError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CirclePolyDegreeBound {
+    log_degree_bound: u32,
+}
+
+impl CirclePolyDegreeBound {
+    pub fn new(log_degree_bound: u32) -> Self {
+        Self { log_degree_bound }
+    }
+
+    /// Maps a circle polynomial's degree bound to the degree bound of the univariate (line)
+    /// polynomial it gets folded into.
+    fn fold_to_line(&self) -> LinePolyDegreeBound {
+        LinePolyDegreeBound {
+            log_degree_bound: self.log_degree_bound - CIRCLE_TO_LINE_FOLD_STEP,
+        }
+    }
+}
+
+impl PartialOrd<LinePolyDegreeBound> for CirclePolyDegreeBound {
+    fn partial_cmp(&self, other: &LinePolyDegreeBound) -> Option<core::cmp::Ordering> {
+        Some(self.log_degree_bound.cmp(&other.log_degree_bound))
+    }
+}
+
+impl PartialEq<LinePolyDegreeBound> for CirclePolyDegreeBound {
+    fn eq(&self, other: &LinePolyDegreeBound) -> bool {
+        self.log_degree_bound == other.log_degree_bound
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct LinePolyDegreeBound {
+    log_degree_bound: u32,
+}
+
+impl LinePolyDegreeBound {
+    /// Returns [None] if the unfolded degree bound is smaller than the folding factor.
+    fn fold(self, n_folds: u32) -> Option<Self> {
+        if self.log_degree_bound < n_folds {
+            return None;
+        }
+
+        let log_degree_bound = self.log_degree_bound - n_folds;
+        Some(Self { log_degree_bound })
+    }
+}
+
+/// A FRI proof.
+#[derive(Debug)]
+pub struct FriProof<H: MerkleHasher> {
+    pub inner_layers: Vec<FriLayerProof<H>>,
+    pub last_layer_poly: LinePoly,
+}
+
+/// Number of folds of a single univariate polynomial folding, and the default number of folds of
+/// an inner layer. See [`FriConfig::fold_step`].
+pub const FOLD_STEP: u32 = 1;
+
+/// Number of folds when folding a circle polynomial to univariate polynomial.
+pub const CIRCLE_TO_LINE_FOLD_STEP: u32 = 1;
+
+/// Stores a subset of evaluations in a fri layer with their corresponding merkle decommitments.
+///
+/// The subset corresponds to the set of evaluations needed by a FRI verifier.
+#[derive(Debug)]
+pub struct FriLayerProof<H: MerkleHasher> {
+    /// The subset stored corresponds to the set of evaluations the verifier doesn't have but needs
+    /// to fold and verify the merkle decommitment.
+    pub evals_subset: Vec<SecureField>,
+    pub decommitment: MerkleDecommitment<H>,
+    pub decomposition_coeff: SecureField,
+    pub commitment: H::Hash,
+}
+
+struct FriLayerVerifier<H: MerkleHasher> {
+    degree_bound: LinePolyDegreeBound,
+    domain: LineDomain,
+    folding_alpha: SecureField,
+    /// Number of times the layer is folded into the next layer.
+    fold_step: u32,
+    layer_index: usize,
+    proof: FriLayerProof<H>,
+}
+
+impl<H: MerkleHasher> FriLayerVerifier<H> {
+    /// Verifies the layer's merkle decommitment and returns the the folded queries and query evals.
+    ///
+    /// # Errors
+    ///
+    /// An `Err` will be returned if:
+    /// * The proof doesn't store enough evaluations.
+    /// * The merkle decommitment is invalid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of queries doesn't match the number of evals.
+    fn verify_and_fold(
+        &self,
+        queries: Queries,
+        evals_at_queries: Vec<SecureField>,
+        folder: Folder<'_>,
+    ) -> Result<(Queries, Vec<SecureField>), FriVerificationError> {
+        let decommitment = self.proof.decommitment.clone();
+        let commitment = self.proof.commitment;
+
+        // Extract the evals needed for decommitment and folding.
+        let sparse_evaluation = self.extract_evaluation(&queries, &evals_at_queries)?;
+
+        // TODO: When leaf values are removed from the decommitment, also remove this block.
+        let mut actual_decommitment_evals: [Vec<BaseField>; SECURE_EXTENSION_DEGREE] =
+            Default::default();
+        for eval in sparse_evaluation
+            .subline_evals
+            .iter()
+            .flat_map(|e| &e.values)
+        {
+            for (column, value) in zip(&mut actual_decommitment_evals, eval.to_m31_array()) {
+                column.push(value);
+            }
+        }
+
+        let folded_queries = queries.fold(self.fold_step);
+
+        // Positions of all the decommitment evals.
+        let decommitment_positions = folded_queries
+            .iter()
+            .flat_map(|folded_query| {
+                let start = folded_query << self.fold_step;
+                let end = start + (1 << self.fold_step);
+                start..end
+            })
+            .collect::<Vec<usize>>();
+
+        let merkle_verifier = MerkleVerifier::new(
+            commitment,
+            vec![self.domain.log_size(); SECURE_EXTENSION_DEGREE],
+        );
+        // TODO(spapini): Propagate error.
+        merkle_verifier
+            .verify(
+                [(self.domain.log_size(), decommitment_positions)]
+                    .into_iter()
+                    .collect(),
+                actual_decommitment_evals.to_vec(),
+                decommitment,
+            )
+            .map_err(|e| FriVerificationError::InnerLayerCommitmentInvalid {
+                layer: self.layer_index,
+                error: e,
+            })?;
+
+        let evals_at_folded_queries =
+            sparse_evaluation.fold(self.folding_alpha, &folded_queries, folder);
+
+        Ok((folded_queries, evals_at_folded_queries))
+    }
+
+    /// Returns the evaluations needed for decommitment.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the proof doesn't store enough evaluations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of queries doesn't match the number of evals.
+    fn extract_evaluation(
+        &self,
+        queries: &Queries,
+        evals_at_queries: &[SecureField],
+    ) -> Result<SparseLineEvaluation, FriVerificationError> {
+        // Evals provided by the verifier.
+        let mut evals_at_queries = evals_at_queries.iter().copied();
+
+        // Evals stored in the proof.
+        let mut proof_evals = self.proof.evals_subset.iter().copied();
+
+        let mut all_subline_evals = Vec::new();
+
+        // Group queries by the subline they reside in.
+        let fold_step = self.fold_step;
+        for (subline_index, subline_queries) in &queries.iter().group_by(|&&q| q >> fold_step) {
+            let subline_start = subline_index << fold_step;
+            let subline_end = subline_start + (1 << fold_step);
+
+            let mut subline_evals = Vec::new();
+            let mut subline_queries = subline_queries.peekable();
+
+            // Insert the evals.
+            for eval_position in subline_start..subline_end {
+                let eval = match subline_queries.next_if_eq(&&eval_position) {
+                    Some(_) => evals_at_queries.next().unwrap(),
+                    None => proof_evals.next().ok_or(
+                        FriVerificationError::InnerLayerEvaluationsInvalid {
+                            layer: self.layer_index,
+                        },
+                    )?,
+                };
+
+                subline_evals.push(eval);
+            }
+
+            // Construct the domain.
+            // TODO(andrew): Create a constructor for LineDomain.
+            let subline_initial_index = bit_reverse_index(subline_start, self.domain.log_size());
+            let subline_initial = self.domain.coset().index_at(subline_initial_index);
+            let subline_domain = LineDomain::new(Coset::new(subline_initial, fold_step));
+
+            all_subline_evals.push(LineSubEvaluation::new(subline_domain, subline_evals));
+        }
+
+        // Check all proof evals have been consumed.
+        if proof_evals.next().is_some() {
+            return Err(FriVerificationError::InnerLayerEvaluationsInvalid {
+                layer: self.layer_index,
+            });
+        }
+
+        Ok(SparseLineEvaluation::new(all_subline_evals, fold_step))
+    }
+}
+
+/// Evaluations of a circle polynomial on a small [CircleDomain], in bit-reversed order.
+#[derive(Debug, Clone)]
+pub struct CircleSubEvaluation {
+    pub domain: CircleDomain,
+    pub values: Vec<SecureField>,
+}
+
+impl CircleSubEvaluation {
+    /// # Panics
+    ///
+    /// Panics if the number of values doesn't match the size of the domain.
+    pub fn new(domain: CircleDomain, values: Vec<SecureField>) -> Self {
+        assert_eq!(values.len(), domain.size());
+        Self { domain, values }
+    }
+}
+
+/// Holds a foldable subset of circle polynomial evaluations.
+#[derive(Debug, Clone)]
+pub struct SparseCircleEvaluation {
+    subcircle_evals: Vec<CircleSubEvaluation>,
+}
+
+impl SparseCircleEvaluation {
+    /// # Panics
+    ///
+    /// Panics if the evaluation domain sizes don't equal the folding factor.
+    pub fn new(subcircle_evals: Vec<CircleSubEvaluation>) -> Self {
+        let folding_factor = 1 << CIRCLE_TO_LINE_FOLD_STEP;
+        assert!(subcircle_evals
+            .iter()
+            .all(|e| e.values.len() == folding_factor));
+        Self { subcircle_evals }
+    }
+
+    /// Folds each subcircle into a single value. The subcircles are at the `positions` of the
+    /// folded domain, and are all folded together.
+    fn fold(self, alpha: SecureField, positions: &Queries, folder: Folder<'_>) -> Vec<SecureField> {
+        let itwiddles = match folder.twiddles {
+            Some(twiddles) => {
+                let itwiddles = &twiddles.circle_itwiddles
+                    [(positions.log_domain_size + CIRCLE_TO_LINE_FOLD_STEP) as usize];
+                positions
+                    .iter()
+                    .map(|&position| itwiddles[position])
+                    .collect()
+            }
+            None => batch_inverse(
+                &self
+                    .subcircle_evals
+                    .iter()
+                    .flat_map(|e| circle_twiddles(e.domain))
+                    .collect_vec(),
+            ),
+        };
+        let values = self
+            .subcircle_evals
+            .into_iter()
+            .flat_map(|e| e.values)
+            .collect_vec();
+        (folder.fold_values)(&values, &itwiddles, alpha)
+    }
+
+    /// Computes `self = self * alpha + rhs`.
+    fn accumulate(&mut self, rhs: Self, alpha: SecureField) {
+        assert_eq!(self.subcircle_evals.len(), rhs.subcircle_evals.len());
+        for (lhs, rhs) in self.subcircle_evals.iter_mut().zip(rhs.subcircle_evals) {
+            assert_eq!(lhs.values.len(), rhs.values.len());
+            for (lhs, rhs) in lhs.values.iter_mut().zip(rhs.values) {
+                *lhs = *lhs * alpha + rhs;
+            }
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a mut SparseCircleEvaluation {
+    type Item = &'a mut CircleSubEvaluation;
+    type IntoIter = core::slice::IterMut<'a, CircleSubEvaluation>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.subcircle_evals.iter_mut()
+    }
+}
+
+/// Evaluations of a univariate polynomial on a small [LineDomain], in bit-reversed order.
+#[derive(Debug, Clone)]
+struct LineSubEvaluation {
+    domain: LineDomain,
+    values: Vec<SecureField>,
+}
+
+impl LineSubEvaluation {
+    fn new(domain: LineDomain, values: Vec<SecureField>) -> Self {
+        assert_eq!(values.len(), domain.size());
+        Self { domain, values }
+    }
+}
+
+/// Holds a small foldable subset of univariate SecureField polynomial evaluations.
+#[derive(Debug, Clone)]
+struct SparseLineEvaluation {
+    subline_evals: Vec<LineSubEvaluation>,
+    /// Number of times each subline is folded into a single value.
+    fold_step: u32,
+}
+
+impl SparseLineEvaluation {
+    /// # Panics
+    ///
+    /// Panics if the evaluation domain sizes don't equal the folding factor `2^fold_step`.
+    fn new(subline_evals: Vec<LineSubEvaluation>, fold_step: u32) -> Self {
+        let folding_factor = 1 << fold_step;
+        assert!(subline_evals
+            .iter()
+            .all(|e| e.values.len() == folding_factor));
+        Self {
+            subline_evals,
+            fold_step,
+        }
+    }
+
+    /// Folds each subline into a single value. See [`fold_line_values_n_times`]. The sublines
+    /// are at the `positions` of the folded domain.
+    ///
+    /// All sublines have the same size, so they are folded together one round at a time.
+    /// Without precomputed twiddles, the twiddles of all rounds are inverted in a single batch.
+    fn fold(
+        self,
+        mut alpha: SecureField,
+        positions: &Queries,
+        folder: Folder<'_>,
+    ) -> Vec<SecureField> {
+        let fold_step = self.fold_step;
+        let itwiddles = match folder.twiddles {
+            Some(twiddles) => {
+                let log_size = positions.log_domain_size + fold_step;
+                (0..fold_step)
+                    .flat_map(|round| {
+                        // The pairs of round `round` of each subline are consecutive in the
+                        // twiddles of the doubled domain.
+                        let itwiddles = &twiddles.line_itwiddles[(log_size - round) as usize];
+                        let log_n_pairs = fold_step - round - 1;
+                        positions.iter().flat_map(move |&position| {
+                            let start = position << log_n_pairs;
+                            &itwiddles[start..start + (1 << log_n_pairs)]
+                        })
+                    })
+                    .copied()
+                    .collect()
+            }
+            None => {
+                let mut domains = self.subline_evals.iter().map(|e| e.domain).collect_vec();
+                let mut twiddles = Vec::new();
+                for _ in 0..fold_step {
+                    twiddles.extend(domains.iter().flat_map(|&domain| line_twiddles(domain)));
+                    domains
+                        .iter_mut()
+                        .for_each(|domain| *domain = domain.double());
+                }
+                batch_inverse(&twiddles)
+            }
+        };
+
+        let mut values = self
+            .subline_evals
+            .into_iter()
+            .flat_map(|e| e.values)
+            .collect_vec();
+        let mut itwiddles = &itwiddles[..];
+        for _ in 0..fold_step {
+            let (round_itwiddles, rest) = itwiddles.split_at(values.len() / 2);
+            values = (folder.fold_values)(&values, round_itwiddles, alpha);
+            itwiddles = rest;
+            alpha = alpha * alpha;
+        }
+        values
+    }
+}
+
+/// Inverse twiddles of the FRI folds of all the domains up to a log size, in bit-reversed order.
+///
+/// Computed once and reused across verifications, so that [FriVerifier::decommit_with_twiddles]
+/// only indexes into them.
+#[derive(Debug, Clone)]
+pub struct FriVerifierTwiddles {
+    max_log_size: u32,
+    /// Inverse twiddles of folding the line domain of each log size into its double. The line
+    /// domain of log size `k` of every layer is `Coset::half_odds(k)`.
+    line_itwiddles: Vec<Vec<BaseField>>,
+    /// Inverse twiddles of folding the canonic circle domain of each log size into a line.
+    circle_itwiddles: Vec<Vec<BaseField>>,
+}
+
+impl FriVerifierTwiddles {
+    /// Precomputes the twiddles to verify columns of log size up to `max_log_size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_log_size` is larger than [`MAX_CIRCLE_DOMAIN_LOG_SIZE`].
+    pub fn new(max_log_size: u32) -> Self {
+        assert!(max_log_size <= MAX_CIRCLE_DOMAIN_LOG_SIZE);
+        let line_twiddles = (0..max_log_size)
+            .map(|log_size| match log_size {
+                0 => Vec::new(),
+                _ => line_twiddles(LineDomain::new(Coset::half_odds(log_size))),
+            })
+            .collect_vec();
+        let circle_twiddles = (0..=max_log_size)
+            .map(|log_size| match log_size {
+                0 => Vec::new(),
+                _ => circle_twiddles(CanonicCoset::new(log_size).circle_domain()),
+            })
+            .collect_vec();
+
+        // Invert all twiddles in a single batch.
+        let all_twiddles = line_twiddles.iter().chain(&circle_twiddles).flatten();
+        let mut itwiddles = batch_inverse(&all_twiddles.copied().collect_vec()).into_iter();
+        let mut take_like = |twiddles: &Vec<Vec<BaseField>>| {
+            twiddles
+                .iter()
+                .map(|twiddles| itwiddles.by_ref().take(twiddles.len()).collect())
+                .collect()
+        };
+        let line_itwiddles = take_like(&line_twiddles);
+        let circle_itwiddles = take_like(&circle_twiddles);
+
+        Self {
+            max_log_size,
+            line_itwiddles,
+            circle_itwiddles,
+        }
+    }
+}
+
+/// Folding of the queried evaluations in [FriVerifier::decommit_with_twiddles]. Implemented by
+/// the prover's backends.
+pub trait FriVerifierOps {
+    /// Folds consecutive pairs of `values` into `f0 + alpha * f1`, where `(f0, f1)` is the inverse
+    /// butterfly of the pair with the inverse twiddle `itwiddles[i]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there aren't twice as many values as inverse twiddles.
+    fn fold_values(
+        values: &[SecureField],
+        itwiddles: &[BaseField],
+        alpha: SecureField,
+    ) -> Vec<SecureField> {
+        fold_values(values, itwiddles, alpha)
+    }
+}
+
+/// How a [FriVerifier] folds queried evaluations: with the twiddles of the queried subdomains,
+/// or with precomputed twiddles and a [FriVerifierOps] implementation.
+#[derive(Clone, Copy)]
+struct Folder<'a> {
+    twiddles: Option<&'a FriVerifierTwiddles>,
+    fold_values: fn(&[SecureField], &[BaseField], SecureField) -> Vec<SecureField>,
+}
+
+/// Returns the evaluations of the polynomial projected out of a column by the FRI decomposition.
+///
+/// The column is evaluated on the canonic circle domain of size `2^log_domain_size`, and its
+/// degree bound is `2^(log_domain_size - log_blowup_factor)`. The projected polynomial is the
+/// vanishing polynomial of the canonic coset of that size, `v(p) = pi^(log_degree_bound - 1)(p.x)`
+/// where `pi(x) = 2x^2 - 1`. In bit-reversed order, `v` is constant on consecutive blocks of
+/// `2^log_degree_bound` points, so only the value of each of the `2^log_blowup_factor` blocks is
+/// returned.
+///
+/// The sum of `v^2` over the domain is half the domain size.
+///
+/// # Panics
+///
+/// Panics if `log_blowup_factor` is zero or not smaller than `log_domain_size`.
+pub fn decomposition_values(log_domain_size: u32, log_blowup_factor: u32) -> Vec<BaseField> {
+    assert!(0 < log_blowup_factor && log_blowup_factor < log_domain_size);
+    let log_degree_bound = log_domain_size - log_blowup_factor;
+    // The x-coordinates of the domain, folded down to the domain of `v`'s blocks.
+    let domain = LineDomain::new(
+        Coset::half_odds(log_domain_size - CIRCLE_TO_LINE_FOLD_STEP)
+            .repeated_double(log_degree_bound - 1),
+    );
+    (0..domain.size())
+        .map(|i| domain.at(bit_reverse_index(i, domain.log_size())))
+        .collect()
+}
+
+/// Folds evaluations of a degree `d` polynomial on `domain` into evaluations of a degree `d/2`
+/// polynomial on the doubled domain. Values are in bit-reversed order.
+///
+/// Let `f` be the polynomial, `alpha` be a random field element and `pi(x) = 2x^2 - 1` be the
+/// circle's x-coordinate doubling map. Returns `f' = f0 + alpha * f1` evaluated on `pi(domain)`
+/// such that `2f(x) = f0(pi(x)) + x * f1(pi(x))`.
+///
+/// # Panics
+///
+/// Panics if there are less than two evaluations or the evaluations don't match the domain.
+pub fn fold_line_values(
+    values: &[SecureField],
+    domain: LineDomain,
+    alpha: SecureField,
+) -> Vec<SecureField> {
+    let n = values.len();
+    assert!(n >= 2, "Evaluation too small");
+    assert_eq!(n, domain.size());
+
+    fold_values(values, &batch_inverse(&line_twiddles(domain)), alpha)
+}
+
+/// Folds evaluations of a degree `d` polynomial on `domain` into evaluations of a degree
+/// `d/2^n_folds` polynomial on the domain doubled `n_folds` times. Values are in bit-reversed
+/// order.
+///
+/// Each fold is a [`fold_line_values`], with `alpha` squared between folds.
+///
+/// # Panics
+///
+/// Panics if there are less than `2^n_folds` evaluations or the evaluations don't match the
+/// domain.
+pub fn fold_line_values_n_times(
+    mut values: Vec<SecureField>,
+    mut domain: LineDomain,
+    mut alpha: SecureField,
+    n_folds: u32,
+) -> Vec<SecureField> {
+    for _ in 0..n_folds {
+        values = fold_line_values(&values, domain, alpha);
+        domain = domain.double();
+        alpha = alpha * alpha;
+    }
+    values
+}
+
+/// Folds and accumulates evaluations of a degree `d` circle polynomial on `src_domain` into
+/// evaluations of a degree `d/2` univariate polynomial. Values are in bit-reversed order.
+///
+/// Computes evaluations of `f' = f0 + alpha * f1` on the x-coordinates of `src_domain` such that
+/// `2f(p) = f0(px) + py * f1(px)`, and accumulates them into `dst` by the formula
+/// `dst = dst * alpha^2 + f'`.
+///
+/// # Panics
+///
+/// Panics if `src` is not double the length of `dst`.
+pub fn fold_circle_into_line_values(
+    dst: &mut [SecureField],
+    src: &[SecureField],
+    src_domain: CircleDomain,
+    alpha: SecureField,
+) {
+    assert_eq!(src.len() >> CIRCLE_TO_LINE_FOLD_STEP, dst.len());
+
+    let alpha_sq = alpha * alpha;
+    let itwiddles = batch_inverse(&circle_twiddles(src_domain));
+
+    for (dst, f_prime) in zip(dst, fold_values(src, &itwiddles, alpha)) {
+        *dst = *dst * alpha_sq + f_prime;
+    }
+}
+
+/// Returns the twiddles for folding evaluations on `domain` into its double, in bit-reversed
+/// order.
+///
+/// Pair `i` of a bit-reversed evaluation is at the points `p` and `-p` where `p` is the `i`th
+/// point of the first half of the domain in bit-reversed order. The twiddle is `p.x`.
+fn line_twiddles(domain: LineDomain) -> Vec<BaseField> {
+    let mut twiddles = domain.iter().take(domain.size() / 2).collect_vec();
+    bit_reverse(&mut twiddles);
+    twiddles
+}
+
+/// Returns the twiddles for folding evaluations on `domain` into a line, in bit-reversed order.
+///
+/// Pair `i` of a bit-reversed evaluation is at the conjugate points `p` and `-p` where `p` is the
+/// `i`th point of the domain's half coset in bit-reversed order. The twiddle is `p.y`.
+fn circle_twiddles(domain: CircleDomain) -> Vec<BaseField> {
+    let mut twiddles = domain.half_coset.iter().map(|p| p.y).collect_vec();
+    bit_reverse(&mut twiddles);
+    twiddles
+}
+
+fn batch_inverse(values: &[BaseField]) -> Vec<BaseField> {
+    if values.is_empty() {
+        return Vec::new();
+    }
+    let mut inverses = vec![BaseField::zero(); values.len()];
+    BaseField::batch_inverse(values, &mut inverses);
+    inverses
+}
+
+/// Folds consecutive pairs of `values` into `f0 + alpha * f1`, where `(f0, f1)` is the inverse
+/// butterfly of the pair with the inverse twiddle `itwiddles[i]`.
+fn fold_values(
+    values: &[SecureField],
+    itwiddles: &[BaseField],
+    alpha: SecureField,
+) -> Vec<SecureField> {
+    assert_eq!(values.len(), itwiddles.len() * 2);
+    zip(values.chunks_exact(2), itwiddles)
+        .map(|(f, &itwiddle)| {
+            let (mut f0, mut f1) = (f[0], f[1]);
+            ibutterfly(&mut f0, &mut f1, itwiddle);
+            f0 + alpha * f1
+        })
+        .collect()
+}
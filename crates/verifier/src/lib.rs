@@ -0,0 +1,147 @@
+#![no_std]
+
+extern crate alloc;
+#[cfg(any(test, feature = "std"))]
+extern crate std;
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut, Index};
+
+use fields::m31::BaseField;
+
+use self::fields::qm31::SecureField;
+use self::prover::VerificationError;
+
+pub mod air;
+pub mod channel;
+pub mod circle;
+pub mod constraints;
+pub mod fft;
+pub mod fields;
+pub mod fri;
+pub mod low_degree_test;
+pub mod math;
+pub mod pcs;
+pub mod poly;
+pub mod proof_of_work;
+pub mod prover;
+pub mod queries;
+pub mod serialization;
+#[cfg(test)]
+pub mod test_utils;
+pub mod utils;
+pub mod vcs;
+
+/// A vector in which each element relates (by index) to a column in the trace.
+pub type ColumnVec<T> = Vec<T>;
+
+/// A vector of [ColumnVec]s. Each [ColumnVec] relates (by index) to a component in the air.
+#[derive(Debug, Clone)]
+pub struct ComponentVec<T>(pub Vec<ColumnVec<T>>);
+
+impl<T> ComponentVec<T> {
+    pub fn flatten(self) -> ColumnVec<T> {
+        self.0.into_iter().flatten().collect()
+    }
+}
+
+impl<T> ComponentVec<ColumnVec<T>> {
+    pub fn flatten_cols(self) -> Vec<T> {
+        self.0.into_iter().flatten().flatten().collect()
+    }
+}
+
+impl<T> Default for ComponentVec<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T> Deref for ComponentVec<T> {
+    type Target = Vec<ColumnVec<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for ComponentVec<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct InteractionElements(BTreeMap<String, SecureField>);
+
+impl InteractionElements {
+    pub fn new(elements: BTreeMap<String, SecureField>) -> Self {
+        Self(elements)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn extend(&mut self, other: Self) {
+        self.0.extend(other.0);
+    }
+
+    /// Returns the interaction element `name`, or an error if it was not drawn.
+    pub fn get(&self, name: &str) -> Result<SecureField, VerificationError> {
+        self.0
+            .get(name)
+            .copied()
+            .ok_or_else(|| VerificationError::MissingInteractionElement(name.to_string()))
+    }
+}
+
+impl Index<&str> for InteractionElements {
+    type Output = SecureField;
+
+    /// # Panics
+    ///
+    /// Panics if the element was not drawn. Use [InteractionElements::get] to handle it instead.
+    fn index(&self, index: &str) -> &Self::Output {
+        self.0
+            .get(index)
+            .unwrap_or_else(|| panic!("Missing interaction element {index}."))
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct LookupValues(pub BTreeMap<String, BaseField>);
+
+impl LookupValues {
+    pub fn new(values: BTreeMap<String, BaseField>) -> Self {
+        Self(values)
+    }
+
+    pub fn extend(&mut self, other: Self) {
+        self.0.extend(other.0);
+    }
+
+    /// Returns the lookup value `name`, or an error if the proof does not provide it.
+    pub fn get(&self, name: &str) -> Result<BaseField, VerificationError> {
+        self.0
+            .get(name)
+            .copied()
+            .ok_or_else(|| VerificationError::MissingLookupValue(name.to_string()))
+    }
+}
+
+impl Index<&str> for LookupValues {
+    type Output = BaseField;
+
+    /// # Panics
+    ///
+    /// Panics if the value is missing. Verifiers should check it with [LookupValues::get] first,
+    /// e.g. in [air::Air::verify_lookups].
+    fn index(&self, index: &str) -> &Self::Output {
+        self.0
+            .get(index)
+            .unwrap_or_else(|| panic!("Missing lookup value {index}."))
+    }
+}
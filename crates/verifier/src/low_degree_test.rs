@@ -0,0 +1,219 @@
+//! A standalone FRI low degree test.
+//!
+//! Proves that a set of circle evaluations are within their degree bounds, independently of a
+//! STARK or the polynomial commitment scheme. The prover commits to the evaluations in a single
+//! Merkle tree, runs FRI on them directly and opens the evaluations at the FRI query positions.
+//! The proof is self-contained: [verify_low_degree] only needs the config and the degree bounds.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::zip;
+
+use itertools::Itertools;
+
+use super::channel::Channel;
+use super::fields::qm31::{SecureField, SECURE_EXTENSION_DEGREE};
+use super::fri::{
+    CirclePolyDegreeBound, CircleSubEvaluation, FriConfig, FriProof, FriVerificationError,
+    FriVerifier, FriVerifierOps, FriVerifierTwiddles, SparseCircleEvaluation,
+};
+use super::poly::circle::CanonicCoset;
+use super::vcs::ops::MerkleHasher;
+use super::vcs::verifier::{MerkleDecommitment, MerkleVerificationError, MerkleVerifier};
+use super::ColumnVec;
+
+/// A proof that committed circle evaluations are within their degree bounds.
+#[derive(Debug)]
+pub struct LowDegreeProof<H: MerkleHasher> {
+    /// Root of the Merkle tree committing to the evaluations, one column per coordinate.
+    pub commitment: H::Hash,
+    /// The values of each evaluation at its FRI opening positions.
+    pub queried_values: ColumnVec<Vec<SecureField>>,
+    pub decommitment: MerkleDecommitment<H>,
+    pub fri_proof: FriProof<H>,
+}
+
+/// Verifies a [LowDegreeProof].
+///
+/// `column_log_degree_bounds` are the log degree bounds of the committed evaluations, in the order
+/// they were proven. Evaluations are over canonic circle domains of size
+/// `2^(log_degree_bound + config.log_blowup_factor)`.
+///
+/// # Panics
+///
+/// Panics if `column_log_degree_bounds` is empty, not sorted in descending order, or contains a
+/// bound less than or equal to the last layer's degree bound.
+pub fn verify_low_degree<H: MerkleHasher>(
+    channel: &mut impl Channel<Digest = H::Hash>,
+    config: FriConfig,
+    column_log_degree_bounds: &[u32],
+    proof: LowDegreeProof<H>,
+) -> Result<(), LowDegreeTestError> {
+    verify_low_degree_with(
+        channel,
+        config,
+        column_log_degree_bounds,
+        proof,
+        FriVerifier::decommit,
+    )
+}
+
+/// Like [verify_low_degree], but folds the FRI queries with `B` and the precomputed `twiddles`.
+/// See [FriVerifier::decommit_with_twiddles].
+///
+/// # Panics
+///
+/// Panics in the same cases as [verify_low_degree], or if `twiddles` don't cover the largest
+/// evaluation.
+pub fn verify_low_degree_with_twiddles<B: FriVerifierOps, H: MerkleHasher>(
+    channel: &mut impl Channel<Digest = H::Hash>,
+    config: FriConfig,
+    column_log_degree_bounds: &[u32],
+    proof: LowDegreeProof<H>,
+    twiddles: &FriVerifierTwiddles,
+) -> Result<(), LowDegreeTestError> {
+    verify_low_degree_with(
+        channel,
+        config,
+        column_log_degree_bounds,
+        proof,
+        |fri_verifier, decommitted_values| {
+            fri_verifier.decommit_with_twiddles::<B>(decommitted_values, twiddles)
+        },
+    )
+}
+
+fn verify_low_degree_with<H: MerkleHasher>(
+    channel: &mut impl Channel<Digest = H::Hash>,
+    config: FriConfig,
+    column_log_degree_bounds: &[u32],
+    proof: LowDegreeProof<H>,
+    decommit: impl FnOnce(
+        FriVerifier<H>,
+        Vec<SparseCircleEvaluation>,
+    ) -> Result<(), FriVerificationError>,
+) -> Result<(), LowDegreeTestError> {
+    let LowDegreeProof {
+        commitment,
+        queried_values,
+        decommitment,
+        fri_proof,
+    } = proof;
+    if queried_values.len() != column_log_degree_bounds.len() {
+        return Err(LowDegreeTestError::NumQueriedColumns {
+            expected: column_log_degree_bounds.len(),
+            actual: queried_values.len(),
+        });
+    }
+    channel.mix_digest(commitment);
+
+    let column_bounds = column_log_degree_bounds
+        .iter()
+        .map(|&log_degree_bound| CirclePolyDegreeBound::new(log_degree_bound))
+        .collect();
+    let mut fri_verifier = FriVerifier::commit(channel, config, fri_proof, column_bounds)?;
+    let query_domain_per_log_size = fri_verifier.column_query_positions(channel);
+
+    let column_log_sizes = column_log_degree_bounds
+        .iter()
+        .map(|log_degree_bound| log_degree_bound + config.log_blowup_factor)
+        .collect_vec();
+    for (column, (log_size, values)) in zip(&column_log_sizes, &queried_values).enumerate() {
+        let expected = query_domain_per_log_size[log_size].flatten().len();
+        if values.len() != expected {
+            return Err(LowDegreeTestError::NumQueriedValues {
+                column,
+                expected,
+                actual: values.len(),
+            });
+        }
+    }
+
+    // Each evaluation is committed as its coordinate columns.
+    let merkle_verifier = MerkleVerifier::<H>::new(
+        commitment,
+        column_log_sizes
+            .iter()
+            .flat_map(|&log_size| [log_size; SECURE_EXTENSION_DEGREE])
+            .collect(),
+    );
+    let queries_per_log_size = query_domain_per_log_size
+        .iter()
+        .map(|(&log_size, domain)| (log_size, domain.flatten()))
+        .collect();
+    let coordinate_values = queried_values
+        .iter()
+        .flat_map(|values| {
+            let coordinates = values.iter().map(|v| v.to_m31_array()).collect_vec();
+            (0..SECURE_EXTENSION_DEGREE)
+                .map(move |i| coordinates.iter().map(|c| c[i]).collect_vec())
+        })
+        .collect();
+    merkle_verifier
+        .verify(queries_per_log_size, coordinate_values, decommitment)
+        .map_err(LowDegreeTestError::Merkle)?;
+
+    let decommitted_values = zip(column_log_sizes, queried_values)
+        .map(|(log_size, values)| {
+            let commitment_domain = CanonicCoset::new(log_size).circle_domain();
+            let mut values = values.into_iter();
+            let subcircle_evals = query_domain_per_log_size[&log_size]
+                .iter()
+                .map(|subdomain| {
+                    let domain = subdomain.to_circle_domain(&commitment_domain);
+                    CircleSubEvaluation::new(domain, values.by_ref().take(domain.size()).collect())
+                })
+                .collect();
+            SparseCircleEvaluation::new(subcircle_evals)
+        })
+        .collect();
+    decommit(fri_verifier, decommitted_values)?;
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum LowDegreeTestError {
+    /// The proof does not have queried values for every column.
+    NumQueriedColumns {
+        expected: usize,
+        actual: usize,
+    },
+    /// A column does not have a value for every opening position.
+    NumQueriedValues {
+        column: usize,
+        expected: usize,
+        actual: usize,
+    },
+    /// The queried values do not resolve to the commitment.
+    Merkle(MerkleVerificationError),
+    Fri(FriVerificationError),
+}
+
+impl fmt::Display for LowDegreeTestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NumQueriedColumns { expected, actual } => {
+                write!(f, "expected {expected} queried columns, got {actual}")
+            }
+            Self::NumQueriedValues {
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "expected {expected} queried values in column {column}, got {actual}"
+            ),
+            Self::Merkle(error) => write!(f, "queried values do not match the commitment: {error}"),
+            Self::Fri(error) => write!(f, "FRI verification failed: {error}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LowDegreeTestError {}
+
+impl From<FriVerificationError> for LowDegreeTestError {
+    fn from(error: FriVerificationError) -> Self {
+        Self::Fri(error)
+    }
+}
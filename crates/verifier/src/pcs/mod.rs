@@ -0,0 +1,112 @@
+//! Implements a FRI polynomial commitment scheme.
+//! This is a protocol where the prover can commit on a set of polynomials and then prove their
+//! opening on a set of points.
+//! Note: This implementation is not really a polynomial commitment scheme, because we are not in
+//! the unique decoding regime. This is enough for a STARK proof though, where we only want to imply
+//! the existence of such polynomials, and are ok with having a small decoding list.
+//! Note: Opened points cannot come from the commitment domain.
+
+pub mod quotients;
+pub mod security;
+mod utils;
+mod verifier;
+
+use itertools::Itertools;
+
+pub use self::utils::TreeVec;
+pub use self::verifier::{CommitmentSchemeProof, CommitmentSchemeVerifier};
+use super::channel::Channel;
+use super::fields::m31::BaseField;
+use super::fields::qm31::SecureField;
+use super::fri::FriConfig;
+use super::vcs::verifier::MerkleConfig;
+
+/// Security parameters of the commitment scheme.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PcsConfig {
+    /// Number of proof of work bits required before drawing the FRI queries.
+    pub pow_bits: u32,
+    /// Number of proof of work bits required before drawing the elements of each interaction
+    /// phase.
+    pub interaction_pow_bits: u32,
+    /// Number of proof of work bits required before drawing the OODS point.
+    pub oods_pow_bits: u32,
+    pub fri_config: FriConfig,
+    /// The shape of the Merkle tree of each commitment, in the order they are committed. Trees
+    /// without a config are binary trees with a single root, see [MerkleConfig::default].
+    pub merkle_configs: TreeVec<MerkleConfig>,
+}
+
+impl PcsConfig {
+    /// Returns the shape of the Merkle tree of the `tree`-th commitment.
+    pub fn merkle_config(&self, tree: usize) -> MerkleConfig {
+        self.merkle_configs.get(tree).copied().unwrap_or_default()
+    }
+
+    /// Sets the shape of the Merkle tree of the `tree`-th commitment, e.g. to commit to a cap
+    /// instead of a root.
+    pub fn with_merkle_config(mut self, tree: usize, merkle_config: MerkleConfig) -> Self {
+        if self.merkle_configs.len() <= tree {
+            self.merkle_configs
+                .resize(tree + 1, MerkleConfig::default());
+        }
+        self.merkle_configs[tree] = merkle_config;
+        self
+    }
+
+    /// Conjectured security bits of the query phase, including proof of work.
+    pub const fn security_bits(&self) -> u32 {
+        self.pow_bits + self.fri_config.security_bits()
+    }
+
+    /// Mixes the config into the channel, binding the proof to the parameters it was made with.
+    /// Must be called on both sides before the first commitment.
+    pub fn mix_into(&self, channel: &mut impl Channel) {
+        let FriConfig {
+            log_blowup_factor,
+            log_last_layer_degree_bound,
+            n_queries,
+            fold_step,
+        } = self.fri_config;
+        let felts = [
+            SecureField::from_m31_array([
+                BaseField::from(self.pow_bits),
+                BaseField::from(log_blowup_factor),
+                BaseField::from(log_last_layer_degree_bound),
+                BaseField::from(n_queries),
+            ]),
+            SecureField::from_m31_array([
+                BaseField::from(fold_step),
+                BaseField::from(self.interaction_pow_bits),
+                BaseField::from(self.oods_pow_bits),
+                BaseField::from(self.merkle_configs.len()),
+            ]),
+        ];
+        let merkle_config_felts = self.merkle_configs.iter().map(|merkle_config| {
+            let MerkleConfig {
+                log_arity,
+                log_leaf_rows,
+                log_cap_size,
+            } = *merkle_config;
+            SecureField::from_m31_array([
+                BaseField::from(log_arity),
+                BaseField::from(log_leaf_rows),
+                BaseField::from(log_cap_size),
+                BaseField::from(0),
+            ])
+        });
+        channel.mix_felts(&felts.into_iter().chain(merkle_config_felts).collect_vec());
+    }
+}
+
+impl Default for PcsConfig {
+    fn default() -> Self {
+        Self {
+            pow_bits: 12,
+            interaction_pow_bits: 0,
+            oods_pow_bits: 0,
+            fri_config: FriConfig::new(0, 1, 3),
+            merkle_configs: TreeVec::default(),
+        }
+    }
+}
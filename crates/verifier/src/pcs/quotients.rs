@@ -0,0 +1,271 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+use itertools::{izip, multiunzip, zip_eq, Itertools};
+use num_traits::{One, Zero};
+
+use crate::circle::CirclePoint;
+use crate::constraints::{complex_conjugate_line_coeffs, pair_vanishing};
+use crate::fields::m31::BaseField;
+use crate::fields::qm31::SecureField;
+use crate::fields::{ComplexConjugate, FieldExpOps};
+use crate::fri::{CircleSubEvaluation, SparseCircleEvaluation};
+use crate::poly::circle::{CanonicCoset, CircleDomain};
+use crate::prover::{ProofShapeError, VerificationError};
+use crate::queries::SparseSubCircleDomain;
+use crate::utils::{bit_reverse, bit_reverse_index};
+
+/// A batch of column samplings at a point.
+pub struct ColumnSampleBatch {
+    /// The point at which the columns are sampled.
+    pub point: CirclePoint<SecureField>,
+    /// The sampled column indices and their values at the point.
+    pub columns_and_values: Vec<(usize, SecureField)>,
+}
+
+impl ColumnSampleBatch {
+    /// Groups column samples by sampled point.
+    /// # Arguments
+    /// samples: For each column, a vector of samples.
+    pub fn new_vec(samples: &[&Vec<PointSample>]) -> Vec<Self> {
+        // Group samples by point, and create a ColumnSampleBatch for each point.
+        // This should keep a stable ordering.
+        let mut grouped_samples = BTreeMap::new();
+        for (column_index, samples) in samples.iter().enumerate() {
+            for sample in samples.iter() {
+                grouped_samples
+                    .entry(sample.point)
+                    .or_insert_with(Vec::new)
+                    .push((column_index, sample.value));
+            }
+        }
+        grouped_samples
+            .into_iter()
+            .map(|(point, columns_and_values)| ColumnSampleBatch {
+                point,
+                columns_and_values,
+            })
+            .collect()
+    }
+}
+
+pub struct PointSample {
+    pub point: CirclePoint<SecureField>,
+    pub value: SecureField,
+}
+
+pub fn fri_answers(
+    column_log_sizes: Vec<u32>,
+    samples: &[Vec<PointSample>],
+    random_coeff: SecureField,
+    query_domain_per_log_size: BTreeMap<u32, SparseSubCircleDomain>,
+    queried_values_per_column: &[Vec<BaseField>],
+) -> Result<Vec<SparseCircleEvaluation>, VerificationError> {
+    izip!(column_log_sizes, samples, queried_values_per_column)
+        .sorted_by_key(|(log_size, ..)| Reverse(*log_size))
+        .group_by(|(log_size, ..)| *log_size)
+        .into_iter()
+        .map(|(log_size, tuples)| {
+            let (_, samples, queried_valued_per_column): (Vec<_>, Vec<_>, Vec<_>) =
+                multiunzip(tuples);
+            fri_answers_for_log_size(
+                log_size,
+                &samples,
+                random_coeff,
+                &query_domain_per_log_size[&log_size],
+                &queried_valued_per_column,
+            )
+        })
+        .collect()
+}
+
+pub fn fri_answers_for_log_size(
+    log_size: u32,
+    samples: &[&Vec<PointSample>],
+    random_coeff: SecureField,
+    query_domain: &SparseSubCircleDomain,
+    queried_values_per_column: &[&Vec<BaseField>],
+) -> Result<SparseCircleEvaluation, VerificationError> {
+    let commitment_domain = CanonicCoset::new(log_size).circle_domain();
+    let sample_batches = ColumnSampleBatch::new_vec(samples);
+    let n_queries = query_domain.flatten().len();
+    for queried_values in queried_values_per_column {
+        if queried_values.len() != n_queries {
+            return Err(ProofShapeError::NumQueriedValues {
+                log_size,
+                expected: n_queries,
+                actual: queried_values.len(),
+            }
+            .into());
+        }
+    }
+    let mut queried_values_per_column = queried_values_per_column
+        .iter()
+        .map(|q| q.iter())
+        .collect_vec();
+
+    let mut evals = Vec::new();
+    for subdomain in query_domain.iter() {
+        let domain = subdomain.to_circle_domain(&commitment_domain);
+        let quotient_constants = quotient_constants(&sample_batches, random_coeff, domain);
+        let mut column_evals = Vec::new();
+        for queried_values in queried_values_per_column.iter_mut() {
+            column_evals.push(queried_values.take(domain.size()).copied().collect_vec());
+        }
+        let columns = column_evals.iter().map(|eval| &eval[..]).collect_vec();
+        // TODO(spapini): bit reverse iterator.
+        let mut values = Vec::new();
+        for row in 0..domain.size() {
+            let domain_point = domain.at(bit_reverse_index(row, log_size));
+            let value = accumulate_row_quotients(
+                &sample_batches,
+                &columns,
+                &quotient_constants,
+                row,
+                domain_point,
+            );
+            values.push(value);
+        }
+        evals.push(CircleSubEvaluation::new(domain, values));
+    }
+
+    // The lengths were checked above, so all the queried values must have been consumed.
+    debug_assert!(queried_values_per_column
+        .iter()
+        .all(|x| x.as_slice().is_empty()));
+    Ok(SparseCircleEvaluation::new(evals))
+}
+
+pub fn accumulate_row_quotients(
+    sample_batches: &[ColumnSampleBatch],
+    columns: &[&[BaseField]],
+    quotient_constants: &QuotientConstants,
+    row: usize,
+    domain_point: CirclePoint<BaseField>,
+) -> SecureField {
+    let mut row_accumulator = SecureField::zero();
+    for (sample_batch, line_coeffs, batch_coeff, denominator_inverses) in izip!(
+        sample_batches,
+        &quotient_constants.line_coeffs,
+        &quotient_constants.batch_random_coeffs,
+        &quotient_constants.denominator_inverses
+    ) {
+        let mut numerator = SecureField::zero();
+        for ((column_index, _), (a, b, c)) in zip_eq(&sample_batch.columns_and_values, line_coeffs)
+        {
+            let column = &columns[*column_index];
+            let value = column[row] * *c;
+            // The numerator is a line equation passing through
+            //   (sample_point.y, sample_value), (conj(sample_point), conj(sample_value))
+            // evaluated at (domain_point.y, value).
+            // When substituting a polynomial in this line equation, we get a polynomial with a root
+            // at sample_point and conj(sample_point) if the original polynomial had the values
+            // sample_value and conj(sample_value) at these points.
+            let linear_term = *a * domain_point.y + *b;
+            numerator += value - linear_term;
+        }
+
+        row_accumulator = row_accumulator * *batch_coeff + numerator * denominator_inverses[row];
+    }
+    row_accumulator
+}
+
+/// Precompute the complex conjugate line coefficients for each column in each sample batch.
+/// Specifically, for the i-th (in a sample batch) column's numerator term
+/// `alpha^i * (c * F(p) - (a * p.y + b))`, we precompute and return the constants:
+/// (`alpha^i * a`, `alpha^i * b`, `alpha^i * c`).
+pub fn column_line_coeffs(
+    sample_batches: &[ColumnSampleBatch],
+    random_coeff: SecureField,
+) -> Vec<Vec<(SecureField, SecureField, SecureField)>> {
+    sample_batches
+        .iter()
+        .map(|sample_batch| {
+            let mut alpha = SecureField::one();
+            sample_batch
+                .columns_and_values
+                .iter()
+                .map(|(_, sampled_value)| {
+                    alpha *= random_coeff;
+                    let sample = PointSample {
+                        point: sample_batch.point,
+                        value: *sampled_value,
+                    };
+                    complex_conjugate_line_coeffs(&sample, alpha)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Precompute the random coefficients used to linearly combine the batched quotients.
+/// Specifically, for each sample batch we compute random_coeff^(number of columns in the batch),
+/// which is used to linearly combine the batch with the next one.
+pub fn batch_random_coeffs(
+    sample_batches: &[ColumnSampleBatch],
+    random_coeff: SecureField,
+) -> Vec<SecureField> {
+    sample_batches
+        .iter()
+        .map(|sb| random_coeff.pow(sb.columns_and_values.len() as u128))
+        .collect()
+}
+
+fn denominator_inverses(
+    sample_batches: &[ColumnSampleBatch],
+    domain: CircleDomain,
+) -> Vec<Vec<SecureField>> {
+    let mut flat_denominators = Vec::with_capacity(sample_batches.len() * domain.size());
+    for sample_batch in sample_batches {
+        for row in 0..domain.size() {
+            let domain_point = domain.at(row);
+            let denominator = pair_vanishing(
+                sample_batch.point,
+                sample_batch.point.complex_conjugate(),
+                domain_point.into_ef(),
+            );
+            flat_denominators.push(denominator);
+        }
+    }
+
+    let mut flat_denominator_inverses = vec![SecureField::zero(); flat_denominators.len()];
+    SecureField::batch_inverse(&flat_denominators, &mut flat_denominator_inverses);
+
+    flat_denominator_inverses
+        .chunks_mut(domain.size())
+        .map(|denominator_inverses| {
+            bit_reverse(denominator_inverses);
+            denominator_inverses.to_vec()
+        })
+        .collect()
+}
+
+pub fn quotient_constants(
+    sample_batches: &[ColumnSampleBatch],
+    random_coeff: SecureField,
+    domain: CircleDomain,
+) -> QuotientConstants {
+    let line_coeffs = column_line_coeffs(sample_batches, random_coeff);
+    let batch_random_coeffs = batch_random_coeffs(sample_batches, random_coeff);
+    let denominator_inverses = denominator_inverses(sample_batches, domain);
+    QuotientConstants {
+        line_coeffs,
+        batch_random_coeffs,
+        denominator_inverses,
+    }
+}
+
+/// Holds the precomputed constant values used in each quotient evaluation.
+/// `C` is the column type holding the denominator inverses of a sample batch.
+pub struct QuotientConstants<C = Vec<SecureField>> {
+    /// The line coefficients for each quotient numerator term. For more details see
+    /// [self::column_line_coeffs].
+    pub line_coeffs: Vec<Vec<(SecureField, SecureField, SecureField)>>,
+    /// The random coefficients used to linearly combine the batched quotients For more details see
+    /// [self::batch_random_coeffs].
+    pub batch_random_coeffs: Vec<SecureField>,
+    /// The inverses of the denominators of the quotients.
+    pub denominator_inverses: Vec<C>,
+}
@@ -0,0 +1,317 @@
+//! Soundness estimates for a STARK proven with a given [PcsConfig].
+//!
+//! Two estimates are provided:
+//! * Conjectured: the ethSTARK-style estimate, assuming FRI behaves well up to list-decoding
+//!   capacity. Each query then contributes `log_blowup_factor` bits.
+//! * Proven: the list-decoding regime up to the Johnson bound, following BCIKS20 for the FRI commit
+//!   phase and ethSTARK (Theorem 2) for DEEP-ALI.
+//!
+//! Both estimates are the minimum over the query phase and the terms bounded by the size of the
+//! secure field (QM31), which are driven by the AIR's composition degree and constraint count.
+//! Proof of work before drawing the interaction elements or the OODS point adds to the respective
+//! terms.
+
+use core::fmt;
+
+use super::PcsConfig;
+use crate::air::{Air, AirExt};
+use crate::fields::qm31::P4;
+
+/// Multiplicity parameter `m` of the Johnson bound analysis (BCIKS20). Must be at least 3.
+const JOHNSON_MULTIPLICITY: u128 = 3;
+
+/// Parameters of an AIR that affect soundness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AirSecurityParams {
+    /// Log of the composition polynomial degree bound.
+    pub composition_log_degree_bound: u32,
+    /// Log of the largest trace column degree bound.
+    pub max_column_log_degree_bound: u32,
+    /// Total number of constraints, combined with a single random coefficient.
+    pub n_constraints: usize,
+    /// Number of interaction phases, each drawing interaction elements for lookups.
+    pub n_interaction_phases: usize,
+}
+
+impl AirSecurityParams {
+    pub fn from_air(air: &impl Air) -> Self {
+        let max_column_log_degree_bound = air
+            .column_log_sizes()
+            .iter()
+            .flatten()
+            .copied()
+            .max()
+            .unwrap_or_default();
+        // Besides the preprocessed and the base trace, each tree is an interaction phase.
+        let n_interaction_phases = air.column_log_sizes().len().saturating_sub(2);
+        Self {
+            composition_log_degree_bound: air.composition_log_degree_bound(),
+            max_column_log_degree_bound,
+            n_constraints: air.components().iter().map(|c| c.n_constraints()).sum(),
+            n_interaction_phases,
+        }
+    }
+}
+
+/// Security bits of a proof, as estimated by [PcsConfig::security_report].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SecurityReport {
+    /// Query phase bits, including proof of work.
+    pub query_bits: f64,
+    /// Bits bounded by the field size: constraint combination, DEEP-ALI, interaction and FRI
+    /// folding.
+    pub field_bits: f64,
+    /// Bits of the OODS point (DEEP-ALI), including proof of work.
+    pub oods_bits: f64,
+    /// Bits of the interaction elements, including proof of work. Infinite without interaction
+    /// phases.
+    pub interaction_bits: f64,
+    /// Overall conjectured security, the minimum of the query and field terms.
+    pub conjectured_bits: f64,
+    /// Overall provable security in the list-decoding regime up to the Johnson bound.
+    pub proven_bits: f64,
+}
+
+impl SecurityReport {
+    /// Rejects the configuration if either estimate is below the required number of bits.
+    pub fn check(
+        &self,
+        min_conjectured_bits: u32,
+        min_proven_bits: u32,
+    ) -> Result<(), InsufficientSecurity> {
+        if self.conjectured_bits < min_conjectured_bits as f64 {
+            return Err(InsufficientSecurity::Conjectured {
+                bits: self.conjectured_bits,
+                required: min_conjectured_bits,
+            });
+        }
+        if self.proven_bits < min_proven_bits as f64 {
+            return Err(InsufficientSecurity::Proven {
+                bits: self.proven_bits,
+                required: min_proven_bits,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InsufficientSecurity {
+    Conjectured { bits: f64, required: u32 },
+    Proven { bits: f64, required: u32 },
+}
+
+impl fmt::Display for InsufficientSecurity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Conjectured { bits, required } => write!(
+                f,
+                "Conjectured security of {bits:.2} bits is below the required {required} bits."
+            ),
+            Self::Proven { bits, required } => write!(
+                f,
+                "Proven security of {bits:.2} bits is below the required {required} bits."
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InsufficientSecurity {}
+
+impl PcsConfig {
+    /// Estimates the security of proving an AIR with the given parameters under this config.
+    pub fn security_report(&self, air: &AirSecurityParams) -> SecurityReport {
+        let fri = &self.fri_config;
+        let log_field_size = log2(P4);
+        let log_blowup = fri.log_blowup_factor as f64;
+        let pow_bits = self.pow_bits as f64;
+        let n_queries = fri.n_queries as f64;
+
+        // The largest committed domain is the composition polynomial's evaluation domain.
+        let log_domain_size = (air
+            .composition_log_degree_bound
+            .max(air.max_column_log_degree_bound)
+            + fri.log_blowup_factor) as f64;
+        let log_n_fri_layers = log2(
+            air.composition_log_degree_bound
+                .saturating_sub(fri.log_last_layer_degree_bound)
+                .max(1)
+                .into(),
+        );
+        let log_n_constraints = log2(air.n_constraints.max(1) as u128);
+        // A circle polynomial of degree `d` has at most `2d` roots on the circle.
+        let log_deep_roots = air.composition_log_degree_bound as f64 + 1.;
+        let oods_pow_bits = self.oods_pow_bits as f64;
+        // A lookup over `n` rows fails with probability at most `2n / |F|` per interaction phase.
+        let interaction_bits = match air.n_interaction_phases {
+            0 => f64::INFINITY,
+            n_phases => {
+                log_field_size
+                    - (air.max_column_log_degree_bound as f64 + 1.)
+                    - log2(n_phases as u128)
+                    + self.interaction_pow_bits as f64
+            }
+        };
+
+        // Conjectured security.
+        let query_bits = n_queries * log_blowup + pow_bits;
+        let oods_bits = log_field_size - log_deep_roots + oods_pow_bits;
+        let field_bits = [
+            log_field_size - log_n_constraints,
+            oods_bits,
+            interaction_bits,
+            log_field_size - log_domain_size - log_n_fri_layers,
+        ]
+        .into_iter()
+        .fold(f64::INFINITY, f64::min);
+        let conjectured_bits = query_bits.min(field_bits);
+
+        // Proven security, up to the Johnson bound.
+        let m = JOHNSON_MULTIPLICITY;
+        let log_sqrt_rate = -log_blowup / 2.;
+        // `log2(m + 1/2)`.
+        let log_m_plus_half = log2(2 * m + 1) - 1.;
+        // Each query passes with probability at most `(1 + 1 / 2m) * sqrt(rate)`.
+        let proven_query_bits =
+            -n_queries * (log2(2 * m + 1) - log2(2 * m) + log_sqrt_rate) + pow_bits;
+        // List size at the Johnson bound.
+        let log_list_size = log_m_plus_half - log_sqrt_rate;
+        let log_commit_error = 7. * log_m_plus_half - log2(3)
+            + 3. * log_blowup / 2.
+            + 2. * log_domain_size
+            + log_n_fri_layers;
+        let proven_bits = [
+            proven_query_bits,
+            log_field_size - log_commit_error,
+            log_field_size - log_list_size - log_n_constraints,
+            log_field_size - 2. * log_list_size - log_deep_roots + oods_pow_bits,
+            interaction_bits,
+        ]
+        .into_iter()
+        .fold(f64::INFINITY, f64::min);
+
+        SecurityReport {
+            query_bits,
+            field_bits,
+            oods_bits,
+            interaction_bits,
+            conjectured_bits,
+            proven_bits,
+        }
+    }
+}
+
+/// Returns `log2(n)` for a positive `n`, with integer arithmetic only, as `f64::log2` is not
+/// available without `std`.
+fn log2(n: u128) -> f64 {
+    const FRAC_BITS: u32 = 62;
+    let int_part = n.ilog2();
+    // `n / 2^int_part`, in `[1, 2)`, as a fixed point number.
+    let mut x = if int_part > FRAC_BITS {
+        n >> (int_part - FRAC_BITS)
+    } else {
+        n << (FRAC_BITS - int_part)
+    };
+    // Each squaring of `x` yields the next bit of its logarithm.
+    let mut frac_part = 0.;
+    let mut bit = 0.5;
+    for _ in 0..f64::MANTISSA_DIGITS {
+        x = (x * x) >> FRAC_BITS;
+        if x >> (FRAC_BITS + 1) != 0 {
+            x >>= 1;
+            frac_part += bit;
+        }
+        bit /= 2.;
+    }
+    int_part as f64 + frac_part
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{log2, AirSecurityParams, InsufficientSecurity};
+    use crate::fri::FriConfig;
+    use crate::pcs::PcsConfig;
+
+    const AIR: AirSecurityParams = AirSecurityParams {
+        composition_log_degree_bound: 21,
+        max_column_log_degree_bound: 20,
+        n_constraints: 100,
+        n_interaction_phases: 0,
+    };
+
+    #[test]
+    fn test_log2() {
+        for n in [1, 2, 3, 7, 100, 1 << 40, 12345678901234567890, u128::MAX] {
+            assert!((log2(n) - (n as f64).log2()).abs() < 1e-12, "log2({n})");
+        }
+    }
+
+    #[test]
+    fn test_conjectured_security_is_query_bound_for_few_queries() {
+        let config = PcsConfig {
+            pow_bits: 20,
+            fri_config: FriConfig::new(0, 2, 30),
+            ..Default::default()
+        };
+
+        let report = config.security_report(&AIR);
+
+        assert_eq!(report.query_bits, 80.);
+        assert_eq!(report.conjectured_bits, 80.);
+        assert_eq!(config.security_bits(), 80);
+        assert!(report.proven_bits < report.conjectured_bits);
+    }
+
+    #[test]
+    fn test_conjectured_security_is_field_bound_for_many_queries() {
+        let config = PcsConfig {
+            pow_bits: 26,
+            fri_config: FriConfig::new(0, 4, 70),
+            ..Default::default()
+        };
+
+        let report = config.security_report(&AIR);
+
+        assert!(report.field_bits < report.query_bits);
+        assert_eq!(report.conjectured_bits, report.field_bits);
+        assert!(report.field_bits > 90.);
+    }
+
+    #[test]
+    fn test_stage_proof_of_work_adds_to_stage_bits() {
+        let air = AirSecurityParams {
+            n_interaction_phases: 2,
+            ..AIR
+        };
+        let config = PcsConfig {
+            interaction_pow_bits: 10,
+            oods_pow_bits: 20,
+            ..Default::default()
+        };
+
+        let report = config.security_report(&air);
+        let baseline = PcsConfig::default().security_report(&air);
+
+        assert_eq!(report.interaction_bits, baseline.interaction_bits + 10.);
+        assert_eq!(report.oods_bits, baseline.oods_bits + 20.);
+        assert_eq!(report.query_bits, baseline.query_bits);
+        assert!(PcsConfig::default()
+            .security_report(&AIR)
+            .interaction_bits
+            .is_infinite());
+    }
+
+    #[test]
+    fn test_security_check_rejects_weak_config() {
+        let report = PcsConfig::default().security_report(&AIR);
+
+        let result = report.check(96, 0);
+
+        assert!(matches!(
+            result,
+            Err(InsufficientSecurity::Conjectured { required: 96, .. })
+        ));
+        assert!(report.check(0, 0).is_ok());
+    }
+}
@@ -1,11 +1,12 @@
-use std::ops::{Deref, DerefMut};
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
 
 use itertools::zip_eq;
 
-use crate::core::ColumnVec;
+use crate::ColumnVec;
 
 /// A container that holds an element for each commitment tree.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TreeVec<T>(pub Vec<T>);
 
 impl<T> TreeVec<T> {
@@ -0,0 +1,246 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::zip;
+
+use itertools::Itertools;
+
+use super::super::circle::CirclePoint;
+use super::super::fields::m31::BaseField;
+use super::super::fields::qm31::SecureField;
+use super::super::fri::{
+    CirclePolyDegreeBound, FriProof, FriVerificationError, FriVerifier, FriVerifierOps,
+    FriVerifierTwiddles, SparseCircleEvaluation,
+};
+use super::super::proof_of_work::{ProofOfWork, ProofOfWorkProof};
+use super::quotients::{fri_answers, PointSample};
+use super::utils::TreeVec;
+use super::PcsConfig;
+use crate::channel::{Channel, MerkleChannel};
+use crate::prover::{ProofShapeError, VerificationError};
+use crate::vcs::ops::MerkleHasher;
+use crate::vcs::verifier::{MerkleDecommitment, MerkleVerifier};
+use crate::ColumnVec;
+
+#[derive(Debug)]
+pub struct CommitmentSchemeProof<H: MerkleHasher> {
+    pub config: PcsConfig,
+    pub sampled_values: TreeVec<ColumnVec<Vec<SecureField>>>,
+    pub decommitments: TreeVec<MerkleDecommitment<H>>,
+    pub queried_values: TreeVec<ColumnVec<Vec<BaseField>>>,
+    pub proof_of_work: ProofOfWorkProof,
+    pub fri_proof: FriProof<H>,
+}
+
+/// The verifier side of a FRI polynomial commitment scheme. See [super].
+pub struct CommitmentSchemeVerifier<MC: MerkleChannel> {
+    pub trees: TreeVec<MerkleVerifier<MC::H>>,
+    pub config: PcsConfig,
+}
+
+impl<MC: MerkleChannel> CommitmentSchemeVerifier<MC> {
+    pub fn new(config: PcsConfig) -> Self {
+        Self {
+            trees: TreeVec::default(),
+            config,
+        }
+    }
+
+    /// A [TreeVec<ColumnVec>] of the log sizes of each column in each commitment tree.
+    fn column_log_sizes(&self) -> TreeVec<ColumnVec<u32>> {
+        self.trees
+            .as_ref()
+            .map(|tree| tree.column_log_sizes.clone())
+    }
+
+    /// Reads a commitment from the prover: the cap of a tree of the shape given for it in the
+    /// config, a single root by default. See [PcsConfig::merkle_config].
+    pub fn commit(
+        &mut self,
+        cap: Vec<<MC::H as MerkleHasher>::Hash>,
+        log_sizes: &[u32],
+        channel: &mut MC::C,
+    ) {
+        MC::mix_cap(channel, &cap);
+        let extended_log_sizes = log_sizes
+            .iter()
+            .map(|&log_size| log_size + self.config.fri_config.log_blowup_factor)
+            .collect();
+        let merkle_config = self.config.merkle_config(self.trees.len());
+        let verifier = MerkleVerifier::from_cap(cap, extended_log_sizes).with_config(merkle_config);
+        self.trees.push(verifier);
+    }
+
+    /// Checks that the proof has a tree per commitment, and that the sampled and queried values
+    /// of each tree match its columns and their `sampled_points`.
+    pub fn check_proof_shape(
+        &self,
+        sampled_points: &TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>>,
+        proof: &CommitmentSchemeProof<MC::H>,
+    ) -> Result<(), ProofShapeError> {
+        let n_trees = self.trees.len();
+        for actual in [
+            sampled_points.len(),
+            proof.sampled_values.len(),
+            proof.decommitments.len(),
+            proof.queried_values.len(),
+        ] {
+            if actual != n_trees {
+                return Err(ProofShapeError::NumTrees {
+                    expected: n_trees,
+                    actual,
+                });
+            }
+        }
+
+        for (tree, ((verifier, points), (sampled_values, queried_values))) in zip(
+            zip(self.trees.iter(), sampled_points.iter()),
+            zip(proof.sampled_values.iter(), proof.queried_values.iter()),
+        )
+        .enumerate()
+        {
+            let n_columns = verifier.column_log_sizes.len();
+            if points.len() != n_columns || sampled_values.len() != n_columns {
+                return Err(ProofShapeError::NumSampledColumns {
+                    tree,
+                    expected: n_columns,
+                    actual: sampled_values.len(),
+                });
+            }
+            if queried_values.len() != n_columns {
+                return Err(ProofShapeError::NumQueriedColumns {
+                    tree,
+                    expected: n_columns,
+                    actual: queried_values.len(),
+                });
+            }
+            for (column, (points, values)) in zip(points, sampled_values).enumerate() {
+                if points.len() != values.len() {
+                    return Err(ProofShapeError::NumSamples {
+                        tree,
+                        column,
+                        expected: points.len(),
+                        actual: values.len(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn verify_values(
+        &self,
+        sampled_points: TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>>,
+        proof: CommitmentSchemeProof<MC::H>,
+        channel: &mut MC::C,
+    ) -> Result<(), VerificationError> {
+        self.verify_values_with(sampled_points, proof, channel, FriVerifier::decommit)
+    }
+
+    /// Like [Self::verify_values], but folds the FRI queries with `B` and the precomputed
+    /// `twiddles`. See [FriVerifier::decommit_with_twiddles].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `twiddles` don't cover the largest committed evaluation.
+    pub fn verify_values_with_twiddles<B: FriVerifierOps>(
+        &self,
+        sampled_points: TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>>,
+        proof: CommitmentSchemeProof<MC::H>,
+        channel: &mut MC::C,
+        twiddles: &FriVerifierTwiddles,
+    ) -> Result<(), VerificationError> {
+        self.verify_values_with(
+            sampled_points,
+            proof,
+            channel,
+            |fri_verifier, fri_answers| {
+                fri_verifier.decommit_with_twiddles::<B>(fri_answers, twiddles)
+            },
+        )
+    }
+
+    fn verify_values_with(
+        &self,
+        sampled_points: TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>>,
+        proof: CommitmentSchemeProof<MC::H>,
+        channel: &mut MC::C,
+        decommit: impl FnOnce(
+            FriVerifier<MC::H>,
+            Vec<SparseCircleEvaluation>,
+        ) -> Result<(), FriVerificationError>,
+    ) -> Result<(), VerificationError> {
+        if proof.config != self.config {
+            return Err(VerificationError::ConfigMismatch);
+        }
+        self.check_proof_shape(&sampled_points, &proof)?;
+
+        channel.mix_felts(&proof.sampled_values.clone().flatten_cols());
+        let random_coeff = channel.draw_felt();
+
+        let bounds = self
+            .column_log_sizes()
+            .zip_cols(&sampled_points)
+            .map_cols(|(log_size, sampled_points)| {
+                let log_degree_bound = log_size - self.config.fri_config.log_blowup_factor;
+                vec![CirclePolyDegreeBound::new(log_degree_bound); sampled_points.len()]
+            })
+            .flatten_cols()
+            .into_iter()
+            .sorted()
+            .rev()
+            .dedup()
+            .collect_vec();
+
+        // FRI commitment phase on OODS quotients.
+        let mut fri_verifier =
+            FriVerifier::commit(channel, self.config.fri_config, proof.fri_proof, bounds)?;
+
+        // Verify proof of work.
+        ProofOfWork::new(self.config.pow_bits).verify(channel, &proof.proof_of_work)?;
+
+        // Get FRI query domains.
+        let fri_query_domains = fri_verifier.column_query_positions(channel);
+
+        // Verify merkle decommitments.
+        self.trees
+            .as_ref()
+            .zip_eq(proof.decommitments)
+            .zip_eq(proof.queried_values.clone())
+            .map(|((tree, decommitment), queried_values)| {
+                let queries = fri_query_domains
+                    .iter()
+                    .map(|(&log_size, domain)| (log_size, domain.flatten()))
+                    .collect();
+                tree.verify(queries, queried_values, decommitment)
+            })
+            .0
+            .into_iter()
+            .enumerate()
+            .try_for_each(|(tree, result)| {
+                result.map_err(|error| VerificationError::Merkle { tree, error })
+            })?;
+
+        // Answer FRI queries.
+        let samples = sampled_points
+            .zip_cols(proof.sampled_values)
+            .map_cols(|(sampled_points, sampled_values)| {
+                zip(sampled_points, sampled_values)
+                    .map(|(point, value)| PointSample { point, value })
+                    .collect_vec()
+            })
+            .flatten();
+
+        // TODO(spapini): Properly defined column log size and dinstinguish between poly and
+        // commitment.
+        let fri_answers = fri_answers(
+            self.column_log_sizes().flatten().into_iter().collect(),
+            &samples,
+            random_coeff,
+            fri_query_domains,
+            &proof.queried_values.flatten(),
+        )?;
+
+        decommit(fri_verifier, fri_answers)?;
+        Ok(())
+    }
+}
@@ -1,11 +1,10 @@
-use std::iter::Chain;
+use alloc::vec::Vec;
+use core::iter::Chain;
 
 use itertools::Itertools;
 
-use crate::core::circle::{
-    CirclePoint, CirclePointIndex, Coset, CosetIterator, M31_CIRCLE_LOG_ORDER,
-};
-use crate::core::fields::m31::BaseField;
+use crate::circle::{CirclePoint, CirclePointIndex, Coset, CosetIterator, M31_CIRCLE_LOG_ORDER};
+use crate::fields::m31::BaseField;
 
 pub const MAX_CIRCLE_DOMAIN_LOG_SIZE: u32 = M31_CIRCLE_LOG_ORDER - 1;
 
@@ -75,7 +74,7 @@ impl CircleDomain {
     ///
     /// Canonic domains are domains with elements that are the entire set of points defined by
     /// `G_2n + <G_n>` where `G_n` and `G_2n` are obtained by repeatedly doubling
-    /// [crate::core::circle::M31_CIRCLE_GEN].
+    /// [crate::circle::M31_CIRCLE_GEN].
     pub fn is_canonic(&self) -> bool {
         self.half_coset.initial_index * 4 == self.half_coset.step_size
     }
@@ -120,11 +119,13 @@ type CircleDomainIndexIterator =
 
 #[cfg(test)]
 mod tests {
+    use alloc::vec::Vec;
+
     use itertools::Itertools;
 
     use super::CircleDomain;
-    use crate::core::circle::{CirclePointIndex, Coset};
-    use crate::core::poly::circle::CanonicCoset;
+    use crate::circle::{CirclePointIndex, Coset};
+    use crate::poly::circle::CanonicCoset;
 
     #[test]
     fn test_circle_domain_iterator() {
@@ -1,5 +1,7 @@
+use alloc::vec::Vec;
+
 use super::line::LineDomain;
-use crate::core::fields::{ExtensionOf, Field};
+use crate::fields::{ExtensionOf, Field};
 
 /// Folds values recursively in `O(n)` by a hierarchical application of folding factors.
 ///
@@ -73,6 +75,8 @@ pub fn domain_line_twiddles_from_tree<T>(
 
 #[cfg(test)]
 mod tests {
+    use alloc::vec;
+
     use super::repeat_value;
 
     #[test]
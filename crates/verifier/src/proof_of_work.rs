@@ -0,0 +1,129 @@
+use core::fmt;
+
+use tracing::{span, Level};
+
+use crate::channel::Channel;
+
+pub struct ProofOfWork {
+    // Proof of work difficulty.
+    pub n_bits: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProofOfWorkProof {
+    pub nonce: u64,
+}
+
+impl ProofOfWork {
+    pub fn new(n_bits: u32) -> Self {
+        Self { n_bits }
+    }
+
+    pub fn prove(&self, channel: &mut impl Channel) -> ProofOfWorkProof {
+        let _span = span!(Level::INFO, "Proof of work").entered();
+        let proof = self.grind(channel);
+        channel.mix_nonce(proof.nonce);
+        proof
+    }
+
+    pub fn verify(
+        &self,
+        channel: &mut impl Channel,
+        proof: &ProofOfWorkProof,
+    ) -> Result<(), ProofOfWorkVerificationError> {
+        if !channel.verify_pow_nonce(self.n_bits, proof.nonce) {
+            return Err(ProofOfWorkVerificationError::ProofOfWorkVerificationFailed);
+        }
+
+        channel.mix_nonce(proof.nonce);
+        Ok(())
+    }
+
+    fn grind(&self, channel: &impl Channel) -> ProofOfWorkProof {
+        let mut nonce = 0u64;
+        // Naive sequential search. Provers grind with the backend's accelerated `GrindOps`.
+        loop {
+            if channel.verify_pow_nonce(self.n_bits, nonce) {
+                return ProofOfWorkProof { nonce };
+            }
+            nonce += 1;
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum ProofOfWorkVerificationError {
+    ProofOfWorkVerificationFailed,
+}
+
+impl fmt::Display for ProofOfWorkVerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ProofOfWorkVerificationFailed => {
+                f.write_str("Proof of work verification failed.")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ProofOfWorkVerificationError {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use starknet_ff::FieldElement as FieldElement252;
+
+    use crate::channel::{Blake2sChannel, Channel, Poseidon252Channel};
+    use crate::proof_of_work::{ProofOfWork, ProofOfWorkProof};
+    use crate::vcs::blake2_hash::Blake2sHash;
+
+    #[test]
+    fn test_verify_proof_of_work_success() {
+        let mut channel = Blake2sChannel::new(Blake2sHash::from(vec![0; 32]));
+        let proof_of_work_prover = ProofOfWork { n_bits: 11 };
+        let proof = ProofOfWorkProof { nonce: 133 };
+
+        proof_of_work_prover.verify(&mut channel, &proof).unwrap();
+    }
+
+    #[test]
+    fn test_verify_proof_of_work_fail() {
+        let mut channel = Blake2sChannel::new(Blake2sHash::from(vec![0; 32]));
+        let proof_of_work_prover = ProofOfWork { n_bits: 1 };
+        let invalid_proof = ProofOfWorkProof { nonce: 0 };
+
+        proof_of_work_prover
+            .verify(&mut channel, &invalid_proof)
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_proof_of_work() {
+        let n_bits = 12;
+        let mut prover_channel = Blake2sChannel::new(Blake2sHash::default());
+        let mut verifier_channel = Blake2sChannel::new(Blake2sHash::default());
+        let prover = ProofOfWork::new(n_bits);
+        let verifier = ProofOfWork::new(n_bits);
+
+        let proof = prover.prove(&mut prover_channel);
+        verifier.verify(&mut verifier_channel, &proof).unwrap();
+
+        assert_eq!(prover_channel.get_digest(), verifier_channel.get_digest());
+    }
+
+    #[test]
+    fn test_proof_of_work_poseidon252() {
+        let n_bits = 8;
+        let mut prover_channel = Poseidon252Channel::new(FieldElement252::default());
+        let mut verifier_channel = Poseidon252Channel::new(FieldElement252::default());
+
+        let proof = ProofOfWork::new(n_bits).prove(&mut prover_channel);
+        ProofOfWork::new(n_bits)
+            .verify(&mut verifier_channel, &proof)
+            .unwrap();
+
+        assert_eq!(prover_channel.get_digest(), verifier_channel.get_digest());
+    }
+}
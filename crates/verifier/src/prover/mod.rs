@@ -0,0 +1,372 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use itertools::Itertools;
+
+use super::fields::qm31::SECURE_EXTENSION_DEGREE;
+use super::fri::{FriVerificationError, FriVerifierOps, FriVerifierTwiddles};
+use super::pcs::{CommitmentSchemeProof, TreeVec};
+use super::proof_of_work::{ProofOfWork, ProofOfWorkProof, ProofOfWorkVerificationError};
+use super::{ColumnVec, InteractionElements, LookupValues};
+use crate::air::{Air, AirExt};
+use crate::channel::{Channel, MerkleChannel};
+use crate::circle::CirclePoint;
+use crate::fields::qm31::SecureField;
+use crate::pcs::CommitmentSchemeVerifier;
+use crate::vcs::ops::MerkleHasher;
+use crate::vcs::verifier::MerkleVerificationError;
+
+#[derive(Debug)]
+pub struct StarkProof<H: MerkleHasher> {
+    /// The cap of each committed tree, see [PcsConfig::merkle_config].
+    ///
+    /// [PcsConfig::merkle_config]: crate::pcs::PcsConfig::merkle_config
+    pub commitments: TreeVec<Vec<H::Hash>>,
+    pub lookup_values: LookupValues,
+    /// Proofs of work preceding the interaction elements of each interaction phase.
+    pub interaction_proofs_of_work: Vec<ProofOfWorkProof>,
+    /// Proof of work preceding the OODS point.
+    pub oods_proof_of_work: ProofOfWorkProof,
+    pub commitment_scheme_proof: CommitmentSchemeProof<H>,
+}
+
+pub fn verify<MC: MerkleChannel>(
+    air: &impl Air,
+    channel: &mut MC::C,
+    interaction_elements: &InteractionElements,
+    commitment_scheme: &mut CommitmentSchemeVerifier<MC>,
+    proof: StarkProof<MC::H>,
+) -> Result<(), VerificationError> {
+    verify_with(
+        air,
+        channel,
+        interaction_elements,
+        commitment_scheme,
+        proof,
+        CommitmentSchemeVerifier::verify_values,
+    )
+}
+
+/// Like [verify], but folds the FRI queries with `B` and the precomputed `twiddles`. See
+/// [CommitmentSchemeVerifier::verify_values_with_twiddles].
+pub fn verify_with_twiddles<MC: MerkleChannel, B: FriVerifierOps>(
+    air: &impl Air,
+    channel: &mut MC::C,
+    interaction_elements: &InteractionElements,
+    commitment_scheme: &mut CommitmentSchemeVerifier<MC>,
+    proof: StarkProof<MC::H>,
+    twiddles: &FriVerifierTwiddles,
+) -> Result<(), VerificationError> {
+    verify_with(
+        air,
+        channel,
+        interaction_elements,
+        commitment_scheme,
+        proof,
+        |commitment_scheme, sample_points, proof, channel| {
+            commitment_scheme.verify_values_with_twiddles::<B>(
+                sample_points,
+                proof,
+                channel,
+                twiddles,
+            )
+        },
+    )
+}
+
+fn verify_with<MC: MerkleChannel>(
+    air: &impl Air,
+    channel: &mut MC::C,
+    interaction_elements: &InteractionElements,
+    commitment_scheme: &mut CommitmentSchemeVerifier<MC>,
+    proof: StarkProof<MC::H>,
+    verify_values: impl FnOnce(
+        &CommitmentSchemeVerifier<MC>,
+        TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>>,
+        CommitmentSchemeProof<MC::H>,
+        &mut MC::C,
+    ) -> Result<(), VerificationError>,
+) -> Result<(), VerificationError> {
+    // Check the config early, as a mismatch would otherwise only surface as an OODS failure.
+    if proof.commitment_scheme_proof.config != commitment_scheme.config {
+        return Err(VerificationError::ConfigMismatch);
+    }
+    air.verify_lookups(&proof.lookup_values)?;
+
+    let random_coeff = channel.draw_felt();
+
+    // Read composition polynomial commitment.
+    let n_trees = commitment_scheme.trees.len() + 1;
+    if proof.commitments.len() != n_trees {
+        return Err(ProofShapeError::NumTrees {
+            expected: n_trees,
+            actual: proof.commitments.len(),
+        }
+        .into());
+    }
+    commitment_scheme.commit(
+        proof.commitments[n_trees - 1].clone(),
+        &[air.composition_log_degree_bound(); SECURE_EXTENSION_DEGREE],
+        channel,
+    );
+
+    // Draw OODS point, after a proof of work against grinding it.
+    ProofOfWork::new(commitment_scheme.config.oods_pow_bits)
+        .verify(channel, &proof.oods_proof_of_work)?;
+    let oods_point = CirclePoint::<SecureField>::get_random_point(channel);
+
+    // Get mask sample points relative to oods point.
+    let sample_points = air.mask_points(oods_point);
+    commitment_scheme.check_proof_shape(&sample_points, &proof.commitment_scheme_proof)?;
+
+    // TODO(spapini): Save clone.
+    let (trace_oods_values, composition_oods_value) =
+        sampled_values_to_mask(air, &proof.commitment_scheme_proof.sampled_values)
+            .expect("The shape of the sampled values was checked");
+
+    if composition_oods_value
+        != air.eval_composition_polynomial_at_point(
+            oods_point,
+            &trace_oods_values,
+            random_coeff,
+            interaction_elements,
+            &proof.lookup_values,
+        )
+    {
+        return Err(VerificationError::OodsNotMatching);
+    }
+
+    verify_values(
+        commitment_scheme,
+        sample_points,
+        proof.commitment_scheme_proof,
+        channel,
+    )
+}
+
+#[allow(clippy::type_complexity)]
+/// Structures the tree-wise sampled values into component-wise OODS values and a composition
+/// polynomial OODS value.
+pub fn sampled_values_to_mask(
+    air: &impl Air,
+    sampled_values: &TreeVec<ColumnVec<Vec<SecureField>>>,
+) -> Result<(Vec<TreeVec<Vec<Vec<SecureField>>>>, SecureField), InvalidOodsSampleStructure> {
+    let mut sampled_values = sampled_values.as_ref();
+    let composition_values = sampled_values.pop().ok_or(InvalidOodsSampleStructure)?;
+
+    let mut sample_iters = sampled_values.map(|tree_value| tree_value.iter());
+    let trace_oods_values = air
+        .components()
+        .iter()
+        .map(|component| {
+            component
+                .mask_points(CirclePoint::zero())
+                .zip(sample_iters.as_mut())
+                .map(|(mask_per_tree, tree_iter)| {
+                    tree_iter.take(mask_per_tree.len()).cloned().collect_vec()
+                })
+        })
+        .collect_vec();
+
+    let composition_oods_value = SecureField::from_partial_evals(
+        composition_values
+            .iter()
+            .flatten()
+            .cloned()
+            .collect_vec()
+            .try_into()
+            .map_err(|_| InvalidOodsSampleStructure)?,
+    );
+
+    Ok((trace_oods_values, composition_oods_value))
+}
+
+/// Error when the sampled values have an invalid structure.
+#[derive(Clone, Copy, Debug)]
+pub struct InvalidOodsSampleStructure;
+
+impl fmt::Display for InvalidOodsSampleStructure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Invalid OODS sample structure.")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidOodsSampleStructure {}
+
+#[derive(Clone, Debug)]
+pub enum VerificationError {
+    /// The proof does not have the shape the AIR and the PCS config expect.
+    InvalidStructure(ProofShapeError),
+    /// The proof was generated with a different PCS config than the verifier's.
+    ConfigMismatch,
+    /// The lookup values of the given name do not match.
+    InvalidLookup(String),
+    /// The proof does not provide the lookup value of the given name.
+    MissingLookupValue(String),
+    /// The AIR has not drawn the interaction element of the given name.
+    MissingInteractionElement(String),
+    /// The decommitment of a commitment tree failed.
+    Merkle {
+        tree: usize,
+        error: MerkleVerificationError,
+    },
+    OodsNotMatching,
+    PreprocessedRootMismatch,
+    Fri(FriVerificationError),
+    ProofOfWork(ProofOfWorkVerificationError),
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidStructure(error) => write!(f, "Proof has invalid structure: {error}"),
+            Self::ConfigMismatch => f.write_str("PCS config does not match the expected config."),
+            Self::InvalidLookup(name) => write!(f, "{name} lookup values do not match."),
+            Self::MissingLookupValue(name) => write!(f, "Lookup value {name} is missing."),
+            Self::MissingInteractionElement(name) => {
+                write!(f, "Interaction element {name} is missing.")
+            }
+            Self::Merkle { tree, error } => write!(f, "Tree {tree}: {error}"),
+            Self::OodsNotMatching => write!(
+                f,
+                "The composition polynomial OODS value does not match the trace OODS values
+    (DEEP-ALI failure)."
+            ),
+            Self::PreprocessedRootMismatch => {
+                write!(
+                    f,
+                    "Preprocessed trace root does not match the expected root."
+                )
+            }
+            Self::Fri(error) => error.fmt(f),
+            Self::ProofOfWork(error) => error.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerificationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Merkle { error, .. } => Some(error),
+            Self::Fri(error) => Some(error),
+            Self::ProofOfWork(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Describes where the shape of a proof differs from the one the verifier expects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofShapeError {
+    /// The number of commitment trees differs from the AIR's, including the composition tree.
+    NumTrees { expected: usize, actual: usize },
+    /// The number of interaction proofs of work differs from the AIR's interaction phases.
+    NumInteractionProofsOfWork { expected: usize, actual: usize },
+    /// The number of sampled columns in a tree differs from the AIR's column log sizes.
+    NumSampledColumns {
+        tree: usize,
+        expected: usize,
+        actual: usize,
+    },
+    /// The number of samples of a column differs from its mask.
+    NumSamples {
+        tree: usize,
+        column: usize,
+        expected: usize,
+        actual: usize,
+    },
+    /// The number of queried columns in a tree differs from the AIR's column log sizes.
+    NumQueriedColumns {
+        tree: usize,
+        expected: usize,
+        actual: usize,
+    },
+    /// The number of queried values of the columns of a given (extended) log size differs from the
+    /// number of queries.
+    NumQueriedValues {
+        log_size: u32,
+        expected: usize,
+        actual: usize,
+    },
+    /// The number of coefficients of the FRI last layer polynomial differs from its degree bound.
+    LastLayerLength { expected: usize, actual: usize },
+}
+
+impl fmt::Display for ProofShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NumTrees { expected, actual } => {
+                write!(f, "expected {expected} trees, got {actual}.")
+            }
+            Self::NumInteractionProofsOfWork { expected, actual } => write!(
+                f,
+                "expected {expected} interaction proofs of work, got {actual}."
+            ),
+            Self::NumSampledColumns {
+                tree,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "expected {expected} sampled columns in tree {tree}, got {actual}."
+            ),
+            Self::NumSamples {
+                tree,
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "expected {expected} samples in tree {tree} column {column}, got {actual}."
+            ),
+            Self::NumQueriedColumns {
+                tree,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "expected {expected} queried columns in tree {tree}, got {actual}."
+            ),
+            Self::NumQueriedValues {
+                log_size,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "expected {expected} queried values in columns of log size {log_size}, got \
+                 {actual}."
+            ),
+            Self::LastLayerLength { expected, actual } => write!(
+                f,
+                "expected {expected} last layer coefficients, got {actual}."
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ProofShapeError {}
+
+impl From<ProofShapeError> for VerificationError {
+    fn from(error: ProofShapeError) -> Self {
+        Self::InvalidStructure(error)
+    }
+}
+
+impl From<FriVerificationError> for VerificationError {
+    fn from(error: FriVerificationError) -> Self {
+        match error {
+            FriVerificationError::InvalidStructure(error) => Self::InvalidStructure(error),
+            error => Self::Fri(error),
+        }
+    }
+}
+
+impl From<ProofOfWorkVerificationError> for VerificationError {
+    fn from(error: ProofOfWorkVerificationError) -> Self {
+        Self::ProofOfWork(error)
+    }
+}
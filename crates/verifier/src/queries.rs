@@ -1,5 +1,6 @@
-use std::collections::BTreeSet;
-use std::ops::Deref;
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use core::ops::Deref;
 
 use itertools::Itertools;
 
@@ -43,7 +44,7 @@ impl Queries {
     // TODO docs
     #[allow(clippy::missing_safety_doc)]
     pub fn from_positions(positions: Vec<usize>, log_domain_size: u32) -> Self {
-        assert!(positions.is_sorted());
+        assert!(positions.windows(2).all(|w| w[0] <= w[1]));
         assert!(positions.iter().all(|p| *p < (1 << log_domain_size)));
         Self {
             positions,
@@ -132,11 +133,14 @@ impl SubCircleDomain {
 
 #[cfg(test)]
 mod tests {
-    use crate::core::channel::{Blake2sChannel, Channel};
-    use crate::core::poly::circle::CanonicCoset;
-    use crate::core::queries::Queries;
-    use crate::core::utils::bit_reverse;
-    use crate::core::vcs::blake2_hash::Blake2sHash;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use crate::channel::{Blake2sChannel, Channel};
+    use crate::poly::circle::CanonicCoset;
+    use crate::queries::Queries;
+    use crate::utils::bit_reverse;
+    use crate::vcs::blake2_hash::Blake2sHash;
 
     #[test]
     fn test_generate_queries() {
@@ -175,7 +179,7 @@ mod tests {
         let folded_queries = queries.fold(n_folds);
         let repeated_folded_queries = folded_queries
             .iter()
-            .flat_map(|q| std::iter::repeat(q).take(ratio));
+            .flat_map(|q| core::iter::repeat(q).take(ratio));
         for (query, folded_query) in queries.iter().zip(repeated_folded_queries) {
             // Check only the x coordinate since folding might give you the conjugate point.
             assert_eq!(
@@ -215,7 +219,9 @@ mod tests {
         let queries = Queries::generate(channel, log_domain_size, n_queries);
         let queries_with_added_positions = queries.opening_positions(fri_step_size).flatten();
 
-        assert!(queries_with_added_positions.is_sorted());
+        assert!(queries_with_added_positions
+            .windows(2)
+            .all(|w| w[0] <= w[1]));
         assert_eq!(
             queries_with_added_positions.len(),
             n_queries * (1 << fri_step_size)
@@ -0,0 +1,726 @@
+//! Canonical binary encoding of proofs.
+//!
+//! The encoding is compact and deterministic:
+//! * Integers are little-endian. Lengths are encoded as `u32`.
+//! * [BaseField] elements are encoded as 4 bytes and must be fully reduced, i.e. less than `P`.
+//! * [SecureField] elements are encoded as their 4 [BaseField] coordinates.
+//! * Hashes are encoded as their raw bytes.
+//!
+//! Every valid proof has exactly one encoding. Decoding rejects non-canonical field elements,
+//! unsorted maps and trailing bytes, so re-encoding a decoded proof yields the original bytes.
+//! A top-level proof is prefixed with [PROOF_FORMAT_VERSION].
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use itertools::Itertools;
+
+use super::fields::m31::{BaseField, P};
+use super::fields::qm31::{SecureField, SECURE_EXTENSION_DEGREE};
+use super::fri::{FriConfig, FriLayerProof, FriProof};
+use super::low_degree_test::LowDegreeProof;
+use super::pcs::{CommitmentSchemeProof, PcsConfig, TreeVec};
+use super::poly::line::LinePoly;
+use super::proof_of_work::ProofOfWorkProof;
+use super::prover::StarkProof;
+use super::vcs::blake2_hash::Blake2sHash;
+use super::vcs::blake3_hash::Blake3Hash;
+use super::vcs::ops::MerkleHasher;
+use super::vcs::poseidon2_m31::Poseidon2M31Hash;
+use super::vcs::verifier::{MerkleConfig, MerkleDecommitment, MerkleOpening};
+use super::LookupValues;
+
+/// Version of the binary proof format. Bumped on every incompatible change to the encoding.
+pub const PROOF_FORMAT_VERSION: u8 = 3;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeserializationError {
+    UnexpectedEnd,
+    UnsupportedVersion(u8),
+    TrailingBytes(usize),
+    NonCanonicalFieldElement,
+    InvalidValue(&'static str),
+}
+
+impl fmt::Display for DeserializationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "Unexpected end of input."),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "Unsupported proof format version {version}, expected {PROOF_FORMAT_VERSION}."
+            ),
+            Self::TrailingBytes(n) => write!(f, "{n} trailing bytes after the proof."),
+            Self::NonCanonicalFieldElement => write!(f, "Non-canonical field element."),
+            Self::InvalidValue(what) => write!(f, "Invalid proof: {what}."),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DeserializationError {}
+
+/// A type with a canonical binary encoding.
+pub trait Serializable: Sized {
+    /// Appends the encoding of `self` to `out`.
+    fn write_bytes(&self, out: &mut Vec<u8>);
+
+    /// Decodes a value from the start of `input`, advancing it past the consumed bytes.
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError>;
+}
+
+/// Reads exactly `N` bytes from the start of `input`, advancing it past them.
+pub fn read_array<const N: usize>(input: &mut &[u8]) -> Result<[u8; N], DeserializationError> {
+    if input.len() < N {
+        return Err(DeserializationError::UnexpectedEnd);
+    }
+    let (bytes, rest) = input.split_at(N);
+    *input = rest;
+    Ok(bytes.try_into().unwrap())
+}
+
+impl Serializable for u8 {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        out.push(*self);
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        Ok(read_array::<1>(input)?[0])
+    }
+}
+
+impl Serializable for u32 {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        Ok(u32::from_le_bytes(read_array(input)?))
+    }
+}
+
+impl Serializable for u64 {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        Ok(u64::from_le_bytes(read_array(input)?))
+    }
+}
+
+/// Encoded as a `u32`.
+impl Serializable for usize {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        u32::try_from(*self)
+            .expect("Length does not fit in u32")
+            .write_bytes(out);
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        Ok(u32::read_bytes(input)? as usize)
+    }
+}
+
+impl Serializable for String {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.len().write_bytes(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        let len = usize::read_bytes(input)?;
+        if input.len() < len {
+            return Err(DeserializationError::UnexpectedEnd);
+        }
+        let (bytes, rest) = input.split_at(len);
+        *input = rest;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| DeserializationError::InvalidValue("string is not valid UTF-8"))
+    }
+}
+
+impl<T: Serializable> Serializable for Vec<T> {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.len().write_bytes(out);
+        self.iter().for_each(|value| value.write_bytes(out));
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        let len = usize::read_bytes(input)?;
+        // Every element takes at least one byte, so don't trust larger lengths for allocation.
+        let mut values = Vec::with_capacity(len.min(input.len()));
+        for _ in 0..len {
+            values.push(T::read_bytes(input)?);
+        }
+        Ok(values)
+    }
+}
+
+impl<T: Serializable> Serializable for TreeVec<T> {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.0.write_bytes(out);
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        Ok(TreeVec::new(Vec::read_bytes(input)?))
+    }
+}
+
+impl Serializable for BaseField {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.0.write_bytes(out);
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        let value = u32::read_bytes(input)?;
+        if value >= P {
+            return Err(DeserializationError::NonCanonicalFieldElement);
+        }
+        Ok(BaseField::from_u32_unchecked(value))
+    }
+}
+
+impl Serializable for SecureField {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.to_m31_array()
+            .iter()
+            .for_each(|value| value.write_bytes(out));
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        let mut values = [BaseField::default(); SECURE_EXTENSION_DEGREE];
+        for value in &mut values {
+            *value = BaseField::read_bytes(input)?;
+        }
+        Ok(SecureField::from_m31_array(values))
+    }
+}
+
+impl Serializable for Blake2sHash {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.as_ref());
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        Ok(Blake2sHash::from(&read_array::<32>(input)?[..]))
+    }
+}
+
+impl Serializable for Blake3Hash {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.as_ref());
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        Ok(Blake3Hash::from(&read_array::<32>(input)?[..]))
+    }
+}
+
+impl Serializable for Poseidon2M31Hash {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.0.iter().for_each(|value| value.write_bytes(out));
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        let mut values = [BaseField::default(); 8];
+        for value in &mut values {
+            *value = BaseField::read_bytes(input)?;
+        }
+        Ok(Poseidon2M31Hash(values))
+    }
+}
+
+/// Encoded as 32 big-endian bytes, which must be less than the field modulus.
+impl Serializable for starknet_ff::FieldElement {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_bytes_be());
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        starknet_ff::FieldElement::from_bytes_be(&read_array(input)?)
+            .map_err(|_| DeserializationError::NonCanonicalFieldElement)
+    }
+}
+
+/// Encoded as the coefficients in their stored (bit-reversed) order.
+impl Serializable for LinePoly {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.to_vec().write_bytes(out);
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        let coeffs = Vec::<SecureField>::read_bytes(input)?;
+        if !coeffs.len().is_power_of_two() {
+            return Err(DeserializationError::InvalidValue(
+                "number of last layer coefficients is not a power of two",
+            ));
+        }
+        Ok(LinePoly::new(coeffs))
+    }
+}
+
+/// Encoded as a sorted list of entries. Decoding rejects unsorted or duplicate keys.
+impl Serializable for LookupValues {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.0.len().write_bytes(out);
+        for (key, value) in &self.0 {
+            key.write_bytes(out);
+            value.write_bytes(out);
+        }
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        let len = usize::read_bytes(input)?;
+        let mut values = BTreeMap::new();
+        for _ in 0..len {
+            let key = String::read_bytes(input)?;
+            let value = BaseField::read_bytes(input)?;
+            if values
+                .last_key_value()
+                .is_some_and(|(last, _)| *last >= key)
+            {
+                return Err(DeserializationError::InvalidValue(
+                    "lookup value keys are not strictly increasing",
+                ));
+            }
+            values.insert(key, value);
+        }
+        Ok(LookupValues::new(values))
+    }
+}
+
+impl Serializable for FriConfig {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.log_blowup_factor.write_bytes(out);
+        self.log_last_layer_degree_bound.write_bytes(out);
+        self.n_queries.write_bytes(out);
+        self.fold_step.write_bytes(out);
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        let log_blowup_factor = u32::read_bytes(input)?;
+        let log_last_layer_degree_bound = u32::read_bytes(input)?;
+        let n_queries = usize::read_bytes(input)?;
+        let fold_step = u32::read_bytes(input)?;
+        if !FriConfig::LOG_BLOWUP_FACTOR_RANGE.contains(&log_blowup_factor) {
+            return Err(DeserializationError::InvalidValue(
+                "blowup factor out of range",
+            ));
+        }
+        if !FriConfig::LOG_LAST_LAYER_DEGREE_BOUND_RANGE.contains(&log_last_layer_degree_bound) {
+            return Err(DeserializationError::InvalidValue(
+                "last layer degree bound out of range",
+            ));
+        }
+        if !FriConfig::last_layer_domain_fits(log_last_layer_degree_bound, log_blowup_factor) {
+            return Err(DeserializationError::InvalidValue(
+                "last layer domain too large",
+            ));
+        }
+        if !FriConfig::FOLD_STEP_RANGE.contains(&fold_step) {
+            return Err(DeserializationError::InvalidValue("fold step out of range"));
+        }
+        Ok(
+            FriConfig::new(log_last_layer_degree_bound, log_blowup_factor, n_queries)
+                .with_fold_step(fold_step),
+        )
+    }
+}
+
+impl Serializable for MerkleConfig {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.log_arity.write_bytes(out);
+        self.log_leaf_rows.write_bytes(out);
+        self.log_cap_size.write_bytes(out);
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        let log_arity = u32::read_bytes(input)?;
+        let log_leaf_rows = u32::read_bytes(input)?;
+        let log_cap_size = u32::read_bytes(input)?;
+        if !MerkleConfig::LOG_ARITY_RANGE.contains(&log_arity) {
+            return Err(DeserializationError::InvalidValue(
+                "Merkle arity out of range",
+            ));
+        }
+        if !MerkleConfig::LOG_LEAF_ROWS_RANGE.contains(&log_leaf_rows) {
+            return Err(DeserializationError::InvalidValue(
+                "Merkle leaf rows out of range",
+            ));
+        }
+        if !MerkleConfig::LOG_CAP_SIZE_RANGE.contains(&log_cap_size) {
+            return Err(DeserializationError::InvalidValue(
+                "Merkle cap size out of range",
+            ));
+        }
+        Ok(MerkleConfig::new(log_arity, log_leaf_rows).with_log_cap_size(log_cap_size))
+    }
+}
+
+impl Serializable for PcsConfig {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.pow_bits.write_bytes(out);
+        self.interaction_pow_bits.write_bytes(out);
+        self.oods_pow_bits.write_bytes(out);
+        self.fri_config.write_bytes(out);
+        self.merkle_configs.write_bytes(out);
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        Ok(PcsConfig {
+            pow_bits: u32::read_bytes(input)?,
+            interaction_pow_bits: u32::read_bytes(input)?,
+            oods_pow_bits: u32::read_bytes(input)?,
+            fri_config: FriConfig::read_bytes(input)?,
+            merkle_configs: TreeVec::read_bytes(input)?,
+        })
+    }
+}
+
+impl Serializable for ProofOfWorkProof {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.nonce.write_bytes(out);
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        Ok(ProofOfWorkProof {
+            nonce: u64::read_bytes(input)?,
+        })
+    }
+}
+
+impl<H: MerkleHasher> Serializable for MerkleDecommitment<H>
+where
+    H::Hash: Serializable,
+{
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.hash_witness.write_bytes(out);
+        self.column_witness.write_bytes(out);
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        Ok(MerkleDecommitment {
+            hash_witness: Vec::read_bytes(input)?,
+            column_witness: Vec::read_bytes(input)?,
+        })
+    }
+}
+
+impl<H: MerkleHasher> Serializable for MerkleOpening<H>
+where
+    H::Hash: Serializable,
+{
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.queried_values.write_bytes(out);
+        self.decommitment.write_bytes(out);
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        Ok(MerkleOpening {
+            queried_values: Vec::read_bytes(input)?,
+            decommitment: MerkleDecommitment::read_bytes(input)?,
+        })
+    }
+}
+
+impl<H: MerkleHasher> Serializable for FriLayerProof<H>
+where
+    H::Hash: Serializable,
+{
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.evals_subset.write_bytes(out);
+        self.decommitment.write_bytes(out);
+        self.decomposition_coeff.write_bytes(out);
+        self.commitment.write_bytes(out);
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        Ok(FriLayerProof {
+            evals_subset: Vec::read_bytes(input)?,
+            decommitment: MerkleDecommitment::read_bytes(input)?,
+            decomposition_coeff: SecureField::read_bytes(input)?,
+            commitment: H::Hash::read_bytes(input)?,
+        })
+    }
+}
+
+impl<H: MerkleHasher> Serializable for FriProof<H>
+where
+    H::Hash: Serializable,
+{
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.inner_layers.write_bytes(out);
+        self.last_layer_poly.write_bytes(out);
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        Ok(FriProof {
+            inner_layers: Vec::read_bytes(input)?,
+            last_layer_poly: LinePoly::read_bytes(input)?,
+        })
+    }
+}
+
+impl<H: MerkleHasher> Serializable for LowDegreeProof<H>
+where
+    H::Hash: Serializable,
+{
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.commitment.write_bytes(out);
+        self.queried_values.write_bytes(out);
+        self.decommitment.write_bytes(out);
+        self.fri_proof.write_bytes(out);
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        Ok(LowDegreeProof {
+            commitment: H::Hash::read_bytes(input)?,
+            queried_values: Vec::read_bytes(input)?,
+            decommitment: MerkleDecommitment::read_bytes(input)?,
+            fri_proof: FriProof::read_bytes(input)?,
+        })
+    }
+}
+
+impl<H: MerkleHasher> Serializable for CommitmentSchemeProof<H>
+where
+    H::Hash: Serializable,
+{
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.config.write_bytes(out);
+        self.sampled_values.write_bytes(out);
+        self.decommitments.write_bytes(out);
+        self.queried_values.write_bytes(out);
+        self.proof_of_work.write_bytes(out);
+        self.fri_proof.write_bytes(out);
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        Ok(CommitmentSchemeProof {
+            config: PcsConfig::read_bytes(input)?,
+            sampled_values: TreeVec::read_bytes(input)?,
+            decommitments: TreeVec::read_bytes(input)?,
+            queried_values: TreeVec::read_bytes(input)?,
+            proof_of_work: ProofOfWorkProof::read_bytes(input)?,
+            fri_proof: FriProof::read_bytes(input)?,
+        })
+    }
+}
+
+impl<H: MerkleHasher> Serializable for StarkProof<H>
+where
+    H::Hash: Serializable,
+{
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.commitments.write_bytes(out);
+        self.lookup_values.write_bytes(out);
+        self.interaction_proofs_of_work.write_bytes(out);
+        self.oods_proof_of_work.write_bytes(out);
+        self.commitment_scheme_proof.write_bytes(out);
+    }
+
+    fn read_bytes(input: &mut &[u8]) -> Result<Self, DeserializationError> {
+        Ok(StarkProof {
+            commitments: TreeVec::read_bytes(input)?,
+            lookup_values: LookupValues::read_bytes(input)?,
+            interaction_proofs_of_work: Vec::read_bytes(input)?,
+            oods_proof_of_work: ProofOfWorkProof::read_bytes(input)?,
+            commitment_scheme_proof: CommitmentSchemeProof::read_bytes(input)?,
+        })
+    }
+}
+
+impl<H: MerkleHasher> StarkProof<H>
+where
+    H::Hash: Serializable,
+{
+    /// Encodes the proof, prefixed with [PROOF_FORMAT_VERSION].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = vec![PROOF_FORMAT_VERSION];
+        self.write_bytes(&mut out);
+        out
+    }
+
+    /// Decodes a proof produced by [Self::to_bytes]. The whole input must be consumed.
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, DeserializationError> {
+        let version = u8::read_bytes(&mut bytes)?;
+        if version != PROOF_FORMAT_VERSION {
+            return Err(DeserializationError::UnsupportedVersion(version));
+        }
+        let proof = Self::read_bytes(&mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(DeserializationError::TrailingBytes(bytes.len()));
+        }
+        Ok(proof)
+    }
+
+    /// Reports the number of bytes each section of the proof takes in [Self::to_bytes].
+    pub fn size_breakdown(&self) -> ProofSizeBreakdown {
+        let CommitmentSchemeProof {
+            config,
+            sampled_values,
+            decommitments,
+            queried_values,
+            proof_of_work,
+            fri_proof,
+        } = &self.commitment_scheme_proof;
+        let sampled_values = sampled_values.iter().map(encoded_len).collect_vec();
+        let queried_values = queried_values.iter().map(encoded_len).collect_vec();
+        let decommitment_hashes = decommitments
+            .iter()
+            .map(|decommitment| encoded_len(&decommitment.hash_witness))
+            .collect_vec();
+        let decommitment_values = decommitments
+            .iter()
+            .map(|decommitment| encoded_len(&decommitment.column_witness))
+            .collect_vec();
+        let fri_inner_layers = fri_proof.inner_layers.iter().map(encoded_len).collect_vec();
+        // Version byte, config, and the length prefixes of the per-tree and FRI layer vectors.
+        let metadata = 1 + encoded_len(config) + 4 * encoded_len(&0usize);
+
+        ProofSizeBreakdown {
+            metadata,
+            commitments: encoded_len(&self.commitments),
+            lookup_values: encoded_len(&self.lookup_values),
+            sampled_values,
+            queried_values,
+            decommitment_hashes,
+            decommitment_values,
+            fri_inner_layers,
+            fri_last_layer: encoded_len(&fri_proof.last_layer_poly),
+            proof_of_work: encoded_len(&self.interaction_proofs_of_work)
+                + encoded_len(&self.oods_proof_of_work)
+                + encoded_len(proof_of_work),
+        }
+    }
+}
+
+fn encoded_len(value: &impl Serializable) -> usize {
+    let mut out = Vec::new();
+    value.write_bytes(&mut out);
+    out.len()
+}
+
+/// Number of bytes each section of a [StarkProof] takes in its binary encoding.
+///
+/// Per-tree sections are indexed like the commitment trees. Length prefixes are counted with
+/// the section they belong to, so [Self::total] equals the length of [StarkProof::to_bytes].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProofSizeBreakdown {
+    /// Format version, PCS config and the length prefixes of the per-tree sections.
+    pub metadata: usize,
+    pub commitments: usize,
+    pub lookup_values: usize,
+    /// OODS sampled values.
+    pub sampled_values: Vec<usize>,
+    pub queried_values: Vec<usize>,
+    /// Merkle decommitment hashes.
+    pub decommitment_hashes: Vec<usize>,
+    /// Column values in Merkle decommitments that the verifier cannot deduce.
+    pub decommitment_values: Vec<usize>,
+    /// FRI inner layers, including their commitments and decommitments.
+    pub fri_inner_layers: Vec<usize>,
+    pub fri_last_layer: usize,
+    /// Proof of work nonces of all stages.
+    pub proof_of_work: usize,
+}
+
+impl ProofSizeBreakdown {
+    pub fn total(&self) -> usize {
+        self.metadata
+            + self.commitments
+            + self.lookup_values
+            + self.sampled_values.iter().sum::<usize>()
+            + self.queried_values.iter().sum::<usize>()
+            + self.decommitment_hashes.iter().sum::<usize>()
+            + self.decommitment_values.iter().sum::<usize>()
+            + self.fri_inner_layers.iter().sum::<usize>()
+            + self.fri_last_layer
+            + self.proof_of_work
+    }
+}
+
+impl fmt::Display for ProofSizeBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sum = |sizes: &[usize]| sizes.iter().sum::<usize>();
+        writeln!(f, "metadata: {}", self.metadata)?;
+        writeln!(f, "commitments: {}", self.commitments)?;
+        writeln!(f, "lookup values: {}", self.lookup_values)?;
+        for (name, sizes) in [
+            ("sampled values", &self.sampled_values),
+            ("queried values", &self.queried_values),
+            ("decommitment hashes", &self.decommitment_hashes),
+            ("decommitment values", &self.decommitment_values),
+            ("fri inner layers", &self.fri_inner_layers),
+        ] {
+            writeln!(f, "{name}: {} {sizes:?}", sum(sizes))?;
+        }
+        writeln!(f, "fri last layer: {}", self.fri_last_layer)?;
+        writeln!(f, "proof of work: {}", self.proof_of_work)?;
+        write!(f, "total: {}", self.total())
+    }
+}
+
+/// [StarkProof]s are (de)serialized with serde as their canonical binary encoding.
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use alloc::vec::Vec;
+    use core::fmt;
+    use core::marker::PhantomData;
+
+    use serde::de::{self, SeqAccess, Visitor};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::Serializable;
+    use crate::prover::StarkProof;
+    use crate::vcs::ops::MerkleHasher;
+
+    impl<H: MerkleHasher> Serialize for StarkProof<H>
+    where
+        H::Hash: Serializable,
+    {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(&self.to_bytes())
+        }
+    }
+
+    impl<'de, H: MerkleHasher> Deserialize<'de> for StarkProof<H>
+    where
+        H::Hash: Serializable,
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_bytes(ProofVisitor(PhantomData))
+        }
+    }
+
+    struct ProofVisitor<H>(PhantomData<H>);
+
+    impl<'de, H: MerkleHasher> Visitor<'de> for ProofVisitor<H>
+    where
+        H::Hash: Serializable,
+    {
+        type Value = StarkProof<H>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("an encoded stark proof")
+        }
+
+        fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+            StarkProof::from_bytes(bytes).map_err(E::custom)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            // The size hint comes from the input, so don't trust it for allocation.
+            let mut bytes = Vec::new();
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+            self.visit_bytes(&bytes)
+        }
+    }
+}
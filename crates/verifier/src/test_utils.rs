@@ -0,0 +1,9 @@
+use alloc::vec;
+
+use crate::channel::{Blake2sChannel, Channel};
+use crate::vcs::blake2_hash::Blake2sHash;
+
+pub fn test_channel() -> Blake2sChannel {
+    let seed = Blake2sHash::from(vec![0; 32]);
+    Blake2sChannel::new(seed)
+}
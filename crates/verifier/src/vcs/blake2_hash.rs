@@ -1,4 +1,5 @@
-use std::fmt;
+use alloc::vec::Vec;
+use core::fmt;
 
 use blake2::{Blake2s256, Digest};
 use bytemuck::{Pod, Zeroable};
@@ -59,7 +60,7 @@ impl fmt::Debug for Blake2sHash {
 }
 
 impl super::hasher::Name for Blake2sHash {
-    const NAME: std::borrow::Cow<'static, str> = std::borrow::Cow::Borrowed("BLAKE2");
+    const NAME: alloc::borrow::Cow<'static, str> = alloc::borrow::Cow::Borrowed("BLAKE2");
 }
 
 impl super::hasher::Hash<u8> for Blake2sHash {}
@@ -101,9 +102,11 @@ impl super::hasher::Hasher for Blake2sHasher {
 
 #[cfg(test)]
 mod tests {
+    use alloc::string::ToString;
+
     use super::Blake2sHasher;
-    use crate::core::vcs::blake2_hash;
-    use crate::core::vcs::hasher::Hasher;
+    use crate::vcs::blake2_hash;
+    use crate::vcs::hasher::Hasher;
 
     #[test]
     fn single_hash_test() {
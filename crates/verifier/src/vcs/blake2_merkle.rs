@@ -0,0 +1,41 @@
+use num_traits::Zero;
+
+use super::blake2_hash::Blake2sHash;
+use super::blake2s_ref::compress;
+use super::ops::MerkleHasher;
+use crate::channel::{Blake2sChannel, MerkleChannel};
+use crate::fields::m31::BaseField;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct Blake2sMerkleHasher;
+impl MerkleHasher for Blake2sMerkleHasher {
+    type Hash = Blake2sHash;
+
+    fn hash_node(children_hashes: &[Self::Hash], column_values: &[BaseField]) -> Self::Hash {
+        let mut state = [0; 8];
+        // Children are hashed in pairs, each pair filling a message block.
+        for pair in children_hashes.chunks(2) {
+            let mut block = [Blake2sHash::default(); 2];
+            block[..pair.len()].copy_from_slice(pair);
+            state = compress(state, unsafe { core::mem::transmute(block) }, 0, 0, 0, 0);
+        }
+        for chunk in column_values.chunks(16) {
+            let mut block = [BaseField::zero(); 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            state = compress(state, unsafe { core::mem::transmute(block) }, 0, 0, 0, 0);
+        }
+        let mut hash = [0; 32];
+        for (bytes, word) in hash.chunks_exact_mut(4).zip(state) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        hash[..].into()
+    }
+}
+
+#[derive(Default)]
+pub struct Blake2sMerkleChannel;
+
+impl MerkleChannel for Blake2sMerkleChannel {
+    type C = Blake2sChannel;
+    type H = Blake2sMerkleHasher;
+}
@@ -1,9 +1,13 @@
-use std::fmt;
+use alloc::vec::Vec;
+use core::fmt;
+
+use bytemuck::{Pod, Zeroable};
 
 use super::hasher::Name;
 
 // Wrapper for the blake3 hash type.
-#[derive(Clone, Copy, PartialEq, Default, Eq)]
+#[repr(C, align(32))]
+#[derive(Clone, Copy, PartialEq, Default, Eq, Pod, Zeroable)]
 pub struct Blake3Hash([u8; 32]);
 
 impl From<Blake3Hash> for Vec<u8> {
@@ -38,6 +42,12 @@ impl AsRef<[u8]> for Blake3Hash {
     }
 }
 
+impl From<Blake3Hash> for [u8; 32] {
+    fn from(val: Blake3Hash) -> Self {
+        val.0
+    }
+}
+
 impl fmt::Display for Blake3Hash {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(&hex::encode(self.0))
@@ -51,7 +61,7 @@ impl fmt::Debug for Blake3Hash {
 }
 
 impl Name for Blake3Hash {
-    const NAME: std::borrow::Cow<'static, str> = std::borrow::Cow::Borrowed("BLAKE3");
+    const NAME: alloc::borrow::Cow<'static, str> = alloc::borrow::Cow::Borrowed("BLAKE3");
 }
 
 impl super::hasher::Hash<u8> for Blake3Hash {}
@@ -95,8 +105,10 @@ impl super::hasher::Hasher for Blake3Hasher {
 
 #[cfg(test)]
 mod tests {
-    use crate::core::vcs::blake3_hash::Blake3Hasher;
-    use crate::core::vcs::hasher::Hasher;
+    use alloc::string::ToString;
+
+    use crate::vcs::blake3_hash::Blake3Hasher;
+    use crate::vcs::hasher::Hasher;
 
     #[test]
     fn single_hash_test() {
@@ -116,6 +128,6 @@ mod tests {
         let hash_empty = state.finalize();
 
         assert_eq!(hash.to_string(), Blake3Hasher::hash(b"ab").to_string());
-        assert_eq!(hash_empty.to_string(), Blake3Hasher::hash(b"").to_string())
+        assert_eq!(hash_empty.to_string(), Blake3Hasher::hash(b"").to_string());
     }
 }
@@ -0,0 +1,30 @@
+use super::blake3_hash::Blake3Hash;
+use super::ops::MerkleHasher;
+use crate::channel::{Blake3Channel, MerkleChannel};
+use crate::fields::m31::BaseField;
+use crate::fields::IntoSlice;
+
+/// Hashes a node as BLAKE3 of the children hashes (if any), followed by the little endian bytes
+/// of the column values.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct Blake3MerkleHasher;
+impl MerkleHasher for Blake3MerkleHasher {
+    type Hash = Blake3Hash;
+
+    fn hash_node(children_hashes: &[Self::Hash], column_values: &[BaseField]) -> Self::Hash {
+        let mut hasher = blake3::Hasher::new();
+        for child in children_hashes {
+            hasher.update(child.as_ref());
+        }
+        hasher.update(IntoSlice::<u8>::into_slice(column_values));
+        hasher.finalize().as_bytes()[..].into()
+    }
+}
+
+#[derive(Default)]
+pub struct Blake3MerkleChannel;
+
+impl MerkleChannel for Blake3MerkleChannel {
+    type C = Blake3Channel;
+    type H = Blake3MerkleHasher;
+}
@@ -1,5 +1,6 @@
-use std::borrow::Cow;
-use std::fmt::{Debug, Display};
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Display};
 
 pub trait Name {
     const NAME: Cow<'static, str>;
@@ -11,15 +12,15 @@ pub trait Name {
 /// # Example
 ///
 /// ```
-/// use stwo_prover::core::vcs::blake3_hash::Blake3Hasher;
-/// use stwo_prover::core::vcs::hasher::Hasher;
+/// use stwo_verifier::vcs::blake2_hash::Blake2sHasher;
+/// use stwo_verifier::vcs::hasher::Hasher;
 ///
-/// let mut hasher = Blake3Hasher::new();
+/// let mut hasher = Blake2sHasher::new();
 /// hasher.update(&[1, 2, 3]);
 /// hasher.update(&[4, 5, 6]);
 /// let hash = hasher.finalize();
 ///
-/// assert_eq!(hash, Blake3Hasher::hash(&[1, 2, 3, 4, 5, 6]));
+/// assert_eq!(hash, Blake2sHasher::hash(&[1, 2, 3, 4, 5, 6]));
 /// ```
 
 pub trait Hasher: Sized + Default {
@@ -0,0 +1,14 @@
+//! Vector commitment scheme (VCS) module.
+
+pub mod blake2_hash;
+pub mod blake2_merkle;
+pub mod blake2s_ref;
+pub mod blake3_hash;
+pub mod blake3_merkle;
+pub mod hasher;
+pub mod ops;
+pub mod poseidon252_merkle;
+pub mod poseidon2_m31;
+pub mod poseidon2_m31_merkle;
+pub mod utils;
+pub mod verifier;
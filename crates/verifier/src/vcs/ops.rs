@@ -0,0 +1,18 @@
+use core::fmt::Debug;
+
+use crate::fields::m31::BaseField;
+
+/// A Merkle node hash is a hash of:
+///   [child0_hash, child1_hash, ...], column0_values, column1_values, ...
+/// "[]" denotes optional values.
+/// The largest Merkle layer has no children hashes. The rest of the layers have the hashes of
+/// their children, 2 of them in a binary tree, and more in trees of higher arity (see
+/// [MerkleConfig](super::verifier::MerkleConfig)).
+/// At each layer, the tree may have multiple columns. Each node in that layer contains the values
+/// of its rows from each column: a single value in columns of the same length as the layer, and
+/// several consecutive values in longer columns.
+pub trait MerkleHasher: Debug {
+    type Hash: Copy + Clone + Eq + core::fmt::Debug;
+    /// Hashes a single Merkle node. See [MerkleHasher] for more details.
+    fn hash_node(children_hashes: &[Self::Hash], column_values: &[BaseField]) -> Self::Hash;
+}
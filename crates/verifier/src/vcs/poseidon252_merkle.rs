@@ -0,0 +1,74 @@
+use alloc::vec::Vec;
+
+use num_traits::Zero;
+use starknet_crypto::poseidon_hash_many;
+use starknet_ff::FieldElement as FieldElement252;
+
+use super::ops::MerkleHasher;
+use crate::channel::{MerkleChannel, Poseidon252Channel};
+use crate::fields::m31::BaseField;
+
+const ELEMENTS_IN_BLOCK: usize = 8;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct Poseidon252MerkleHasher;
+impl MerkleHasher for Poseidon252MerkleHasher {
+    type Hash = FieldElement252;
+
+    fn hash_node(children_hashes: &[Self::Hash], column_values: &[BaseField]) -> Self::Hash {
+        let n_column_blocks = column_values.len().div_ceil(ELEMENTS_IN_BLOCK);
+        let values_len = children_hashes.len() + n_column_blocks;
+        let mut values = Vec::with_capacity(values_len);
+        values.extend_from_slice(children_hashes);
+
+        for chunk in column_values.chunks(ELEMENTS_IN_BLOCK) {
+            let mut block = [BaseField::zero(); ELEMENTS_IN_BLOCK];
+            block[..chunk.len()].copy_from_slice(chunk);
+            let mut word = FieldElement252::default();
+            for x in block {
+                word = word * FieldElement252::from(2u64.pow(31)) + FieldElement252::from(x.0);
+            }
+            values.push(word);
+        }
+        poseidon_hash_many(&values)
+    }
+}
+
+#[derive(Default)]
+pub struct Poseidon252MerkleChannel;
+
+impl MerkleChannel for Poseidon252MerkleChannel {
+    type C = Poseidon252Channel;
+    type H = Poseidon252MerkleHasher;
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet_ff::FieldElement as FieldElement252;
+
+    use crate::m31;
+    use crate::vcs::ops::MerkleHasher;
+    use crate::vcs::poseidon252_merkle::Poseidon252MerkleHasher;
+
+    #[test]
+    fn test_vector() {
+        assert_eq!(
+            Poseidon252MerkleHasher::hash_node(&[], &[m31!(0), m31!(1)]),
+            FieldElement252::from_dec_str(
+                "2552053700073128806553921687214114320458351061521275103654266875084493044716"
+            )
+            .unwrap()
+        );
+
+        assert_eq!(
+            Poseidon252MerkleHasher::hash_node(
+                &[FieldElement252::from(1u32), FieldElement252::from(2u32)],
+                &[m31!(3)]
+            ),
+            FieldElement252::from_dec_str(
+                "159358216886023795422515519110998391754567506678525778721401012606792642769"
+            )
+            .unwrap()
+        );
+    }
+}
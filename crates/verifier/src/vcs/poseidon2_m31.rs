@@ -0,0 +1,713 @@
+//! The Poseidon2 permutation over M31 with a state of 16 elements, and a sponge hash built on it.
+//! See <https://eprint.iacr.org/2023/323.pdf>.
+//!
+//! The round functions are generic over the state element type, so that the permutation can be
+//! computed on field elements, on packed SIMD vectors and on AIR expressions alike.
+//!
+//! # Parameters
+//!
+//! This is not a published Poseidon2-M31 instance, so hashes are not compatible with other
+//! implementations, and the known-answer tests are regression vectors of this implementation.
+//! * The S-box is `x^5`, a permutation of M31 as `gcd(5, p - 1) = 1`.
+//! * There are 8 full and 14 partial rounds, the round numbers commonly used for 128 bits of
+//!   security with a 31-bit prime field, a state of 16 elements and `x^5`. They are not re-derived
+//!   here.
+//! * The round constants are derived from `blake2s` with a fixed domain separator, see
+//!   [EXTERNAL_ROUND_CONSTS].
+//! * The external round matrix is the one of section 5.1 of the paper, built from its MDS matrix
+//!   M4.
+//! * The internal round matrix is `1 + diag(mu)` (section 5.2), with `mu` in
+//!   [INTERNAL_ROUND_MATRIX_DIAGONAL] made of small powers of two. Its choice is checked against
+//!   the condition of section 5.3 in the tests: the characteristic polynomials of its first 32
+//!   powers are irreducible, so that no power has an invariant subspace.
+
+use core::ops::{Add, AddAssign, Mul, Sub};
+
+use num_traits::{One, Zero};
+
+use crate::fields::m31::{BaseField, P};
+use crate::fields::FieldExpOps;
+use crate::m31;
+use crate::math::matrix::SquareMatrix;
+
+pub const N_STATE: usize = 16;
+pub const N_HALF_FULL_ROUNDS: usize = 4;
+pub const N_FULL_ROUNDS: usize = 2 * N_HALF_FULL_ROUNDS;
+pub const N_PARTIAL_ROUNDS: usize = 14;
+/// Number of state elements absorbed per permutation. The rest of the state is the capacity.
+pub const RATE: usize = 8;
+/// Number of elements in a [Poseidon2M31Hash].
+pub const N_HASH_FELTS: usize = 8;
+
+/// Round constants. Constant number `k`, counting the external round constants first, is the
+/// first 8 bytes of `blake2s("Poseidon2M31" || k as u32)` as a little endian integer, reduced
+/// modulo P.
+pub const EXTERNAL_ROUND_CONSTS: [[BaseField; N_STATE]; N_FULL_ROUNDS] = [
+    [
+        m31!(553884893),
+        m31!(334281991),
+        m31!(1392259146),
+        m31!(154035504),
+        m31!(289123265),
+        m31!(1895305675),
+        m31!(686084592),
+        m31!(714709510),
+        m31!(987184168),
+        m31!(2126014465),
+        m31!(1022221050),
+        m31!(2059323228),
+        m31!(1029919191),
+        m31!(889003249),
+        m31!(1646621272),
+        m31!(764131941),
+    ],
+    [
+        m31!(1995238610),
+        m31!(1845764997),
+        m31!(1039665001),
+        m31!(116217498),
+        m31!(1008045554),
+        m31!(1300753195),
+        m31!(1238756499),
+        m31!(1321264639),
+        m31!(242960369),
+        m31!(825518337),
+        m31!(169107508),
+        m31!(535214334),
+        m31!(395409734),
+        m31!(1383327142),
+        m31!(1803211350),
+        m31!(430413629),
+    ],
+    [
+        m31!(1576060257),
+        m31!(1501964864),
+        m31!(1392750566),
+        m31!(642083754),
+        m31!(352284828),
+        m31!(1929380932),
+        m31!(2086952522),
+        m31!(1663341180),
+        m31!(941985939),
+        m31!(1025267518),
+        m31!(1085075936),
+        m31!(1777157219),
+        m31!(1469758368),
+        m31!(912497598),
+        m31!(661716910),
+        m31!(411429727),
+    ],
+    [
+        m31!(1258309482),
+        m31!(970165815),
+        m31!(760936287),
+        m31!(1395778411),
+        m31!(2099404739),
+        m31!(333345126),
+        m31!(725449567),
+        m31!(1969211630),
+        m31!(1881941586),
+        m31!(428994978),
+        m31!(1306579701),
+        m31!(804018040),
+        m31!(921421251),
+        m31!(2137795330),
+        m31!(621648221),
+        m31!(29725216),
+    ],
+    [
+        m31!(1757211806),
+        m31!(2142606355),
+        m31!(1250274697),
+        m31!(281671003),
+        m31!(295605928),
+        m31!(776353577),
+        m31!(1921238948),
+        m31!(633787012),
+        m31!(1894865878),
+        m31!(536356082),
+        m31!(1982595678),
+        m31!(864166385),
+        m31!(1491936977),
+        m31!(2070467691),
+        m31!(1299758151),
+        m31!(1825309954),
+    ],
+    [
+        m31!(2001705895),
+        m31!(2081354362),
+        m31!(1600815252),
+        m31!(1750533291),
+        m31!(1406725952),
+        m31!(1768622565),
+        m31!(1366981632),
+        m31!(1080590189),
+        m31!(1371299252),
+        m31!(1926701470),
+        m31!(1858492814),
+        m31!(1376323635),
+        m31!(16106331),
+        m31!(1429993705),
+        m31!(323314360),
+        m31!(533846282),
+    ],
+    [
+        m31!(182930589),
+        m31!(514179741),
+        m31!(381724094),
+        m31!(614271473),
+        m31!(1794951072),
+        m31!(292454494),
+        m31!(1213798597),
+        m31!(1937229843),
+        m31!(1986140026),
+        m31!(1785783701),
+        m31!(347772352),
+        m31!(1674126754),
+        m31!(1011888900),
+        m31!(674555973),
+        m31!(333751031),
+        m31!(647895217),
+    ],
+    [
+        m31!(894763276),
+        m31!(1610911003),
+        m31!(2051555095),
+        m31!(436276181),
+        m31!(653042988),
+        m31!(1336459104),
+        m31!(1231428134),
+        m31!(1529223764),
+        m31!(731060051),
+        m31!(361324391),
+        m31!(1998072487),
+        m31!(1488817665),
+        m31!(1745196457),
+        m31!(1008666549),
+        m31!(2147219863),
+        m31!(679970754),
+    ],
+];
+pub const INTERNAL_ROUND_CONSTS: [BaseField; N_PARTIAL_ROUNDS] = [
+    m31!(517955332),
+    m31!(1095127007),
+    m31!(1872147143),
+    m31!(2007268766),
+    m31!(1978839276),
+    m31!(1538605490),
+    m31!(1990483232),
+    m31!(1327063122),
+    m31!(1317178046),
+    m31!(1262417466),
+    m31!(85181330),
+    m31!(1817164122),
+    m31!(1120382501),
+    m31!(453563488),
+];
+
+/// The diagonal `mu` of the internal round matrix `1 + diag(mu)`: `-2` followed by powers of two,
+/// so that the products are cheap.
+pub const INTERNAL_ROUND_MATRIX_DIAGONAL: [BaseField; N_STATE] = [
+    m31!(P - 2),
+    m31!(1 << 0),
+    m31!(1 << 1),
+    m31!(1 << 2),
+    m31!(1 << 3),
+    m31!(1 << 4),
+    m31!(1 << 5),
+    m31!(1 << 6),
+    m31!(1 << 7),
+    m31!(1 << 8),
+    m31!(1 << 10),
+    m31!(1 << 12),
+    m31!(1 << 13),
+    m31!(1 << 14),
+    m31!(1 << 15),
+    m31!(1 << 16),
+];
+
+/// The 4x4 MDS matrix M4 from section 5.1 of the paper.
+const M4: [[u32; 4]; 4] = [[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+
+/// A Poseidon2 hash: the first [N_HASH_FELTS] elements of the sponge state.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Poseidon2M31Hash(pub [BaseField; N_HASH_FELTS]);
+
+/// Hashes `input` with a [Poseidon2M31Sponge].
+pub fn hash(input: &[BaseField]) -> Poseidon2M31Hash {
+    let mut sponge = Poseidon2M31Sponge::new(input.len());
+    input.iter().for_each(|value| sponge.absorb(*value));
+    Poseidon2M31Hash(sponge.finalize())
+}
+
+/// A sponge over [permute], absorbing [RATE] elements per permutation. The capacity is
+/// initialized with the input length, and the last block is zero padded.
+///
+/// Generic over the state element type, so that inputs of the same length can be hashed on packed
+/// elements.
+#[derive(Clone, Debug)]
+pub struct Poseidon2M31Sponge<F> {
+    state: [F; N_STATE],
+    input_len: usize,
+    n_absorbed: usize,
+}
+
+impl<F> Poseidon2M31Sponge<F>
+where
+    F: FieldExpOps
+        + AddAssign<F>
+        + Add<F, Output = F>
+        + Sub<F, Output = F>
+        + Mul<BaseField, Output = F>
+        + AddAssign<BaseField>
+        + From<BaseField>,
+{
+    /// Starts hashing an input of `input_len` elements.
+    pub fn new(input_len: usize) -> Self {
+        let mut state = [F::from(BaseField::zero()); N_STATE];
+        state[RATE] = F::from(BaseField::from(input_len));
+        Self {
+            state,
+            input_len,
+            n_absorbed: 0,
+        }
+    }
+
+    pub fn absorb(&mut self, value: F) {
+        assert!(self.n_absorbed < self.input_len, "Input is too long");
+        self.state[self.n_absorbed % RATE] += value;
+        self.n_absorbed += 1;
+        if self.n_absorbed % RATE == 0 {
+            permute(&mut self.state);
+        }
+    }
+
+    pub fn finalize(mut self) -> [F; N_HASH_FELTS] {
+        assert_eq!(self.n_absorbed, self.input_len, "Input is too short");
+        if self.n_absorbed == 0 || self.n_absorbed % RATE != 0 {
+            permute(&mut self.state);
+        }
+        self.state[..N_HASH_FELTS].try_into().unwrap()
+    }
+}
+
+/// Applies the Poseidon2 permutation: an external linear layer, followed by
+/// [N_HALF_FULL_ROUNDS] full rounds, [N_PARTIAL_ROUNDS] partial rounds and [N_HALF_FULL_ROUNDS]
+/// full rounds.
+pub fn permute<F>(state: &mut [F; N_STATE])
+where
+    F: FieldExpOps
+        + AddAssign<F>
+        + Add<F, Output = F>
+        + Sub<F, Output = F>
+        + Mul<BaseField, Output = F>
+        + AddAssign<BaseField>,
+{
+    apply_external_round_matrix(state);
+    for round_consts in &EXTERNAL_ROUND_CONSTS[..N_HALF_FULL_ROUNDS] {
+        apply_full_round(state, round_consts);
+    }
+    for round_const in INTERNAL_ROUND_CONSTS {
+        state[0] += round_const;
+        state[0] = pow5(state[0]);
+        apply_internal_round_matrix(state);
+    }
+    for round_consts in &EXTERNAL_ROUND_CONSTS[N_HALF_FULL_ROUNDS..] {
+        apply_full_round(state, round_consts);
+    }
+}
+
+fn apply_full_round<F>(state: &mut [F; N_STATE], round_consts: &[BaseField; N_STATE])
+where
+    F: FieldExpOps
+        + AddAssign<F>
+        + Add<F, Output = F>
+        + Sub<F, Output = F>
+        + Mul<BaseField, Output = F>
+        + AddAssign<BaseField>,
+{
+    for (s, round_const) in state.iter_mut().zip(round_consts) {
+        *s += *round_const;
+        *s = pow5(*s);
+    }
+    apply_external_round_matrix(state);
+}
+
+#[inline(always)]
+/// Applies the M4 MDS matrix described in <https://eprint.iacr.org/2023/323.pdf> 5.1.
+pub fn apply_m4<F>(x: [F; 4]) -> [F; 4]
+where
+    F: Copy + AddAssign<F> + Add<F, Output = F> + Sub<F, Output = F> + Mul<BaseField, Output = F>,
+{
+    let t0 = x[0] + x[1];
+    let t02 = t0 + t0;
+    let t1 = x[2] + x[3];
+    let t12 = t1 + t1;
+    let t2 = x[1] + x[1] + t1;
+    let t3 = x[3] + x[3] + t0;
+    let t4 = t12 + t12 + t3;
+    let t5 = t02 + t02 + t2;
+    let t6 = t3 + t5;
+    let t7 = t2 + t4;
+    [t6, t5, t7, t4]
+}
+
+/// Applies the external round matrix.
+/// See <https://eprint.iacr.org/2023/323.pdf> 5.1 and Appendix B.
+pub fn apply_external_round_matrix<F>(state: &mut [F; N_STATE])
+where
+    F: Copy + AddAssign<F> + Add<F, Output = F> + Sub<F, Output = F> + Mul<BaseField, Output = F>,
+{
+    // Applies circ(2M4, M4, M4, M4).
+    for i in 0..4 {
+        [
+            state[4 * i],
+            state[4 * i + 1],
+            state[4 * i + 2],
+            state[4 * i + 3],
+        ] = apply_m4([
+            state[4 * i],
+            state[4 * i + 1],
+            state[4 * i + 2],
+            state[4 * i + 3],
+        ]);
+    }
+    for j in 0..4 {
+        let s = state[j] + state[j + 4] + state[j + 8] + state[j + 12];
+        for i in 0..4 {
+            state[4 * i + j] += s;
+        }
+    }
+}
+
+/// Applies the internal round matrix `1 + diag(mu)`, with `mu` the
+/// [INTERNAL_ROUND_MATRIX_DIAGONAL].
+/// See <https://eprint.iacr.org/2023/323.pdf> 5.2.
+pub fn apply_internal_round_matrix<F>(state: &mut [F; N_STATE])
+where
+    F: Copy + AddAssign<F> + Add<F, Output = F> + Sub<F, Output = F> + Mul<BaseField, Output = F>,
+{
+    let sum = state[1..].iter().fold(state[0], |acc, s| acc + *s);
+    state.iter_mut().enumerate().for_each(|(i, s)| {
+        // TODO(spapini): Change to rotations.
+        *s = *s * INTERNAL_ROUND_MATRIX_DIAGONAL[i] + sum;
+    });
+}
+
+pub fn pow5<F: FieldExpOps>(x: F) -> F {
+    let x2 = x * x;
+    let x4 = x2 * x2;
+    x4 * x
+}
+
+/// The matrix applied by [apply_external_round_matrix].
+pub struct ExternalRoundMatrix;
+
+impl SquareMatrix<BaseField, N_STATE> for ExternalRoundMatrix {
+    fn get_at(&self, i: usize, j: usize) -> BaseField {
+        let block_factor = if i / 4 == j / 4 { 2 } else { 1 };
+        BaseField::from(M4[i % 4][j % 4] * block_factor)
+    }
+}
+
+/// The matrix applied by [apply_internal_round_matrix].
+pub struct InternalRoundMatrix;
+
+impl SquareMatrix<BaseField, N_STATE> for InternalRoundMatrix {
+    fn get_at(&self, i: usize, j: usize) -> BaseField {
+        let diagonal = if i == j {
+            INTERNAL_ROUND_MATRIX_DIAGONAL[i]
+        } else {
+            BaseField::zero()
+        };
+        diagonal + BaseField::one()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::array;
+
+    use blake2::{Blake2s256, Digest};
+    use num_traits::{One, Zero};
+
+    use super::{
+        apply_external_round_matrix, apply_internal_round_matrix, apply_m4, hash, permute,
+        ExternalRoundMatrix, InternalRoundMatrix, EXTERNAL_ROUND_CONSTS, INTERNAL_ROUND_CONSTS,
+        N_FULL_ROUNDS, N_PARTIAL_ROUNDS, N_STATE,
+    };
+    use crate::fields::m31::{BaseField, P};
+    use crate::fields::FieldExpOps;
+    use crate::m31;
+    use crate::math::matrix::{RowMajorMatrix, SquareMatrix};
+
+    #[test]
+    fn test_apply_m4() {
+        let m4 = RowMajorMatrix::<BaseField, 4>::new(
+            [5, 7, 1, 3, 4, 6, 1, 1, 1, 3, 5, 7, 1, 1, 4, 6]
+                .map(BaseField::from_u32_unchecked)
+                .to_vec(),
+        );
+        let state = array::from_fn(|i| BaseField::from_u32_unchecked(i as u32));
+
+        assert_eq!(apply_m4(state), m4.mul(state));
+    }
+
+    #[test]
+    fn test_apply_external_round_matrix() {
+        let mut state: [BaseField; N_STATE] = array::from_fn(|i| m31!(i as u32 * 5 + 91));
+        let expected_state = ExternalRoundMatrix.mul(state);
+
+        apply_external_round_matrix(&mut state);
+
+        assert_eq!(state, expected_state);
+    }
+
+    #[test]
+    fn test_apply_internal_round_matrix() {
+        let mut state: [BaseField; N_STATE] = array::from_fn(|i| m31!(i as u32 * 3 + 187));
+        let expected_state = InternalRoundMatrix.mul(state);
+
+        apply_internal_round_matrix(&mut state);
+
+        assert_eq!(state, expected_state);
+    }
+
+    /// Checks the condition of section 5.3 of the paper against subspace trails: the
+    /// characteristic polynomials of the powers of the internal round matrix are irreducible, and
+    /// thus equal to their minimal polynomials, of maximal degree. Then no power has an invariant
+    /// subspace.
+    #[test]
+    fn test_internal_round_matrix_powers_have_irreducible_characteristic_polynomials() {
+        for power in 1..=2 * N_STATE {
+            let apply_power = |state: &mut [BaseField; N_STATE]| {
+                (0..power).for_each(|_| apply_internal_round_matrix(state))
+            };
+            let char_poly = characteristic_polynomial(apply_power)
+                .unwrap_or_else(|| panic!("e_0 is not cyclic for power {power}"));
+            assert!(is_irreducible(&char_poly), "power {power}");
+        }
+    }
+
+    #[test]
+    fn test_sbox_is_permutation() {
+        assert_ne!((P - 1) % 5, 0);
+    }
+
+    #[test]
+    fn test_is_irreducible() {
+        // 3 is not a square modulo P, and `x^4 - 4x^2 + 5` is the minimal polynomial of the
+        // generator of QM31 over M31.
+        assert!(is_irreducible(&[m31!(P - 3), m31!(0), m31!(1)]));
+        assert!(!is_irreducible(&[m31!(P - 4), m31!(0), m31!(1)]));
+        assert!(is_irreducible(&[
+            m31!(5),
+            m31!(0),
+            m31!(P - 4),
+            m31!(0),
+            m31!(1)
+        ]));
+        assert!(!is_irreducible(&[
+            m31!(6),
+            m31!(0),
+            m31!(P - 5),
+            m31!(0),
+            m31!(1)
+        ]));
+    }
+
+    /// Returns the characteristic polynomial of the matrix applied by `apply`, as its coefficients
+    /// from the lowest degree, if the Krylov subspace of `e_0` is the whole space.
+    fn characteristic_polynomial(
+        apply: impl Fn(&mut [BaseField; N_STATE]),
+    ) -> Option<Vec<BaseField>> {
+        // The columns of `krylov` are `e_0, M e_0, ..., M^N e_0`.
+        let mut krylov = [[BaseField::zero(); N_STATE + 1]; N_STATE];
+        let mut v: [BaseField; N_STATE] = array::from_fn(|i| BaseField::from(u32::from(i == 0)));
+        for j in 0..=N_STATE {
+            (0..N_STATE).for_each(|i| krylov[i][j] = v[i]);
+            apply(&mut v);
+        }
+
+        // Solve `sum_j c_j M^j e_0 = M^N e_0` by Gaussian elimination.
+        for col in 0..N_STATE {
+            let pivot = (col..N_STATE).find(|&row| !krylov[row][col].is_zero())?;
+            krylov.swap(col, pivot);
+            let inv = krylov[col][col].inverse();
+            krylov[col].iter_mut().for_each(|x| *x *= inv);
+            for row in (0..N_STATE).filter(|&row| row != col) {
+                let factor = krylov[row][col];
+                for k in 0..=N_STATE {
+                    let x = krylov[col][k];
+                    krylov[row][k] -= factor * x;
+                }
+            }
+        }
+        let mut char_poly = krylov.iter().map(|row| -row[N_STATE]).collect::<Vec<_>>();
+        char_poly.push(BaseField::one());
+        Some(char_poly)
+    }
+
+    /// Rabin's irreducibility test for a polynomial `f` of degree `n`, a power of two: `f` is
+    /// irreducible iff `f | x^(p^n) - x` and `gcd(f, x^(p^(n/2)) - x) = 1`.
+    fn is_irreducible(f: &[BaseField]) -> bool {
+        let n = f.len() - 1;
+        assert!(n.is_power_of_two());
+        let x = [BaseField::zero(), BaseField::one()];
+        let mut frobenius = poly_rem(&x, f);
+        let mut half_frobenius = Vec::new();
+        for i in 1..=n {
+            frobenius = poly_pow_mod(&frobenius, P, f);
+            if i == n / 2 {
+                half_frobenius = frobenius.clone();
+            }
+        }
+        half_frobenius[1] -= BaseField::one();
+        frobenius == poly_rem(&x, f) && poly_gcd(half_frobenius, f.to_vec()).len() == 1
+    }
+
+    fn poly_trim(mut a: Vec<BaseField>) -> Vec<BaseField> {
+        while a.last().is_some_and(|c| c.is_zero()) {
+            a.pop();
+        }
+        a
+    }
+
+    /// Returns `a mod f`, padded to the degree of `f`.
+    fn poly_rem(a: &[BaseField], f: &[BaseField]) -> Vec<BaseField> {
+        let n = f.len() - 1;
+        let mut a = a.to_vec();
+        a.resize(a.len().max(n), BaseField::zero());
+        let lead_inv = f[n].inverse();
+        for i in (n..a.len()).rev() {
+            let factor = a[i] * lead_inv;
+            for (j, c) in f.iter().enumerate() {
+                a[i - n + j] -= factor * *c;
+            }
+        }
+        a.truncate(n);
+        a
+    }
+
+    fn poly_mul_mod(a: &[BaseField], b: &[BaseField], f: &[BaseField]) -> Vec<BaseField> {
+        let mut product = vec![BaseField::zero(); a.len() + b.len() - 1];
+        for (i, x) in a.iter().enumerate() {
+            for (j, y) in b.iter().enumerate() {
+                product[i + j] += *x * *y;
+            }
+        }
+        poly_rem(&product, f)
+    }
+
+    fn poly_pow_mod(a: &[BaseField], mut exp: u32, f: &[BaseField]) -> Vec<BaseField> {
+        let mut result = poly_rem(&[BaseField::one()], f);
+        let mut base = a.to_vec();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = poly_mul_mod(&result, &base, f);
+            }
+            base = poly_mul_mod(&base, &base, f);
+            exp >>= 1;
+        }
+        result
+    }
+
+    fn poly_gcd(mut a: Vec<BaseField>, mut b: Vec<BaseField>) -> Vec<BaseField> {
+        a = poly_trim(a);
+        b = poly_trim(b);
+        while !b.is_empty() {
+            let r = poly_trim(poly_rem(&a, &b));
+            a = b;
+            b = r;
+        }
+        a
+    }
+
+    #[test]
+    fn test_round_constants_derivation() {
+        let consts = EXTERNAL_ROUND_CONSTS
+            .iter()
+            .flatten()
+            .chain(&INTERNAL_ROUND_CONSTS)
+            .collect::<Vec<_>>();
+        assert_eq!(consts.len(), N_FULL_ROUNDS * N_STATE + N_PARTIAL_ROUNDS);
+
+        for (k, round_const) in consts.into_iter().enumerate() {
+            let digest = Blake2s256::new()
+                .chain_update(b"Poseidon2M31")
+                .chain_update((k as u32).to_le_bytes())
+                .finalize();
+            let value = u64::from_le_bytes(digest[..8].try_into().unwrap()) % P as u64;
+            assert_eq!(round_const.0 as u64, value, "constant {k}");
+        }
+    }
+
+    #[test]
+    fn test_permute_known_answer() {
+        let mut state: [BaseField; N_STATE] = array::from_fn(|i| m31!(i as u32));
+
+        permute(&mut state);
+
+        assert_eq!(
+            state,
+            [
+                m31!(2068640410),
+                m31!(1988653175),
+                m31!(467027325),
+                m31!(2031697007),
+                m31!(2004601869),
+                m31!(2133920822),
+                m31!(555385429),
+                m31!(2038228149),
+                m31!(1642871212),
+                m31!(305659058),
+                m31!(457150868),
+                m31!(382457270),
+                m31!(2128056297),
+                m31!(417435021),
+                m31!(559544426),
+                m31!(1453146838),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hash_known_answer() {
+        let input = (0..20).map(|i| m31!(i)).collect::<Vec<_>>();
+
+        assert_eq!(
+            hash(&[]).0,
+            [
+                m31!(1982492044),
+                m31!(255396377),
+                m31!(676606377),
+                m31!(899011789),
+                m31!(1940771555),
+                m31!(1319392541),
+                m31!(124423748),
+                m31!(538802157),
+            ]
+        );
+        assert_eq!(
+            hash(&input).0,
+            [
+                m31!(2052302061),
+                m31!(1070637343),
+                m31!(1753360879),
+                m31!(1181488162),
+                m31!(1787742973),
+                m31!(1030682370),
+                m31!(1783491540),
+                m31!(1907369309),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hash_is_length_separated() {
+        assert_ne!(hash(&[]), hash(&[m31!(0)]));
+        assert_ne!(hash(&[m31!(1)]), hash(&[m31!(1), m31!(0)]));
+    }
+}
@@ -0,0 +1,73 @@
+use super::ops::MerkleHasher;
+use super::poseidon2_m31::{Poseidon2M31Hash, Poseidon2M31Sponge, N_HASH_FELTS};
+use crate::channel::{MerkleChannel, Poseidon2M31Channel};
+use crate::fields::m31::BaseField;
+
+/// Hashes a node with the Poseidon2 sponge, over the elements of the children hashes (if any)
+/// followed by the column values. All operations are over M31, so nodes are cheap to hash in an
+/// AIR.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct Poseidon2M31MerkleHasher;
+impl MerkleHasher for Poseidon2M31MerkleHasher {
+    type Hash = Poseidon2M31Hash;
+
+    fn hash_node(children_hashes: &[Self::Hash], column_values: &[BaseField]) -> Self::Hash {
+        let children_len = children_hashes.len() * N_HASH_FELTS;
+        let mut sponge = Poseidon2M31Sponge::new(children_len + column_values.len());
+        for child in children_hashes {
+            child.0.into_iter().for_each(|value| sponge.absorb(value));
+        }
+        column_values.iter().for_each(|value| sponge.absorb(*value));
+        Poseidon2M31Hash(sponge.finalize())
+    }
+}
+
+#[derive(Default)]
+pub struct Poseidon2M31MerkleChannel;
+
+impl MerkleChannel for Poseidon2M31MerkleChannel {
+    type C = Poseidon2M31Channel;
+    type H = Poseidon2M31MerkleHasher;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::m31;
+    use crate::vcs::ops::MerkleHasher;
+    use crate::vcs::poseidon2_m31::Poseidon2M31Hash;
+    use crate::vcs::poseidon2_m31_merkle::Poseidon2M31MerkleHasher;
+
+    #[test]
+    fn test_hash_node_known_answer() {
+        let left = Poseidon2M31Hash([m31!(1); 8]);
+        let right = Poseidon2M31Hash([m31!(2); 8]);
+        let values = [m31!(3), m31!(4), m31!(5)];
+
+        assert_eq!(
+            Poseidon2M31MerkleHasher::hash_node(&[], &values).0,
+            [
+                m31!(914729097),
+                m31!(1686542702),
+                m31!(23438788),
+                m31!(105857251),
+                m31!(1916144466),
+                m31!(1178399334),
+                m31!(1894043274),
+                m31!(1309844216),
+            ]
+        );
+        assert_eq!(
+            Poseidon2M31MerkleHasher::hash_node(&[left, right], &values).0,
+            [
+                m31!(1793938305),
+                m31!(980776966),
+                m31!(322603066),
+                m31!(77085086),
+                m31!(1170240352),
+                m31!(1467227627),
+                m31!(1258205363),
+                m31!(238815009),
+            ]
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::iter::Peekable;
+use core::ops::RangeInclusive;
+
+use itertools::Itertools;
+
+/// Fetches the next node that needs to be decommited in the current Merkle layer, where each node
+/// has `2^log_arity` children in the previous layer.
+pub fn next_decommitment_node(
+    prev_queries: &mut Peekable<impl Iterator<Item = usize>>,
+    layer_queries: &mut Peekable<impl Iterator<Item = usize>>,
+    log_arity: u32,
+) -> Option<usize> {
+    prev_queries
+        .peek()
+        .map(|q| *q >> log_arity)
+        .into_iter()
+        .chain(layer_queries.peek().into_iter().copied())
+        .min()
+}
+
+/// Maps queries to rows of the columns hashed in a Merkle layer of log size `layer_log_size`,
+/// whose log sizes are `column_log_sizes`, to the sorted nodes of the layer that hash those rows.
+pub fn layer_node_queries(
+    queries_per_log_size: &BTreeMap<u32, Vec<usize>>,
+    column_log_sizes: RangeInclusive<u32>,
+    layer_log_size: u32,
+) -> Vec<usize> {
+    queries_per_log_size
+        .range(column_log_sizes)
+        .flat_map(|(log_size, queries)| {
+            queries
+                .iter()
+                .map(move |query| query >> (log_size - layer_log_size))
+        })
+        .sorted_unstable()
+        .dedup()
+        .collect()
+}
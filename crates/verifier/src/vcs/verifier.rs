@@ -0,0 +1,502 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::{Ordering, Reverse};
+use core::fmt;
+use core::iter::zip;
+use core::ops::RangeInclusive;
+
+use educe::Educe;
+use itertools::Itertools;
+
+use super::ops::MerkleHasher;
+use super::utils::{layer_node_queries, next_decommitment_node};
+use crate::fields::m31::BaseField;
+use crate::utils::PeekableExt;
+use crate::ColumnVec;
+
+// TODO(spapini): This struct is not necessary. Make it a function on decommitment?
+pub struct MerkleVerifier<H: MerkleHasher> {
+    /// The nodes of the top layer of the tree, see [MerkleConfig]. A single root by default.
+    pub cap: Vec<H::Hash>,
+    pub column_log_sizes: Vec<u32>,
+    pub config: MerkleConfig,
+}
+impl<H: MerkleHasher> MerkleVerifier<H> {
+    /// Creates a verifier of a binary tree with one row per leaf, see [MerkleConfig::default].
+    pub fn new(root: H::Hash, column_log_sizes: Vec<u32>) -> Self {
+        Self::from_cap(vec![root], column_log_sizes)
+    }
+
+    /// Creates a verifier of a tree committed to `cap`. The log size of the cap must be set in
+    /// the config, see [Self::with_config].
+    pub fn from_cap(cap: Vec<H::Hash>, column_log_sizes: Vec<u32>) -> Self {
+        Self {
+            cap,
+            column_log_sizes,
+            config: MerkleConfig::default(),
+        }
+    }
+
+    /// Sets the shape of the tree, which must be the one the prover committed with.
+    pub fn with_config(self, config: MerkleConfig) -> Self {
+        Self { config, ..self }
+    }
+
+    /// Verifies the decommitment of the columns.
+    ///
+    /// # Arguments
+    ///
+    /// * `queries_per_log_size` - A map from log_size to a vector of queries for columns of that
+    ///  log_size.
+    /// * `queried_values` - A vector of vectors of queried values. For each column, there is a
+    /// vector of queried values to that column.
+    /// * `decommitment` - The decommitment object containing the witness and column values.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the following conditions are met:
+    ///
+    /// * The witness is too long (not fully consumed).
+    /// * The witness is too short (missing values).
+    /// * The column values are too long (not fully consumed).
+    /// * The column values are too short (missing values).
+    /// * The cap doesn't have a node for each node of the top layer of the tree.
+    /// * The computed nodes of the top layer don't match the cap.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the `values` vector is not sorted in descending order based on
+    /// the `log_size` of the columns.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` if the decommitment is successfully verified.
+    pub fn verify(
+        &self,
+        queries_per_log_size: BTreeMap<u32, Vec<usize>>,
+        queried_values: ColumnVec<Vec<BaseField>>,
+        decommitment: MerkleDecommitment<H>,
+    ) -> Result<(), MerkleVerificationError> {
+        if self.column_log_sizes.is_empty() {
+            return self.verify_empty(queried_values, decommitment);
+        }
+        let max_log_size = self.column_log_sizes.iter().max().copied().unwrap_or(0);
+        let layers = self.config.layers(&self.column_log_sizes);
+        let (cap_log_size, _) = layers.last().unwrap();
+        if self.cap.len() != 1 << cap_log_size {
+            return Err(MerkleVerificationError::CapSizeMismatch);
+        }
+
+        // Prepare read buffers.
+        let mut queried_values_by_layer = self
+            .column_log_sizes
+            .iter()
+            .copied()
+            .zip(
+                queried_values
+                    .into_iter()
+                    .map(|column_values| column_values.into_iter()),
+            )
+            .sorted_by_key(|(log_size, _)| Reverse(*log_size))
+            .peekable();
+        let mut hash_witness = decommitment.hash_witness.into_iter();
+        let mut column_witness = decommitment.column_witness.into_iter();
+
+        let mut last_layer_hashes: Option<Vec<(usize, H::Hash)>> = None;
+        let mut last_layer_log_size = max_log_size;
+        for (layer_log_size, layer_column_log_sizes) in layers {
+            // Prepare read buffer for queried values to the current layer, along with the rows
+            // they were queried at.
+            let mut layer_queried_values = queried_values_by_layer
+                .peek_take_while(|(log_size, _)| layer_column_log_sizes.contains(log_size))
+                .map(|(log_size, column_values)| {
+                    let column_queries = queries_per_log_size
+                        .get(&log_size)
+                        .map_or(&[][..], |queries| queries);
+                    (log_size, column_values, column_queries)
+                })
+                .collect_vec();
+
+            // Prepare write buffer for queries to the current layer. This will propagate to the
+            // next layer.
+            let mut layer_total_queries = vec![];
+
+            // Each node hashes `2^log_arity` consecutive nodes of the previous layer.
+            let log_arity = match last_layer_hashes {
+                Some(_) => last_layer_log_size - layer_log_size,
+                None => 0,
+            };
+
+            // Queries to this layer come from queried node in the previous layer and queried
+            // columns in this one.
+            let mut prev_layer_queries = last_layer_hashes
+                .iter()
+                .flatten()
+                .map(|(q, _)| *q)
+                .collect_vec()
+                .into_iter()
+                .peekable();
+            let mut prev_layer_hashes = last_layer_hashes.as_ref().map(|x| x.iter().peekable());
+            let mut layer_column_queries = layer_node_queries(
+                &queries_per_log_size,
+                layer_column_log_sizes,
+                layer_log_size,
+            )
+            .into_iter()
+            .peekable();
+
+            // Merge previous layer queries and column queries.
+            while let Some(node_index) = next_decommitment_node(
+                &mut prev_layer_queries,
+                &mut layer_column_queries,
+                log_arity,
+            ) {
+                prev_layer_queries
+                    .peek_take_while(|q| q >> log_arity == node_index)
+                    .for_each(drop);
+
+                let node_hashes = prev_layer_hashes
+                    .as_mut()
+                    .map(|prev_layer_hashes| {
+                        // If a child was not computed, read it from the witness.
+                        (node_index << log_arity..(node_index + 1) << log_arity)
+                            .map(|child_index| {
+                                prev_layer_hashes
+                                    .next_if(|(index, _)| *index == child_index)
+                                    .map(|(_, hash)| Ok(*hash))
+                                    .unwrap_or_else(|| {
+                                        hash_witness
+                                            .next()
+                                            .ok_or(MerkleVerificationError::WitnessTooShort)
+                                    })
+                            })
+                            .collect::<Result<Vec<_>, _>>()
+                    })
+                    .transpose()?
+                    .unwrap_or_default();
+
+                // If a row was queried, read its column values from `queried_values`. Otherwise,
+                // read them from the witness.
+                layer_column_queries.next_if_eq(&node_index);
+                let mut node_values = vec![];
+                for (log_size, column_values, column_queries) in &mut layer_queried_values {
+                    let log_node_rows = *log_size - layer_log_size;
+                    for row in node_index << log_node_rows..(node_index + 1) << log_node_rows {
+                        node_values.push(if column_queries.binary_search(&row).is_ok() {
+                            column_values
+                                .next()
+                                .ok_or(MerkleVerificationError::ColumnValuesTooShort)?
+                        } else {
+                            column_witness
+                                .next()
+                                .ok_or(MerkleVerificationError::WitnessTooShort)?
+                        });
+                    }
+                }
+
+                layer_total_queries.push((node_index, H::hash_node(&node_hashes, &node_values)));
+            }
+
+            if !layer_queried_values
+                .iter()
+                .all(|(_, c, _)| c.as_slice().is_empty())
+            {
+                return Err(MerkleVerificationError::ColumnValuesTooLong);
+            }
+            last_layer_hashes = Some(layer_total_queries);
+            last_layer_log_size = layer_log_size;
+        }
+
+        // Check that all witnesses and values have been consumed.
+        if !hash_witness.as_slice().is_empty() {
+            return Err(MerkleVerificationError::WitnessTooLong);
+        }
+        if !column_witness.as_slice().is_empty() {
+            return Err(MerkleVerificationError::WitnessTooLong);
+        }
+
+        for (index, computed_hash) in last_layer_hashes.unwrap() {
+            if computed_hash != self.cap[index] {
+                return Err(MerkleVerificationError::RootMismatch);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifies an opening of `rows` of each column, produced by `MerkleProver::open`.
+    ///
+    /// Returns the opened values of each column, in the order of its `rows`. Rows may be given in
+    /// any order, and may repeat.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `rows` doesn't fit the committed columns, if `opening` doesn't have
+    /// exactly one value per opened row of each column, or if [Self::verify] rejects it.
+    pub fn verify_opening(
+        &self,
+        rows: &ColumnVec<Vec<usize>>,
+        opening: MerkleOpening<H>,
+    ) -> Result<ColumnVec<Vec<BaseField>>, MerkleVerificationError> {
+        let queries_per_log_size = queries_per_log_size(&self.column_log_sizes, rows)?;
+        match opening
+            .queried_values
+            .len()
+            .cmp(&self.column_log_sizes.len())
+        {
+            Ordering::Less => return Err(MerkleVerificationError::ColumnValuesTooShort),
+            Ordering::Greater => return Err(MerkleVerificationError::ColumnValuesTooLong),
+            Ordering::Equal => {}
+        }
+
+        let mut opened_values = Vec::with_capacity(rows.len());
+        for ((log_size, column_rows), column_values) in
+            zip(zip(&self.column_log_sizes, rows), &opening.queried_values)
+        {
+            let queries = queries_per_log_size
+                .get(log_size)
+                .map_or(&[][..], |queries| queries);
+            match column_values.len().cmp(&queries.len()) {
+                Ordering::Less => return Err(MerkleVerificationError::ColumnValuesTooShort),
+                Ordering::Greater => return Err(MerkleVerificationError::ColumnValuesTooLong),
+                Ordering::Equal => {}
+            }
+            opened_values.push(
+                column_rows
+                    .iter()
+                    .map(|row| column_values[queries.binary_search(row).unwrap()])
+                    .collect_vec(),
+            );
+        }
+
+        // Opening no rows proves nothing, and has nothing to decommit.
+        if queries_per_log_size.is_empty() && !self.column_log_sizes.is_empty() {
+            let decommitment = opening.decommitment;
+            if !decommitment.hash_witness.is_empty() || !decommitment.column_witness.is_empty() {
+                return Err(MerkleVerificationError::WitnessTooLong);
+            }
+            return Ok(opened_values);
+        }
+
+        self.verify(
+            queries_per_log_size,
+            opening.queried_values,
+            opening.decommitment,
+        )?;
+        Ok(opened_values)
+    }
+
+    /// Verifies a commitment to no columns. Such a tree has a single node hashing no values, so
+    /// there is nothing to decommit.
+    fn verify_empty(
+        &self,
+        queried_values: ColumnVec<Vec<BaseField>>,
+        decommitment: MerkleDecommitment<H>,
+    ) -> Result<(), MerkleVerificationError> {
+        if !queried_values.is_empty() {
+            return Err(MerkleVerificationError::ColumnValuesTooLong);
+        }
+        if !decommitment.hash_witness.is_empty() || !decommitment.column_witness.is_empty() {
+            return Err(MerkleVerificationError::WitnessTooLong);
+        }
+        if self.cap != [H::hash_node(&[], &[])] {
+            return Err(MerkleVerificationError::RootMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// The shape of a Merkle tree.
+///
+/// The leaves hash `2^log_leaf_rows` consecutive rows of the largest columns, and each inner node
+/// hashes `2^log_arity` children, except in the topmost layer, which may have fewer children if
+/// the height of the tree isn't a multiple of `log_arity`. A column shorter than the leaves is
+/// hashed in the largest layer that is not longer than it, `2^(column_log_size - layer_log_size)`
+/// consecutive rows per node.
+///
+/// The tree may also commit to the `2^log_cap_size` nodes of a layer, its cap, instead of to a
+/// single root, which removes the layers above the cap from each authentication path. The cap is
+/// no larger than the leaves, nor than the smallest column, so that every column is hashed below
+/// it.
+///
+/// Higher arities and more rows per leaf make trees with fewer hashes, at the cost of larger
+/// decommitments of each query. The default is a binary tree with one row per leaf, and a single
+/// root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MerkleConfig {
+    pub log_arity: u32,
+    pub log_leaf_rows: u32,
+    pub log_cap_size: u32,
+}
+
+impl MerkleConfig {
+    const MIN_LOG_ARITY: u32 = 1;
+    const MAX_LOG_ARITY: u32 = 3;
+    pub const LOG_ARITY_RANGE: RangeInclusive<u32> = Self::MIN_LOG_ARITY..=Self::MAX_LOG_ARITY;
+
+    const MIN_LOG_LEAF_ROWS: u32 = 0;
+    const MAX_LOG_LEAF_ROWS: u32 = 4;
+    pub const LOG_LEAF_ROWS_RANGE: RangeInclusive<u32> =
+        Self::MIN_LOG_LEAF_ROWS..=Self::MAX_LOG_LEAF_ROWS;
+
+    const MIN_LOG_CAP_SIZE: u32 = 0;
+    const MAX_LOG_CAP_SIZE: u32 = 10;
+    pub const LOG_CAP_SIZE_RANGE: RangeInclusive<u32> =
+        Self::MIN_LOG_CAP_SIZE..=Self::MAX_LOG_CAP_SIZE;
+
+    /// Creates a new Merkle tree configuration, of a tree with a single root.
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// * `log_arity` is equal to zero or greater than 3.
+    /// * `log_leaf_rows` is greater than 4.
+    pub fn new(log_arity: u32, log_leaf_rows: u32) -> Self {
+        assert!(Self::LOG_ARITY_RANGE.contains(&log_arity));
+        assert!(Self::LOG_LEAF_ROWS_RANGE.contains(&log_leaf_rows));
+        Self {
+            log_arity,
+            log_leaf_rows,
+            log_cap_size: 0,
+        }
+    }
+
+    /// Sets the log size of the cap of the tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `log_cap_size` is greater than 10.
+    pub fn with_log_cap_size(self, log_cap_size: u32) -> Self {
+        assert!(Self::LOG_CAP_SIZE_RANGE.contains(&log_cap_size));
+        Self {
+            log_cap_size,
+            ..self
+        }
+    }
+
+    /// Returns the layers of a tree of columns of the given log sizes, from the leaves to the top
+    /// layer, which is the cap. Each layer is given by its log size, and the log sizes of the
+    /// columns hashed in it.
+    pub fn layers(&self, column_log_sizes: &[u32]) -> Vec<(u32, RangeInclusive<u32>)> {
+        let max_log_size = column_log_sizes.iter().max().copied().unwrap_or(0);
+        let min_log_size = column_log_sizes.iter().min().copied().unwrap_or(0);
+        let cap_log_size = self.log_cap_size.min(min_log_size);
+
+        let mut layers = vec![];
+        let mut layer_log_size = max_log_size.saturating_sub(self.log_leaf_rows);
+        let mut max_column_log_size = max_log_size;
+        loop {
+            layers.push((layer_log_size, layer_log_size..=max_column_log_size));
+            if layer_log_size <= cap_log_size {
+                return layers;
+            }
+            max_column_log_size = layer_log_size - 1;
+            layer_log_size = layer_log_size
+                .saturating_sub(self.log_arity)
+                .max(cap_log_size);
+        }
+    }
+}
+
+impl Default for MerkleConfig {
+    fn default() -> Self {
+        Self {
+            log_arity: 1,
+            log_leaf_rows: 0,
+            log_cap_size: 0,
+        }
+    }
+}
+
+#[derive(Debug, Educe)]
+#[educe(Clone)]
+pub struct MerkleDecommitment<H: MerkleHasher> {
+    /// Hash values that the verifier needs but cannot deduce from previous computations, in the
+    /// order they are needed.
+    pub hash_witness: Vec<H::Hash>,
+    /// Column values that the verifier needs but cannot deduce from previous computations, in the
+    /// order they are needed.
+    /// This complements the column values that were queried, including the rows that weren't
+    /// queried in nodes that hash several rows (see [MerkleConfig]). These must be supplied
+    /// directly to the verifier.
+    pub column_witness: Vec<BaseField>,
+}
+
+/// Returns the rows to decommit in each log size: the sorted union of the `rows` of all columns of
+/// that log size.
+///
+/// # Errors
+///
+/// Returns an error if `rows` doesn't have an entry for each column, or if a row is out of its
+/// column's bounds.
+pub fn queries_per_log_size(
+    column_log_sizes: &[u32],
+    rows: &ColumnVec<Vec<usize>>,
+) -> Result<BTreeMap<u32, Vec<usize>>, MerkleVerificationError> {
+    if rows.len() != column_log_sizes.len() {
+        return Err(MerkleVerificationError::NumColumnsMismatch);
+    }
+    let mut queries_per_log_size = BTreeMap::<u32, Vec<usize>>::new();
+    for (&log_size, column_rows) in zip(column_log_sizes, rows) {
+        if column_rows.iter().any(|&row| row >= 1 << log_size) {
+            return Err(MerkleVerificationError::RowOutOfBounds);
+        }
+        if !column_rows.is_empty() {
+            queries_per_log_size
+                .entry(log_size)
+                .or_default()
+                .extend(column_rows);
+        }
+    }
+    for queries in queries_per_log_size.values_mut() {
+        queries.sort_unstable();
+        queries.dedup();
+    }
+    Ok(queries_per_log_size)
+}
+
+/// A self-contained proof of the values of some rows of committed columns, which can be verified
+/// against the Merkle root alone. See [MerkleVerifier::verify_opening].
+#[derive(Debug, Educe)]
+#[educe(Clone)]
+pub struct MerkleOpening<H: MerkleHasher> {
+    /// For each column, its values at the decommitted rows of its log size (see
+    /// [queries_per_log_size]), in increasing row order. All the columns of a log size are hashed
+    /// together, so opening a row in one of them decommits it in all of them.
+    pub queried_values: ColumnVec<Vec<BaseField>>,
+    pub decommitment: MerkleDecommitment<H>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MerkleVerificationError {
+    WitnessTooShort,
+    WitnessTooLong,
+    ColumnValuesTooLong,
+    ColumnValuesTooShort,
+    RootMismatch,
+    /// The number of opened columns doesn't match the number of committed columns.
+    NumColumnsMismatch,
+    /// An opened row is out of its column's bounds.
+    RowOutOfBounds,
+    /// The cap doesn't have a node for each node of the top layer of the tree.
+    CapSizeMismatch,
+}
+
+impl fmt::Display for MerkleVerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::WitnessTooShort => "Witness is too short.",
+            Self::WitnessTooLong => "Witness is too long.",
+            Self::ColumnValuesTooLong => "Column values are too long.",
+            Self::ColumnValuesTooShort => "Column values are too short.",
+            Self::RootMismatch => "Root mismatch.",
+            Self::NumColumnsMismatch => "Number of columns mismatch.",
+            Self::RowOutOfBounds => "Row out of bounds.",
+            Self::CapSizeMismatch => "Cap size mismatch.",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MerkleVerificationError {}